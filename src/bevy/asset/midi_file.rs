@@ -3,8 +3,6 @@ Asset types
 
 TODO
 "#]
-#![allow(dead_code)]
-#![allow(unused_variables)]
 
 use bevy::{
     asset::{AssetLoader, LoadContext, io::Reader},
@@ -12,9 +10,10 @@ use bevy::{
 };
 
 use crate::{
+    bevy::asset::SoundFont,
     events::LiveEvent,
-    file::ParsedMidiFile as Mf,
-    prelude::{FormatType, Timed, Timing},
+    file::{ParsedMidiFile as Mf, SoundBankFormat},
+    prelude::{FormatType, TempoMap, Timed},
     reader::ReaderError,
 };
 
@@ -26,12 +25,25 @@ use crate::bevy::song::MidiSong;
 #[derive(Asset, TypePath)]
 pub struct MidiFile {
     inner: Mf<'static>,
+    sound_font: Option<Handle<SoundFont>>,
 }
 
 impl MidiFile {
     /// Create a new midifile with the given inner midix MidiFile
     pub fn new(file: Mf<'static>) -> Self {
-        Self { inner: file }
+        Self {
+            inner: file,
+            sound_font: None,
+        }
+    }
+
+    /// The sound font embedded in a RIFF MIDI (`.rmi`) file, if any.
+    ///
+    /// This is loaded as the labeled asset `"SoundFont"`.
+    /// Note that only SF2 banks are loaded. DLS banks are available via
+    /// [`Rmid::sound_banks`](crate::file::Rmid::sound_banks).
+    pub fn sound_font(&self) -> Option<&Handle<SoundFont>> {
+        self.sound_font.as_ref()
     }
 
     /// Get a reference to the inner midifile
@@ -51,86 +63,7 @@ impl MidiFile {
 
 impl<'a> From<Mf<'a>> for MidiSong {
     fn from(midi: Mf<'a>) -> Self {
-        let mut commands = Vec::new();
-        let tracks = midi.tracks();
-
-        // is Some if the tempo is set for the whole file.
-        // None if the format is sequentially independent
-        let file_tempo = match midi.format_type() {
-            FormatType::SequentiallyIndependent => None,
-            FormatType::Simultaneous | FormatType::SingleMultiChannel => {
-                let first_track = tracks.first().unwrap();
-                Some(first_track.info().tempo)
-            }
-        };
-
-        for track in tracks {
-            let track_tempo = file_tempo.unwrap_or(track.info().tempo);
-            let micros_per_quarter_note = track_tempo.micros_per_quarter_note();
-
-            let (micros_per_tick, offset_in_micros) = match midi.header().timing() {
-                Timing::Smpte(v) => {
-                    //µs_per_tick = 1 000 000 / (fps × ticks_per_frame)
-                    //FPS is −24/−25/−29/−30 in the high byte of division;
-                    // ticks per frame is the low byte.
-
-                    let frames_per_second = v.fps().as_division() as u32;
-                    let ticks_per_frame = v.ticks_per_frame() as u32;
-                    let ticks_per_second = frames_per_second * ticks_per_frame;
-                    let micros_per_tick = 1_000_000. / ticks_per_second as f64;
-
-                    //NOTE: if the file header uses smpte, that overrides any track smpte offset.
-                    let offset_in_micros = track
-                        .info()
-                        .smpte_offset
-                        .as_ref()
-                        .map(|offset| {
-                            if offset.fps != v.fps() {
-                                warn!(
-                                    "Header's fps({}) does not align with track's fps({}). \
-                                    The file's fps will override the track's!",
-                                    v.fps().as_f64(),
-                                    offset.fps.as_f64()
-                                );
-                            }
-                            offset.as_micros_with_override(v.fps())
-                        })
-                        .unwrap_or(0.);
-
-                    (micros_per_tick, offset_in_micros)
-                }
-                Timing::TicksPerQuarterNote(tpqn) => {
-                    // µs_per_tick = tempo_meta / TPQN
-                    // micro_seconds/quarternote * quarternote_per_tick (1/ticks per qn)
-                    let micros_per_tick =
-                        micros_per_quarter_note as f64 / tpqn.ticks_per_quarter_note() as f64;
-
-                    let offset_in_micros = track
-                        .info()
-                        .smpte_offset
-                        .as_ref()
-                        .map(|offset| offset.as_micros())
-                        .unwrap_or(0.);
-
-                    (micros_per_tick, offset_in_micros)
-                }
-            };
-
-            for event in track.events() {
-                match event.event() {
-                    LiveEvent::ChannelVoice(cv) => {
-                        let tick = event.accumulated_ticks();
-                        let micros = micros_per_tick * tick as f64;
-
-                        commands.push(Timed::new(micros as u64, *cv));
-                    }
-                    _ => {
-                        //idk
-                    }
-                }
-            }
-        }
-        MidiSong::new(commands)
+        (&midi).into()
     }
 }
 
@@ -138,78 +71,31 @@ impl<'a> From<&Mf<'a>> for MidiSong {
     fn from(midi: &Mf<'a>) -> Self {
         let mut commands = Vec::new();
         let tracks = midi.tracks();
+        let timing = *midi.header().timing();
 
-        let ticks_per_qn = midi.header().timing().ticks_per_quarter_note().unwrap();
-
-        // is Some if the tempo is set for the whole file.
+        // is Some if the tempo map is shared by the whole file.
         // None if the format is sequentially independent
-        let file_tempo = match midi.format_type() {
+        let file_tempo_map = match midi.format_type() {
             FormatType::SequentiallyIndependent => None,
-            FormatType::Simultaneous | FormatType::SingleMultiChannel => {
-                let first_track = tracks.first().unwrap();
-                Some(first_track.info().tempo)
-            }
+            FormatType::Simultaneous | FormatType::SingleMultiChannel => Some(midi.tempo_map()),
         };
 
         for track in tracks {
-            let track_tempo = file_tempo.unwrap_or(track.info().tempo);
-            let micros_per_quarter_note = track_tempo.micros_per_quarter_note();
-
-            let (micros_per_tick, offset_in_micros) = match midi.header().timing() {
-                Timing::Smpte(v) => {
-                    //µs_per_tick = 1 000 000 / (fps × ticks_per_frame)
-                    //FPS is −24/−25/−29/−30 in the high byte of division;
-                    // ticks per frame is the low byte.
-
-                    let frames_per_second = v.fps().as_division() as u32;
-                    let ticks_per_frame = v.ticks_per_frame() as u32;
-                    let ticks_per_second = frames_per_second * ticks_per_frame;
-                    let micros_per_tick = 1_000_000. / ticks_per_second as f64;
-
-                    //NOTE: if the file header uses smpte, that overrides any track smpte offset.
-                    let offset_in_micros = track
-                        .info()
-                        .smpte_offset
-                        .as_ref()
-                        .map(|offset| {
-                            if offset.fps != v.fps() {
-                                warn!(
-                                    "Header's fps({}) does not align with track's fps({}). \
-                                    The file's fps will override the track's!",
-                                    v.fps().as_f64(),
-                                    offset.fps.as_f64()
-                                );
-                            }
-                            offset.as_micros_with_override(v.fps())
-                        })
-                        .unwrap_or(0.);
-
-                    (micros_per_tick, offset_in_micros)
-                }
-                Timing::TicksPerQuarterNote(tpqn) => {
-                    // µs_per_tick = tempo_meta / TPQN
-                    // micro_seconds/quarternote * quarternote_per_tick (1/ticks per qn)
-                    let micros_per_tick =
-                        micros_per_quarter_note as f64 / tpqn.ticks_per_quarter_note() as f64;
-
-                    let offset_in_micros = track
-                        .info()
-                        .smpte_offset
-                        .as_ref()
-                        .map(|offset| offset.as_micros())
-                        .unwrap_or(0.);
-
-                    (micros_per_tick, offset_in_micros)
+            let track_tempo_map;
+            let tempo_map = match &file_tempo_map {
+                Some(map) => map,
+                None => {
+                    track_tempo_map = TempoMap::from_tracks(timing, [track]);
+                    &track_tempo_map
                 }
             };
 
             for event in track.events() {
                 match event.event() {
                     LiveEvent::ChannelVoice(cv) => {
-                        let tick = event.accumulated_ticks();
-                        let micros = micros_per_tick * tick as f64;
+                        let micros = tempo_map.ticks_to_micros(event.accumulated_ticks());
 
-                        commands.push(Timed::new(micros as u64, *cv));
+                        commands.push(Timed::new(micros, *cv));
                     }
                     _ => {
                         //idk
@@ -233,19 +119,31 @@ impl AssetLoader for MidiFileLoader {
         &self,
         reader: &mut dyn Reader,
         _settings: &(),
-        _load_context: &mut LoadContext<'_>,
+        load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
-        let mut bytes = Vec::new();
-        reader.read_to_end(&mut bytes).await.unwrap();
-
-        let inner = Mf::parse(bytes)?;
+        let inner = Mf::parse_async_reader(reader).await?;
+
+        let sound_font = inner
+            .rmid()
+            .into_iter()
+            .flat_map(|rmid| rmid.sound_banks())
+            .filter(|bank| bank.format() == SoundBankFormat::Sf2)
+            .find_map(|bank| match SoundFont::try_new(bank.data()) {
+                Ok(sound_font) => Some(sound_font),
+                Err(e) => {
+                    warn!("Could not read the sound font embedded in a midi file: {e}");
+                    None
+                }
+            })
+            .map(|sound_font| load_context.add_labeled_asset("SoundFont".into(), sound_font));
 
-        let res = MidiFile::new(inner);
+        let mut res = MidiFile::new(inner);
+        res.sound_font = sound_font;
 
         Ok(res)
     }
 
     fn extensions(&self) -> &[&str] {
-        &["mid"]
+        &["mid", "rmi"]
     }
 }
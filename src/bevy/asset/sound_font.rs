@@ -8,11 +8,11 @@ use alloc::sync::Arc;
 use bevy_platform::prelude::*;
 use thiserror::Error;
 
+use crate::synth::SoundFont as Sf;
 use bevy::{
     asset::{AssetLoader, LoadContext, io::Reader},
     prelude::*,
 };
-use rustysynth::SoundFont as Sf;
 
 /// Sound font asset
 #[derive(Asset, TypePath)]
@@ -27,6 +27,13 @@ impl SoundFont {
 
         Self { file: Arc::new(sf) }
     }
+
+    /// Try to read a sound font from the bytes of an SF2 file
+    pub(crate) fn try_new(mut file: &[u8]) -> Result<Self, crate::synth::SoundFontError> {
+        let sf = Sf::new(&mut file)?;
+
+        Ok(Self { file: Arc::new(sf) })
+    }
 }
 /// Possible errors that can be produced by [`CustomAssetLoader`]
 #[derive(Debug, Error)]
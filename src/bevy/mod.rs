@@ -1,6 +1,6 @@
 #![doc = r#"
 Bevy plugin that uses [`midix`](https://crates.io/crates/midix),
-[`midir`](https://github.com/Boddlnagg/midir), and a [`rustysynth`](https://github.com/sinshu/rustysynth) fork to play midi sounds!
+[`midir`](https://github.com/Boddlnagg/midir), and [`midix_synth`](https://crates.io/crates/midix_synth), a [`rustysynth`](https://github.com/sinshu/rustysynth) fork, to play midi sounds!
 
 Read from MIDI devices, MIDI files, and programmable input, and output to user audio with a soundfont!
 
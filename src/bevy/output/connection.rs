@@ -1,4 +1,4 @@
-use crate::MidiMessageBytes;
+use crate::{MidiMessageBytes, events::ToLiveEventBytes};
 use bevy::prelude::*;
 use midir::{MidiOutputPort, SendError};
 
@@ -20,8 +20,9 @@ impl MidiOutputConnection {
     }
     pub fn send(&mut self, message: impl Into<MidiMessageBytes>) -> Result<(), SendError> {
         let mut buf = [0; 3];
-        let mut message: MidiMessageBytes = message.into();
-        let wrote = message.write_into(&mut buf);
+        let message: MidiMessageBytes = message.into();
+        // a MidiMessageBytes is at most 3 bytes
+        let wrote = message.write_into(&mut buf).unwrap();
         self.conn.send(&buf[..wrote])?;
         Ok(())
     }
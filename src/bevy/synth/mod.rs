@@ -3,7 +3,7 @@ Synthesizer resources, setup and plugins
 "#]
 
 use crate::bevy::song::{SongId, SongWriter};
-use crate::prelude::{ChannelVoiceMessage, Timed};
+use crate::prelude::{ChannelState, ChannelVoiceMessage, Timed};
 use bevy::prelude::*;
 use bevy_platform::collections::HashMap;
 use bevy_platform::sync::Mutex;
@@ -138,10 +138,18 @@ impl Synth {
         Ok(())
     }
 
-    /// Play a song.
-    ///
-    /// TODO: only plays from beginning
+    /// Play a song from the beginning.
     pub fn play(&self, id: SongId) -> Result<(), SynthError> {
+        self.play_from(id, 0)
+    }
+
+    /// Play a song, starting `from` microseconds in.
+    ///
+    /// The programs, controllers and pitch bends set before that point are sent first,
+    /// so instruments sound as they would have. See [`ChannelState`].
+    ///
+    /// Looped songs repeat from `from`.
+    pub fn play_from(&self, id: SongId, from: u64) -> Result<(), SynthError> {
         let Some(song) = self.store.get(&id) else {
             return Err(SynthError::SongNotFound(id));
         };
@@ -149,11 +157,33 @@ impl Synth {
             error!("An event was passed to the synth, but the soundfont has not been loaded!");
             return Err(SynthError::NotReady);
         };
-        sink_channel.send(SinkCommand::NewSong {
+
+        let mut events = song.events.clone();
+        events.sort_by_key(|event| event.timestamp);
+
+        let mut state = ChannelState::new();
+        let mut commands = Vec::with_capacity(events.len());
+        for event in events {
+            if event.timestamp < from {
+                state.apply(&event.event);
+            } else {
+                commands.push(Timed::new(event.timestamp - from, event.event));
+            }
+        }
+        commands.splice(
+            0..0,
+            state
+                .messages()
+                .into_iter()
+                .map(|message| Timed::new(0, message)),
+        );
+
+        sink_channel.send(SinkCommand::NewSong(NewSong::new(
             id,
-            looped: song.looped,
-            commands: song.events.clone(),
-        })?;
+            song.looped,
+            commands,
+            self.params.sample_rate as u64,
+        )))?;
 
         Ok(())
     }
@@ -195,11 +225,12 @@ impl Synth {
         );
 
         if !song.paused() {
-            sink_channel.send(SinkCommand::NewSong {
+            sink_channel.send(SinkCommand::NewSong(NewSong::new(
                 id,
                 looped,
-                commands: song.events().collect(),
-            })?;
+                song.events().collect(),
+                self.params.sample_rate as u64,
+            )))?;
         }
 
         Ok(id)
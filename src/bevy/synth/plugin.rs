@@ -7,9 +7,9 @@ use crossbeam_channel::{Receiver, Sender, TryIter};
 use bevy_platform::sync::Mutex;
 
 #[cfg(feature = "std")]
-use itertools::Itertools;
+use crate::synth::{Synthesizer, SynthesizerExt, SynthesizerSettings};
 #[cfg(feature = "std")]
-use rustysynth::{Synthesizer, SynthesizerSettings};
+use itertools::Itertools;
 #[cfg(feature = "std")]
 use tinyaudio::{OutputDeviceParameters, run_output_device};
 
@@ -143,8 +143,6 @@ fn load_audio_font(
     mut synth: ResMut<Synth>,
     assets: Res<Assets<SoundFont>>,
 ) {
-    use bevy::tasks::IoTaskPool;
-
     let SynthState::LoadHandle { sound_font } = &synth.synthesizer else {
         warn!(
             "loading the audio font is out of sync. This is an issue with bevy_midix. Please file an issue!"
@@ -160,7 +158,8 @@ fn load_audio_font(
     let mut synth_settings = SynthesizerSettings::new(synth.params.sample_rate as i32);
     synth_settings.enable_reverb_and_chorus = synth.params.enable_reverb_and_chorus;
 
-    let mut synthesizer = Synthesizer::new(&sound_font.file, &synth_settings).unwrap();
+    let mut synthesizer =
+        Synthesizer::new(sound_font.file.as_ref().clone(), &synth_settings).unwrap();
 
     let mut left = vec![0f32; synth.params.channel_sample_count];
     let mut right = vec![0f32; synth.params.channel_sample_count];
@@ -171,54 +170,46 @@ fn load_audio_font(
         channel_sample_count: synth.params.channel_sample_count,
     };
 
-    let _device = if let Some(mut receiver) = receiver {
-        let send = receiver.take_sender();
-        run_output_device(output_device_params, {
-            move |data| {
-                for command in synth_receiver.try_iter() {
-                    // I am uneasy about this.
-                    send.try_send(SynthEvent {
-                        received: Instant::now(),
-                        message: command,
-                    })
-                    .unwrap();
-                    let data1 = command.data_1_byte() as i32;
-                    let data2 = command.data_2_byte().unwrap_or(0) as i32;
-                    let channel = (command.status() & 0b0000_1111) as i32;
-                    let command = (command.status() & 0b1111_0000) as i32;
-                    synthesizer.process_midi_message(channel, command, data1, data2);
-                }
-                synthesizer.render(&mut left[..], &mut right[..]);
-                for (i, value) in left.iter().interleave(right.iter()).enumerate() {
-                    data[i] = *value;
-                }
-            }
-        })
-    } else {
-        run_output_device(output_device_params, {
-            move |data| {
-                for command in synth_receiver.try_iter() {
-                    let data1 = command.data_1_byte() as i32;
-                    let data2 = command.data_2_byte().unwrap_or(0) as i32;
-                    let channel = (command.status() & 0b0000_1111) as i32;
-                    let command = (command.status() & 0b1111_0000) as i32;
-                    synthesizer.process_midi_message(channel, command, data1, data2);
-                }
-                synthesizer.render(&mut left[..], &mut right[..]);
-                for (i, value) in left.iter().interleave(right.iter()).enumerate() {
-                    data[i] = *value;
-                }
+    let (sink_sender, sink_receiver) = crossbeam_channel::unbounded();
+    let mut sink = SinkQueue::new(synth.params.sample_rate as u64, sink_receiver);
+    let send = receiver.map(|mut receiver| receiver.take_sender());
+
+    // sends the message to the synth event receiver, if there is one
+    let notify = move |command: ChannelVoiceMessage| {
+        if let Some(send) = &send {
+            // I am uneasy about this.
+            send.try_send(SynthEvent {
+                received: Instant::now(),
+                message: command,
+            })
+            .unwrap();
+        }
+    };
+
+    let _device = run_output_device(output_device_params, {
+        move |data| {
+            sink.receive();
+            for command in synth_receiver.try_iter() {
+                notify(command);
+                synthesizer.handle_voice(&command);
             }
-        })
-    }
-    .unwrap();
 
-    let (sink_sender, sink_receiver) = crossbeam_channel::unbounded();
+            // The synthesizer applies each timed message at its frame. It renders a block
+            // ahead, so messages are scheduled a block early to never land in a rendered one.
+            let lookahead = left.len() + synthesizer.get_block_size();
+            for &(offset, command) in sink.due(lookahead) {
+                notify(command);
+                synthesizer.schedule_voice(offset, &command);
+            }
+            synthesizer.render(&mut left[..], &mut right[..]);
+            sink.advance(left.len());
 
-    let thread_pool = IoTaskPool::get();
-    thread_pool
-        .spawn(SinkTask::new(synth_sender.clone(), sink_receiver))
-        .detach();
+            for (i, value) in left.iter().interleave(right.iter()).enumerate() {
+                data[i] = *value;
+            }
+        }
+    })
+    .unwrap();
 
     synth.synthesizer = SynthState::Loaded {
         synth_channel: synth_sender,
@@ -3,17 +3,14 @@ use bevy_platform::prelude::*;
 
 use crate::bevy::song::SongId;
 
+use super::micros_to_frames;
+
 /// Command the sink to do something
 pub(crate) enum SinkCommand {
     /// Play an event in time x
     PlayEvent(Timed<ChannelVoiceMessage>),
     /// Play a new song
-    NewSong {
-        id: SongId,
-        looped: bool,
-        /// The associated events with the song
-        commands: Vec<Timed<ChannelVoiceMessage>>,
-    },
+    NewSong(NewSong),
     /// Stop a song
     Stop {
         song_id: Option<SongId>,
@@ -21,8 +18,40 @@ pub(crate) enum SinkCommand {
     },
 }
 
+/// A song, sorted and measured before it's sent to the audio callback
+pub(crate) struct NewSong {
+    pub(crate) id: SongId,
+    /// The associated events with the song, sorted by their time
+    pub(crate) commands: Vec<Timed<ChannelVoiceMessage>>,
+    /// The frames between repeats, if the song loops
+    pub(crate) loop_length: Option<u64>,
+}
+
+impl NewSong {
+    pub(crate) fn new(
+        id: SongId,
+        looped: bool,
+        mut commands: Vec<Timed<ChannelVoiceMessage>>,
+        sample_rate: u64,
+    ) -> Self {
+        commands.sort_by_key(|m| m.timestamp);
+
+        // a song with all of its events at once has nothing to loop
+        let length = micros_to_frames(
+            commands.last().map(|e| e.timestamp).unwrap_or(0),
+            sample_rate,
+        );
+        Self {
+            id,
+            commands,
+            loop_length: (looped && length > 0).then_some(length),
+        }
+    }
+}
+
 pub(crate) struct InnerCommand {
-    pub(crate) time_to_send: u64,
+    /// The frame of the audio callback's output the command is played at
+    pub(crate) frame: u64,
     pub(crate) parent: Option<SongId>,
     pub(crate) command: ChannelVoiceMessage,
 }
@@ -1,11 +1,11 @@
 /*
 
-This Sink will send events to another thread that will constantly poll/flush command out to the synth.
+The audio callback holds the sink queue, and plays each command at the frame it's due at.
 */
 
 mod commands;
 pub(crate) use commands::*;
 
-mod task;
+mod queue;
 #[allow(unused_imports)]
-pub(crate) use task::*;
+pub(crate) use queue::*;
@@ -0,0 +1,272 @@
+use alloc::collections::vec_deque::VecDeque;
+use bevy_platform::prelude::*;
+
+use crossbeam_channel::Receiver;
+
+use crate::prelude::*;
+
+use crate::bevy::song::SongId;
+
+use super::{NewSong, SinkCommand, commands::InnerCommand};
+
+/// Commands waiting for their frame, sorted by it
+#[derive(Default)]
+struct CommandQueue(VecDeque<InnerCommand>);
+
+impl CommandQueue {
+    /// Queue events that are already sorted, at their frames after `start`
+    fn queue_commands(
+        &mut self,
+        id: Option<SongId>,
+        events: impl IntoIterator<Item = Timed<ChannelVoiceMessage>>,
+        start: u64,
+        sample_rate: u64,
+    ) {
+        let events = events.into_iter();
+        // grow once for the events, rather than while inserting them
+        self.0.reserve(events.size_hint().0);
+        for message in events {
+            let frame = start + micros_to_frames(message.timestamp, sample_rate);
+            // messages at the same frame keep the order they were queued in
+            let index = self.0.partition_point(|command| command.frame <= frame);
+            self.0.insert(
+                index,
+                InnerCommand {
+                    frame,
+                    parent: id,
+                    command: message.event,
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn micros_to_frames(micros: u64, sample_rate: u64) -> u64 {
+    (micros as u128 * sample_rate as u128 / 1_000_000) as u64
+}
+
+/// Held by the audio callback, this determines the frame each message is played at.
+///
+/// Time is kept by counting the frames the callback renders, rather than by a clock
+/// on another thread, so the events of a song land on the frames they're due at.
+/// Commands are timed from the first frame of the callback that receives them.
+///
+/// Songs are sorted and measured before they're sent, so the callback doesn't copy them.
+/// It only allocates or frees memory as songs arrive and stop, never per event.
+pub(crate) struct SinkQueue {
+    sample_rate: u64,
+    /// The frames rendered so far
+    position: u64,
+    commands: Receiver<SinkCommand>,
+    queue: CommandQueue,
+    /// Stored songs that are looping
+    loops: Vec<LoopedSong>,
+    /// Returned by [`SinkQueue::due`], kept to not allocate on the audio thread
+    due: Vec<(usize, ChannelVoiceMessage)>,
+}
+
+struct LoopedSong {
+    id: SongId,
+    events: Vec<Timed<ChannelVoiceMessage>>,
+    /// The frame the next repeat starts at
+    next_repeat: u64,
+    /// In frames
+    length: u64,
+}
+
+impl SinkQueue {
+    pub(crate) fn new(sample_rate: u64, commands: Receiver<SinkCommand>) -> Self {
+        Self {
+            sample_rate,
+            position: 0,
+            commands,
+            queue: CommandQueue::default(),
+            loops: Vec::new(),
+            due: Vec::new(),
+        }
+    }
+
+    /// Receives the commands sent since the last callback
+    pub(crate) fn receive(&mut self) {
+        while let Ok(command) = self.commands.try_recv() {
+            match command {
+                SinkCommand::PlayEvent(event) => self.queue.queue_commands(
+                    None,
+                    core::iter::once(event),
+                    self.position,
+                    self.sample_rate,
+                ),
+                SinkCommand::NewSong(NewSong {
+                    id,
+                    commands,
+                    loop_length,
+                }) => {
+                    self.queue.queue_commands(
+                        Some(id),
+                        commands.iter().cloned(),
+                        self.position,
+                        self.sample_rate,
+                    );
+                    if let Some(length) = loop_length {
+                        self.loops.push(LoopedSong {
+                            id,
+                            events: commands,
+                            next_repeat: self.position + length,
+                            length,
+                        });
+                    }
+                }
+                SinkCommand::Stop {
+                    song_id,
+                    stop_voices,
+                } => {
+                    if let Some(song_id) = song_id {
+                        self.queue
+                            .0
+                            .retain(|command| command.parent.is_none_or(|id| id != song_id));
+                        self.loops.retain(|info| info.id != song_id);
+                    }
+                    if stop_voices {
+                        let events = Channel::all().into_iter().map(|channel| {
+                            Timed::new(
+                                0,
+                                ChannelVoiceMessage::new(
+                                    channel,
+                                    VoiceEvent::control_change(Controller::mute_all()),
+                                ),
+                            )
+                        });
+                        self.queue
+                            .queue_commands(None, events, self.position, self.sample_rate);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the messages due within the next `frames` frames, with their offset into them.
+    /// `frames` may look further ahead than the frames about to be rendered.
+    ///
+    /// Messages whose frame has passed are due at offset 0.
+    pub(crate) fn due(&mut self, frames: usize) -> &[(usize, ChannelVoiceMessage)] {
+        let end = self.position + frames as u64;
+
+        // queue any songs that repeat within these frames, as often as they do
+        for info in self.loops.iter_mut() {
+            while info.next_repeat < end {
+                self.queue.queue_commands(
+                    Some(info.id),
+                    info.events.iter().cloned(),
+                    info.next_repeat,
+                    self.sample_rate,
+                );
+                info.next_repeat += info.length;
+            }
+        }
+
+        self.due.clear();
+        while self.queue.0.front().is_some_and(|first| first.frame < end) {
+            let message = self.queue.0.pop_front().unwrap();
+            let offset = message.frame.saturating_sub(self.position) as usize;
+            self.due.push((offset, message.command));
+        }
+        &self.due
+    }
+
+    /// Moves the position past frames that have been rendered
+    pub(crate) fn advance(&mut self, frames: usize) {
+        self.position += frames as u64;
+    }
+}
+
+#[test]
+fn frames_of_a_song() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut sink = SinkQueue::new(44_100, receiver);
+    let note = |key| {
+        ChannelVoiceMessage::new(
+            Channel::One,
+            VoiceEvent::note_on(Key::from_databyte(key).unwrap(), Velocity::MAX),
+        )
+    };
+
+    // 10 ms, 20 ms and 30 ms in. The song loops every 30 ms.
+    let events = [10_000, 20_000, 30_000]
+        .into_iter()
+        .enumerate()
+        .map(|(i, micros)| Timed::new(micros, note(60 + i as u8)))
+        .collect();
+    sender
+        .send(SinkCommand::NewSong(NewSong::new(
+            SongId::default(),
+            true,
+            events,
+            44_100,
+        )))
+        .unwrap();
+
+    sink.receive();
+    let offsets = |due: &[(usize, ChannelVoiceMessage)]| {
+        due.iter().map(|(offset, _)| *offset).collect::<Vec<_>>()
+    };
+    assert_eq!(offsets(sink.due(441)), []);
+    sink.advance(441);
+    assert_eq!(offsets(sink.due(441)), [0]);
+    sink.advance(441);
+    assert_eq!(offsets(sink.due(882)), [0, 441]);
+    sink.advance(882);
+    // the repeat starts right after the last event
+    assert_eq!(offsets(sink.due(441)), [0]);
+    sink.advance(441);
+
+    sender
+        .send(SinkCommand::PlayEvent(Timed::new(0, note(72))))
+        .unwrap();
+    sink.receive();
+    // the second event of the repeat, then the event played now
+    let due = sink.due(441);
+    assert_eq!(offsets(due), [0, 0]);
+    assert_eq!(due[1].1, note(72));
+}
+
+#[test]
+fn short_loops() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut sink = SinkQueue::new(44_100, receiver);
+    let note = ChannelVoiceMessage::new(
+        Channel::One,
+        VoiceEvent::note_on(Key::from_databyte(60).unwrap(), Velocity::MAX),
+    );
+    let song = |id, micros: &[u64]| {
+        let commands = micros
+            .iter()
+            .map(|micros| Timed::new(*micros, note))
+            .collect();
+        SinkCommand::NewSong(NewSong::new(id, true, commands, 44_100))
+    };
+
+    // 0 and 5 ms in, repeating every 5 ms: several times a callback
+    let id = SongId::default();
+    sender.send(song(id, &[0, 5_000])).unwrap();
+    sink.receive();
+    let offsets = sink
+        .due(882)
+        .iter()
+        .map(|(offset, _)| *offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [0, 220, 220, 440, 440, 660, 660, 880, 880]);
+    sink.advance(882);
+
+    // a song with no length plays once
+    sender
+        .send(SinkCommand::Stop {
+            song_id: Some(id),
+            stop_voices: false,
+        })
+        .unwrap();
+    sender.send(song(SongId::default(), &[0])).unwrap();
+    sink.receive();
+    assert_eq!(sink.due(441).len(), 1);
+    sink.advance(441);
+    assert!(sink.due(441).is_empty());
+}
@@ -2,7 +2,7 @@ use core::fmt::{self, Debug};
 
 use alloc::borrow::Cow;
 
-use crate::ParseError;
+use crate::{events::sized_buf, prelude::*};
 
 #[doc = r#"
 There are only three types of midi message bytes:
@@ -34,48 +34,61 @@ pub enum MidiMessageBytes {
 }
 
 impl MidiMessageBytes {
-    /// Writes bytes into a buffer
-    pub fn write_into(&mut self, buf: &mut [u8]) -> usize {
-        use MidiMessageBytes::*;
+    /// Get the status byte of the message
+    pub const fn status(&self) -> StatusByte {
         match self {
-            Status(s) => {
-                let Some(byte) = buf.get_mut(0) else {
-                    return 0;
-                };
-                *byte = s.0;
-                1
-            }
-            Single(s, d) => {
-                let Some(byte) = buf.get_mut(0) else {
-                    return 0;
-                };
-                *byte = s.0;
-                let Some(byte) = buf.get_mut(1) else {
-                    return 1;
-                };
-                *byte = d.0;
-                2
-            }
-            Double(s, d1, d2) => {
-                let Some(byte) = buf.get_mut(0) else {
-                    return 0;
-                };
-                *byte = s.0;
-                let Some(byte) = buf.get_mut(1) else {
-                    return 1;
-                };
-                *byte = d1.0;
-
-                let Some(byte) = buf.get_mut(1) else {
-                    return 2;
-                };
-                *byte = d2.0;
-                3
-            }
+            Self::Status(s) | Self::Single(s, _) | Self::Double(s, _, _) => *s,
         }
     }
 }
 
+impl ToLiveEventBytes for MidiMessageBytes {
+    fn byte_len(&self) -> usize {
+        match self {
+            Self::Status(_) => 1,
+            Self::Single(..) => 2,
+            Self::Double(..) => 3,
+        }
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = sized_buf(buf, self.byte_len())?;
+        match self {
+            Self::Status(s) => buf[0] = s.0,
+            Self::Single(s, d) => buf.copy_from_slice(&[s.0, d.0]),
+            Self::Double(s, d1, d2) => buf.copy_from_slice(&[s.0, d1.0, d2.0]),
+        }
+        Ok(buf.len())
+    }
+}
+
+impl From<ChannelVoiceMessage> for MidiMessageBytes {
+    fn from(value: ChannelVoiceMessage) -> Self {
+        let status = StatusByte(value.status());
+        let data_1 = DataByte(value.data_1_byte());
+        match value.data_2_byte() {
+            Some(data_2) => Self::Double(status, data_1, DataByte(data_2)),
+            None => Self::Single(status, data_1),
+        }
+    }
+}
+
+impl From<ChannelModeMessage> for MidiMessageBytes {
+    fn from(value: ChannelModeMessage) -> Self {
+        let [controller, data] = value.mode.to_bytes();
+        Self::Double(
+            StatusByte(value.status()),
+            DataByte(controller),
+            DataByte(data),
+        )
+    }
+}
+
+impl From<SystemRealTimeMessage> for MidiMessageBytes {
+    fn from(value: SystemRealTimeMessage) -> Self {
+        Self::Status(StatusByte(value.byte()))
+    }
+}
+
 // impl Read for MidiMessageBytes {
 //     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
 //         use MidiMessageBytes::*;
@@ -11,6 +11,9 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 
 use crate::message::{ChannelVoiceMessage, VoiceEvent};
 
+mod state;
+pub use state::*;
+
 /// Identifies a channel for MIDI.
 ///
 /// To get this channel from a `u8`, use [`Channel::try_from_primitive`].
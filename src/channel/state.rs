@@ -0,0 +1,375 @@
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+
+/// Controllers that are reset by [`Controller::ResetAllControllers`], with their reset values
+const RESET_CONTROLLERS: [(u8, u8); 10] = [
+    (0x01, 0),
+    (0x21, 0),
+    (0x0B, 127),
+    (0x2B, 0),
+    (0x40, 0),
+    (0x41, 0),
+    (0x42, 0),
+    (0x43, 0),
+    (0x44, 0),
+    (0x45, 0),
+];
+
+/// The number of registered parameters that are tracked: pitch bend range, fine tuning and coarse tuning
+const TRACKED_RPNS: usize = 3;
+
+/// The parameter selected by the last (N)RPN select controllers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ParameterSelection {
+    registered: bool,
+    msb: u8,
+    lsb: u8,
+}
+
+impl ParameterSelection {
+    /// The null function (`0x7F7F`), which deselects any parameter
+    const NULL: Self = Self {
+        registered: true,
+        msb: 0x7F,
+        lsb: 0x7F,
+    };
+
+    /// The index of the tracked registered parameter that is selected
+    fn tracked_rpn(&self) -> Option<usize> {
+        (self.registered && self.msb == 0 && (self.lsb as usize) < TRACKED_RPNS)
+            .then_some(self.lsb as usize)
+    }
+
+    fn controllers(&self) -> [(u8, u8); 2] {
+        if self.registered {
+            [(0x65, self.msb), (0x64, self.lsb)]
+        } else {
+            [(0x63, self.msb), (0x62, self.lsb)]
+        }
+    }
+}
+
+#[doc = r#"
+The settings of a single channel, as tracked by [`ChannelState`].
+"#]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelSettings {
+    program: u8,
+    controllers: [u8; 128],
+    pitch_bend: PitchBend,
+    /// Coarse and fine data entry values of the tracked registered parameters
+    rpns: [[u8; 2]; TRACKED_RPNS],
+    selection: ParameterSelection,
+}
+
+impl Default for ChannelSettings {
+    fn default() -> Self {
+        let mut controllers = [0; 128];
+        controllers[0x07] = 100;
+        controllers[0x08] = 64;
+        controllers[0x0A] = 64;
+        controllers[0x0B] = 127;
+        controllers[0x5B] = 40;
+        Self {
+            program: 0,
+            controllers,
+            pitch_bend: PitchBend::new_unchecked(0x00, 0x40),
+            rpns: [[2, 0], [0x40, 0x00], [0x40, 0x00]],
+            selection: ParameterSelection::NULL,
+        }
+    }
+}
+
+impl ChannelSettings {
+    /// The current program
+    pub const fn program(&self) -> Program {
+        Program::new_unchecked(self.program)
+    }
+
+    /// The selected bank, combining the bank select MSB (`0x00`) and LSB (`0x20`)
+    pub const fn bank(&self) -> u16 {
+        ((self.controllers[0x00] as u16) << 7) | self.controllers[0x20] as u16
+    }
+
+    /// The last value of a controller, by its number
+    pub const fn controller(&self, controller: DataByte) -> DataByte {
+        DataByte::new_unchecked(self.controllers[controller.value() as usize])
+    }
+
+    /// The current pitch bend
+    pub const fn pitch_bend(&self) -> PitchBend {
+        self.pitch_bend
+    }
+
+    /// The pitch bend range, in semitones and cents
+    pub const fn pitch_bend_range(&self) -> (u8, u8) {
+        (self.rpns[0][0], self.rpns[0][1])
+    }
+
+    /// The fine tuning, as a 14-bit value where `0x2000` is A440
+    pub const fn fine_tuning(&self) -> u16 {
+        ((self.rpns[1][0] as u16) << 7) | self.rpns[1][1] as u16
+    }
+
+    /// The coarse tuning, in semitones where `0x40` is A440
+    pub const fn coarse_tuning(&self) -> u8 {
+        self.rpns[2][0]
+    }
+
+    /// Returns true if the hold (sustain) pedal is down
+    pub const fn hold_pedal(&self) -> bool {
+        self.controllers[0x40] >= 64
+    }
+
+    fn apply(&mut self, event: &VoiceEvent) {
+        match event {
+            VoiceEvent::ControlChange(controller) => {
+                let [number, value] = controller.to_bytes();
+                self.apply_controller(number, value);
+            }
+            VoiceEvent::ProgramChange { program } => self.program = program.byte(),
+            VoiceEvent::PitchBend(pitch_bend) => self.pitch_bend = *pitch_bend,
+            _ => {}
+        }
+    }
+
+    fn apply_controller(&mut self, number: u8, value: u8) {
+        match number {
+            0x06 | 0x26 => {
+                if let Some(rpn) = self.selection.tracked_rpn() {
+                    self.rpns[rpn][(number == 0x26) as usize] = value;
+                }
+            }
+            0x62 | 0x63 => {
+                self.selection.registered = false;
+                if number == 0x63 {
+                    self.selection.msb = value;
+                } else {
+                    self.selection.lsb = value;
+                }
+            }
+            0x64 | 0x65 => {
+                self.selection.registered = true;
+                if number == 0x65 {
+                    self.selection.msb = value;
+                } else {
+                    self.selection.lsb = value;
+                }
+            }
+            0x79 => {
+                for (number, value) in RESET_CONTROLLERS {
+                    self.controllers[number as usize] = value;
+                }
+                self.pitch_bend = Self::default().pitch_bend;
+                self.selection = ParameterSelection::NULL;
+            }
+            // data increment/decrement and channel mode messages don't hold state
+            0x60 | 0x61 | 0x78..=0x7F => {}
+            _ => self.controllers[number as usize] = value,
+        }
+    }
+
+    /// Pushes the controller and program changes that take a channel from `from` to `self`
+    fn diff(&self, from: &Self, messages: &mut Vec<(u8, u8)>, program: &mut Option<u8>) {
+        let bank_changed = self.bank() != from.bank();
+        if bank_changed {
+            messages.push((0x00, self.controllers[0x00]));
+            messages.push((0x20, self.controllers[0x20]));
+        }
+        if bank_changed || self.program != from.program {
+            *program = Some(self.program);
+        }
+
+        for number in 0x01..0x78 {
+            if matches!(number, 0x06 | 0x20 | 0x26 | 0x60..=0x65) {
+                continue;
+            }
+            let value = self.controllers[number as usize];
+            if value != from.controllers[number as usize] {
+                messages.push((number, value));
+            }
+        }
+
+        let mut selection = from.selection;
+        for (rpn, values) in self.rpns.iter().enumerate() {
+            if *values == from.rpns[rpn] {
+                continue;
+            }
+            selection = ParameterSelection {
+                registered: true,
+                msb: 0,
+                lsb: rpn as u8,
+            };
+            messages.extend(selection.controllers());
+            messages.push((0x06, values[0]));
+            messages.push((0x26, values[1]));
+        }
+        if selection != self.selection {
+            messages.extend(self.selection.controllers());
+        }
+    }
+}
+
+#[doc = r#"
+The settings of all 16 channels, reconstructed from the voice messages sent to them.
+
+This is used to "chase" the state of a song: when playback starts part-way through,
+the program changes, controllers, pitch bend and RPN settings that came earlier must be
+sent first, or instruments will sound wrong.
+
+Tracked state:
+- bank select and program
+- every controller value, including the hold pedal
+- pitch bend
+- the pitch bend range, fine tuning and coarse tuning registered parameters, and the
+  selected (N)RPN
+
+Notes and pressure are not tracked.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let bytes = include_bytes!("../../test-asset/Clementi.mid");
+let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+
+// the state of every channel at tick 1000
+let state = ChannelState::at_tick(&file, 1000);
+
+// the messages a freshly reset synthesizer needs to match it
+for message in state.messages() {
+    assert!(!message.is_note_on());
+}
+```
+"#]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelState {
+    channels: [ChannelSettings; 16],
+}
+
+impl Default for ChannelState {
+    fn default() -> Self {
+        Self {
+            channels: core::array::from_fn(|_| ChannelSettings::default()),
+        }
+    }
+}
+
+impl ChannelState {
+    /// The state of every channel after a reset
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replays every voice message of a file before `tick`
+    pub fn at_tick(file: &ParsedMidiFile<'_>, tick: u32) -> Self {
+        let mut events = file
+            .tracks()
+            .into_iter()
+            .flat_map(|track| track.events())
+            .filter(|event| event.accumulated_ticks() < tick)
+            .collect::<Vec<_>>();
+        // stable, so events on the same tick keep their track order
+        events.sort_by_key(|event| event.accumulated_ticks());
+
+        let mut state = Self::default();
+        for event in events {
+            if let LiveEvent::ChannelVoice(message) = event.event() {
+                state.apply(message);
+            }
+        }
+        state
+    }
+
+    /// Update the state with a message
+    pub fn apply(&mut self, message: &ChannelVoiceMessage) {
+        self.channels[message.channel().to_byte() as usize].apply(message.event());
+    }
+
+    /// The settings of a channel
+    pub fn channel(&self, channel: Channel) -> &ChannelSettings {
+        &self.channels[channel.to_byte() as usize]
+    }
+
+    /// The messages that take a freshly reset receiver to this state.
+    ///
+    /// Only settings that differ from their defaults are sent.
+    pub fn messages(&self) -> Vec<ChannelVoiceMessage> {
+        self.diff(&Self::default())
+    }
+
+    /// The minimal set of messages that take a receiver in the state `from` to this state.
+    ///
+    /// For each channel, bank selects come first, followed by the program change,
+    /// controllers, registered parameters, and pitch bend.
+    pub fn diff(&self, from: &ChannelState) -> Vec<ChannelVoiceMessage> {
+        let mut messages = Vec::new();
+        let mut controllers = Vec::new();
+        for (channel, (settings, from)) in Channel::all()
+            .into_iter()
+            .zip(self.channels.iter().zip(from.channels.iter()))
+        {
+            let mut program = None;
+            controllers.clear();
+            settings.diff(from, &mut controllers, &mut program);
+
+            let control_change = |(number, value)| {
+                channel.send_event(VoiceEvent::ControlChange(Controller::new(
+                    DataByte::new_unchecked(number),
+                    DataByte::new_unchecked(value),
+                )))
+            };
+            // bank select must precede the program change
+            let banks = if controllers.first().is_some_and(|(n, _)| *n == 0x00) {
+                2
+            } else {
+                0
+            };
+            messages.extend(controllers[..banks].iter().copied().map(control_change));
+            if let Some(program) = program {
+                messages.push(channel.send_event(VoiceEvent::ProgramChange {
+                    program: Program::new_unchecked(program),
+                }));
+            }
+            messages.extend(controllers[banks..].iter().copied().map(control_change));
+            if settings.pitch_bend != from.pitch_bend {
+                messages.push(channel.send_event(VoiceEvent::PitchBend(settings.pitch_bend)));
+            }
+        }
+        messages
+    }
+}
+
+#[test]
+fn registered_parameters() {
+    let mut state = ChannelState::new();
+    let cc = |number, value| {
+        Channel::Two.send_event(VoiceEvent::ControlChange(Controller::new(
+            DataByte::new_unchecked(number),
+            DataByte::new_unchecked(value),
+        )))
+    };
+    for message in [cc(0x65, 0), cc(0x64, 0), cc(0x06, 12), cc(0x26, 50)] {
+        state.apply(&message);
+    }
+    let settings = state.channel(Channel::Two);
+    assert_eq!(settings.pitch_bend_range(), (12, 50));
+
+    // the selection stays on the pitch bend range
+    let messages = state.messages();
+    assert_eq!(
+        messages,
+        [cc(0x65, 0), cc(0x64, 0), cc(0x06, 12), cc(0x26, 50)]
+    );
+
+    // data entry for an NRPN isn't tracked
+    for message in [cc(0x63, 1), cc(0x62, 8), cc(0x06, 99)] {
+        state.apply(&message);
+    }
+    assert_eq!(state.channel(Channel::Two).pitch_bend_range(), (12, 50));
+    assert_eq!(state.messages()[4..], [cc(0x63, 1), cc(0x62, 8)]);
+
+    state.apply(&cc(0x79, 0));
+    assert_eq!(state.messages().len(), 4 + 2);
+}
@@ -0,0 +1,460 @@
+use crate::{prelude::*, reader::ReaderError};
+
+mod tracker;
+pub use tracker::*;
+
+/// Identifies a modification to the controller.
+///
+/// There's a lot of these, and they're really useful,
+/// but I don't yet have great explanations for all of these.
+///
+/// Every controller defined by the MIDI 1.0 spec has a variant.
+/// Undefined controller numbers are read as [`Controller::Other`].
+/// This enum is marked as non_exhaustive, as later specs define new controllers.
+///
+/// In general, you'll see there's a `Coarse` and `Fine` variant
+/// of these structs. That's because controllers `0x00..0x20` are paired
+/// with `0x20..0x40` to send 14-bit values.
+///
+/// Often, you'll find that MIDI files only adjust the particular variant
+/// in a "coarse" manner. [`ControllerTracker`] combines the pairs.
+#[non_exhaustive]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+pub enum Controller {
+    /// 0x00
+    BankSelection(DataByte),
+    /// 0x01
+    ModulationCoarse(DataByte),
+    /// 0x02
+    BreathCoarse(DataByte),
+    /// 0x04
+    FootCoarse(DataByte),
+    /// 0x05
+    PortamentoTimeCoarse(DataByte),
+    /// 0x06
+    DataEntryCoarse(DataByte),
+    /// 0x07
+    VolumeCoarse(DataByte),
+    /// 0x08
+    BalanceCoarse(DataByte),
+    /// 0x0A
+    PanCoarse(DataByte),
+    /// 0x0B
+    ExpressionCoarse(DataByte),
+    /// 0x0C
+    EffectControl1Coarse(DataByte),
+    /// 0x0D
+    EffectControl2Coarse(DataByte),
+    /// 0x10
+    GeneralPurpose1Coarse(DataByte),
+    /// 0x11
+    GeneralPurpose2Coarse(DataByte),
+    /// 0x12
+    GeneralPurpose3Coarse(DataByte),
+    /// 0x13
+    GeneralPurpose4Coarse(DataByte),
+    /// 0x20
+    BankSelectionFine(DataByte),
+    /// 0x21
+    ModulationFine(DataByte),
+    /// 0x22
+    BreathFine(DataByte),
+    /// 0x24
+    FootFine(DataByte),
+    /// 0x25
+    PortamentoTimeFine(DataByte),
+    /// 0x26
+    DataEntryFine(DataByte),
+    /// 0x27
+    VolumeFine(DataByte),
+    /// 0x28
+    BalanceFine(DataByte),
+    /// 0x2A
+    PanFine(DataByte),
+    /// 0x2B
+    ExpressionFine(DataByte),
+    /// 0x2C
+    EffectControl1Fine(DataByte),
+    /// 0x2D
+    EffectControl2Fine(DataByte),
+    /// 0x30
+    GeneralPurpose1Fine(DataByte),
+    /// 0x31
+    GeneralPurpose2Fine(DataByte),
+    /// 0x32
+    GeneralPurpose3Fine(DataByte),
+    /// 0x33
+    GeneralPurpose4Fine(DataByte),
+    /// 0x40
+    HoldPedal(DataByte),
+    /// 0x41
+    ///
+    /// Portamento on (>= 64) or off
+    Portamento(DataByte),
+    /// 0x42
+    ///
+    /// Sustains only the notes held when pressed (>= 64)
+    Sostenuto(DataByte),
+    /// 0x43
+    SoftPedal(DataByte),
+    /// 0x44
+    ///
+    /// Legato on (>= 64) or off
+    Legato(DataByte),
+    /// 0x45
+    ///
+    /// Lengthens the release of held notes (>= 64)
+    Hold2(DataByte),
+    /// 0x46
+    ///
+    /// Sound controller 1
+    SoundVariation(DataByte),
+    /// 0x47
+    ///
+    /// Sound controller 2, also known as harmonic intensity or filter resonance
+    Timbre(DataByte),
+    /// 0x48
+    ///
+    /// Sound controller 3
+    ReleaseTime(DataByte),
+    /// 0x49
+    ///
+    /// Sound controller 4
+    AttackTime(DataByte),
+    /// 0x4A
+    ///
+    /// Sound controller 5, usually the filter cutoff
+    Brightness(DataByte),
+    /// 0x4B
+    ///
+    /// Sound controller 6
+    DecayTime(DataByte),
+    /// 0x4C
+    ///
+    /// Sound controller 7
+    VibratoRate(DataByte),
+    /// 0x4D
+    ///
+    /// Sound controller 8
+    VibratoDepth(DataByte),
+    /// 0x4E
+    ///
+    /// Sound controller 9
+    VibratoDelay(DataByte),
+    /// 0x4F
+    ///
+    /// Sound controller 10, which has no default meaning
+    SoundController10(DataByte),
+    /// 0x50
+    GeneralPurpose5(DataByte),
+    /// 0x51
+    GeneralPurpose6(DataByte),
+    /// 0x52
+    GeneralPurpose7(DataByte),
+    /// 0x53
+    GeneralPurpose8(DataByte),
+    /// 0x54
+    ///
+    /// The key the next note glides from
+    PortamentoControl(DataByte),
+    /// 0x58
+    ///
+    /// The low 7 bits of the velocity of the next note on
+    HighResolutionVelocityPrefix(DataByte),
+    /// 0x5B
+    ///
+    /// Effects 1 depth
+    ReverbSend(DataByte),
+    /// 0x5C
+    ///
+    /// Effects 2 depth
+    TremoloDepth(DataByte),
+    /// 0x5D
+    ///
+    /// Effects 3 depth
+    ChorusSend(DataByte),
+    /// 0x5E
+    ///
+    /// Effects 4 depth, also known as detune
+    CelesteDepth(DataByte),
+    /// 0x5F
+    ///
+    /// Effects 5 depth
+    PhaserDepth(DataByte),
+    /// 0x60
+    ///
+    /// Increments the selected (N)RPN
+    DataIncrement(DataByte),
+    /// 0x61
+    ///
+    /// Decrements the selected (N)RPN
+    DataDecrement(DataByte),
+    /// 0x62
+    NRPNFine(DataByte),
+    /// 0x63
+    NRPNCoarse(DataByte),
+    /// 0x64
+    RPNFine(DataByte),
+    /// 0x65
+    RPNCoarse(DataByte),
+    /// 0x78
+    ///
+    /// All sound should immediately turn off
+    MuteImmediately(DataByte),
+    /// 0x79
+    ResetAllControllers(DataByte),
+    /// 0x7A
+    ///
+    /// Local control on (127) or off (0)
+    LocalControl(DataByte),
+    /// 0x7B
+    ///
+    /// All notes should turn off, but not immediately
+    Mute(DataByte),
+    /// 0x7C
+    OmniModeOff(DataByte),
+    /// 0x7D
+    OmniModeOn(DataByte),
+    /// 0x7E
+    ///
+    /// The value is the number of channels, or 0 for as many as there are voices
+    MonoModeOn(DataByte),
+    /// 0x7F
+    PolyModeOn(DataByte),
+
+    /// A value not listed in this enum.
+    /// it's value is in byte_1, and byte_2 *may* have valuable data.
+    ///
+    /// Note: the second byte is ALWAYS read here. If I am missing
+    /// something important, please file an issue immediately so I can
+    /// patch this!
+    Other {
+        /// The value of the controller change
+        byte_1: DataByte,
+        /// The byte following the controller change.
+        /// NOTE: this list is non-exhaustive, so this could be part of the next message.
+        byte_2: DataByte,
+    },
+}
+
+impl Controller {
+    /// Mute all (without immediacy)
+    pub const fn mute_all() -> Self {
+        Self::Mute(DataByte::ZERO)
+    }
+    /// Mute all voices immediately
+    pub const fn mute_all_immediately() -> Self {
+        Self::MuteImmediately(DataByte::ZERO)
+    }
+
+    /// Reset all controllers/voices to defaults
+    pub const fn reset_all() -> Self {
+        Self::ResetAllControllers(DataByte::ZERO)
+    }
+    /// A controller command not listed
+    pub const fn other(byte_1: DataByte, byte_2: DataByte) -> Self {
+        Self::Other { byte_1, byte_2 }
+    }
+
+    pub(crate) fn read<'a, R>(reader: &mut Reader<R>) -> ReadResult<Self>
+    where
+        R: MidiSource<'a>,
+    {
+        let controller_byte = reader.read_next()?;
+        let data_byte = reader.read_next_as_databyte()?;
+        let controller_byte = DataByte::new(controller_byte)
+            .map_err(|v| ReaderError::parse_error(reader.buffer_position(), v))?;
+        Ok(Self::new(controller_byte, data_byte))
+    }
+
+    /// Identify a controller from its number and value
+    pub const fn new(controller: DataByte, value: DataByte) -> Self {
+        use Controller::*;
+        match controller.value() {
+            0x00 => BankSelection(value),
+            0x01 => ModulationCoarse(value),
+            0x02 => BreathCoarse(value),
+            0x04 => FootCoarse(value),
+            0x05 => PortamentoTimeCoarse(value),
+            0x06 => DataEntryCoarse(value),
+            0x07 => VolumeCoarse(value),
+            0x08 => BalanceCoarse(value),
+            0x0A => PanCoarse(value),
+            0x0B => ExpressionCoarse(value),
+            0x0C => EffectControl1Coarse(value),
+            0x0D => EffectControl2Coarse(value),
+            0x10 => GeneralPurpose1Coarse(value),
+            0x11 => GeneralPurpose2Coarse(value),
+            0x12 => GeneralPurpose3Coarse(value),
+            0x13 => GeneralPurpose4Coarse(value),
+            0x20 => BankSelectionFine(value),
+            0x21 => ModulationFine(value),
+            0x22 => BreathFine(value),
+            0x24 => FootFine(value),
+            0x25 => PortamentoTimeFine(value),
+            0x26 => DataEntryFine(value),
+            0x27 => VolumeFine(value),
+            0x28 => BalanceFine(value),
+            0x2A => PanFine(value),
+            0x2B => ExpressionFine(value),
+            0x2C => EffectControl1Fine(value),
+            0x2D => EffectControl2Fine(value),
+            0x30 => GeneralPurpose1Fine(value),
+            0x31 => GeneralPurpose2Fine(value),
+            0x32 => GeneralPurpose3Fine(value),
+            0x33 => GeneralPurpose4Fine(value),
+            0x40 => HoldPedal(value),
+            0x41 => Portamento(value),
+            0x42 => Sostenuto(value),
+            0x43 => SoftPedal(value),
+            0x44 => Legato(value),
+            0x45 => Hold2(value),
+            0x46 => SoundVariation(value),
+            0x47 => Timbre(value),
+            0x48 => ReleaseTime(value),
+            0x49 => AttackTime(value),
+            0x4A => Brightness(value),
+            0x4B => DecayTime(value),
+            0x4C => VibratoRate(value),
+            0x4D => VibratoDepth(value),
+            0x4E => VibratoDelay(value),
+            0x4F => SoundController10(value),
+            0x50 => GeneralPurpose5(value),
+            0x51 => GeneralPurpose6(value),
+            0x52 => GeneralPurpose7(value),
+            0x53 => GeneralPurpose8(value),
+            0x54 => PortamentoControl(value),
+            0x58 => HighResolutionVelocityPrefix(value),
+            0x5B => ReverbSend(value),
+            0x5C => TremoloDepth(value),
+            0x5D => ChorusSend(value),
+            0x5E => CelesteDepth(value),
+            0x5F => PhaserDepth(value),
+            0x60 => DataIncrement(value),
+            0x61 => DataDecrement(value),
+            0x62 => NRPNFine(value),
+            0x63 => NRPNCoarse(value),
+            0x64 => RPNFine(value),
+            0x65 => RPNCoarse(value),
+            0x78 => MuteImmediately(value),
+            0x79 => ResetAllControllers(value),
+            0x7A => LocalControl(value),
+            0x7B => Mute(value),
+            0x7C => OmniModeOff(value),
+            0x7D => OmniModeOn(value),
+            0x7E => MonoModeOn(value),
+            0x7F => PolyModeOn(value),
+            _ => Other {
+                byte_1: controller,
+                byte_2: value,
+            },
+        }
+    }
+    /// Converts self to a vector of bytes.
+    pub const fn to_bytes(&self) -> [u8; 2] {
+        use Controller::*;
+        match self {
+            BankSelection(b) => [0x00, b.value()],
+            ModulationCoarse(b) => [0x01, b.value()],
+            BreathCoarse(b) => [0x02, b.value()],
+            FootCoarse(b) => [0x04, b.value()],
+            PortamentoTimeCoarse(b) => [0x05, b.value()],
+            DataEntryCoarse(b) => [0x06, b.value()],
+            VolumeCoarse(b) => [0x07, b.value()],
+            BalanceCoarse(b) => [0x08, b.value()],
+            PanCoarse(b) => [0x0A, b.value()],
+            ExpressionCoarse(b) => [0x0B, b.value()],
+            EffectControl1Coarse(b) => [0x0C, b.value()],
+            EffectControl2Coarse(b) => [0x0D, b.value()],
+            GeneralPurpose1Coarse(b) => [0x10, b.value()],
+            GeneralPurpose2Coarse(b) => [0x11, b.value()],
+            GeneralPurpose3Coarse(b) => [0x12, b.value()],
+            GeneralPurpose4Coarse(b) => [0x13, b.value()],
+            BankSelectionFine(b) => [0x20, b.value()],
+            ModulationFine(b) => [0x21, b.value()],
+            BreathFine(b) => [0x22, b.value()],
+            FootFine(b) => [0x24, b.value()],
+            PortamentoTimeFine(b) => [0x25, b.value()],
+            DataEntryFine(b) => [0x26, b.value()],
+            VolumeFine(b) => [0x27, b.value()],
+            BalanceFine(b) => [0x28, b.value()],
+            PanFine(b) => [0x2A, b.value()],
+            ExpressionFine(b) => [0x2B, b.value()],
+            EffectControl1Fine(b) => [0x2C, b.value()],
+            EffectControl2Fine(b) => [0x2D, b.value()],
+            GeneralPurpose1Fine(b) => [0x30, b.value()],
+            GeneralPurpose2Fine(b) => [0x31, b.value()],
+            GeneralPurpose3Fine(b) => [0x32, b.value()],
+            GeneralPurpose4Fine(b) => [0x33, b.value()],
+            HoldPedal(b) => [0x40, b.value()],
+            Portamento(b) => [0x41, b.value()],
+            Sostenuto(b) => [0x42, b.value()],
+            SoftPedal(b) => [0x43, b.value()],
+            Legato(b) => [0x44, b.value()],
+            Hold2(b) => [0x45, b.value()],
+            SoundVariation(b) => [0x46, b.value()],
+            Timbre(b) => [0x47, b.value()],
+            ReleaseTime(b) => [0x48, b.value()],
+            AttackTime(b) => [0x49, b.value()],
+            Brightness(b) => [0x4A, b.value()],
+            DecayTime(b) => [0x4B, b.value()],
+            VibratoRate(b) => [0x4C, b.value()],
+            VibratoDepth(b) => [0x4D, b.value()],
+            VibratoDelay(b) => [0x4E, b.value()],
+            SoundController10(b) => [0x4F, b.value()],
+            GeneralPurpose5(b) => [0x50, b.value()],
+            GeneralPurpose6(b) => [0x51, b.value()],
+            GeneralPurpose7(b) => [0x52, b.value()],
+            GeneralPurpose8(b) => [0x53, b.value()],
+            PortamentoControl(b) => [0x54, b.value()],
+            HighResolutionVelocityPrefix(b) => [0x58, b.value()],
+            ReverbSend(b) => [0x5B, b.value()],
+            TremoloDepth(b) => [0x5C, b.value()],
+            ChorusSend(b) => [0x5D, b.value()],
+            CelesteDepth(b) => [0x5E, b.value()],
+            PhaserDepth(b) => [0x5F, b.value()],
+            DataIncrement(b) => [0x60, b.value()],
+            DataDecrement(b) => [0x61, b.value()],
+            NRPNFine(b) => [0x62, b.value()],
+            NRPNCoarse(b) => [0x63, b.value()],
+            RPNFine(b) => [0x64, b.value()],
+            RPNCoarse(b) => [0x65, b.value()],
+            MuteImmediately(b) => [0x78, b.value()],
+            ResetAllControllers(b) => [0x79, b.value()],
+            LocalControl(b) => [0x7A, b.value()],
+            Mute(b) => [0x7B, b.value()],
+            OmniModeOff(b) => [0x7C, b.value()],
+            OmniModeOn(b) => [0x7D, b.value()],
+            MonoModeOn(b) => [0x7E, b.value()],
+            PolyModeOn(b) => [0x7F, b.value()],
+            Other { byte_1, byte_2 } => [byte_1.value(), byte_2.value()],
+        }
+    }
+}
+
+#[test]
+fn controller_numbers() {
+    for number in 0..128 {
+        let value = DataByte::new_unchecked(0x12);
+        let controller = Controller::new(DataByte::new_unchecked(number), value);
+        assert_eq!(controller.to_bytes(), [number, 0x12]);
+    }
+
+    let data = DataByte::new_unchecked;
+    assert_eq!(
+        Controller::new(data(0x65), data(0)),
+        Controller::RPNCoarse(data(0))
+    );
+    assert_eq!(
+        Controller::new(data(0x64), data(0)),
+        Controller::RPNFine(data(0))
+    );
+    assert_eq!(
+        Controller::new(data(0x4A), data(0)),
+        Controller::Brightness(data(0))
+    );
+    assert_eq!(
+        Controller::new(data(0x03), data(0)),
+        Controller::other(data(0x03), data(0))
+    );
+}
@@ -0,0 +1,129 @@
+use crate::prelude::*;
+
+/// The number of coarse/fine controller pairs: `0x00..0x20` with `0x20..0x40`
+const PAIRS: usize = 32;
+
+#[doc = r#"
+Combines coarse (MSB) and fine (LSB) controller pairs into 14-bit values, per channel.
+
+Controllers `0x00..0x20` send the upper 7 bits of a value, and controllers `0x20..0x40`
+send the lower 7 bits of the same value. As the MIDI spec asks, a coarse change clears
+the fine part, so a sender that never uses the fine controller reads as `coarse << 7`.
+
+Values start at their reset defaults (volume 100, pan, balance and expression centered
+or full), and [`Controller::ResetAllControllers`] resets modulation and expression.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let mut tracker = ControllerTracker::new();
+let volume = |value| Controller::VolumeCoarse(DataByte::new(value).unwrap());
+let volume_fine = |value| Controller::VolumeFine(DataByte::new(value).unwrap());
+
+assert_eq!(tracker.update(Channel::One, &volume(0x40)), Some(0x2000));
+assert_eq!(tracker.update(Channel::One, &volume_fine(0x01)), Some(0x2001));
+
+// a coarse change clears the fine part
+assert_eq!(tracker.update(Channel::One, &volume(0x41)), Some(0x2080));
+
+// other channels are untouched
+assert_eq!(tracker.value(Channel::Two, DataByte::new(0x07).unwrap()), Some(100 << 7));
+
+// controllers without a fine part aren't tracked
+assert_eq!(tracker.update(Channel::One, &Controller::HoldPedal(DataByte::MAX)), None);
+```
+"#]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ControllerTracker {
+    channels: [[u16; PAIRS]; 16],
+}
+
+impl Default for ControllerTracker {
+    fn default() -> Self {
+        let mut values = [0; PAIRS];
+        values[0x07] = 100 << 7;
+        values[0x08] = 64 << 7;
+        values[0x0A] = 64 << 7;
+        values[0x0B] = 127 << 7;
+        Self {
+            channels: [values; 16],
+        }
+    }
+}
+
+impl ControllerTracker {
+    /// A tracker with every channel at its reset values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the tracker with a controller sent to a channel.
+    ///
+    /// Returns the combined 14-bit value if the controller is half of a pair.
+    pub fn update(&mut self, channel: Channel, controller: &Controller) -> Option<u16> {
+        let values = &mut self.channels[channel.to_byte() as usize];
+        let [number, value] = controller.to_bytes();
+        let number = number as usize;
+        match number {
+            0x00..0x20 => {
+                values[number] = (value as u16) << 7;
+                Some(values[number])
+            }
+            0x20..0x40 => {
+                let pair = &mut values[number - PAIRS];
+                *pair = (*pair & !0x7F) | value as u16;
+                Some(*pair)
+            }
+            0x79 => {
+                values[0x01] = 0;
+                values[0x0B] = 127 << 7;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Update the tracker with a message. Only control changes are tracked.
+    ///
+    /// Returns the combined 14-bit value if the message changes half of a pair.
+    pub fn apply(&mut self, message: &ChannelVoiceMessage) -> Option<u16> {
+        match message.event() {
+            VoiceEvent::ControlChange(controller) => self.update(message.channel(), controller),
+            _ => None,
+        }
+    }
+
+    /// The 14-bit value of a controller pair, by the number of either half.
+    ///
+    /// Returns `None` for controllers without a fine part (`0x40` and above).
+    pub fn value(&self, channel: Channel, controller: DataByte) -> Option<u16> {
+        let number = controller.value() as usize;
+        (number < PAIRS * 2).then(|| self.channels[channel.to_byte() as usize][number % PAIRS])
+    }
+}
+
+#[test]
+fn reset_all_controllers() {
+    let mut tracker = ControllerTracker::new();
+    let data = |value| DataByte::new(value).unwrap();
+
+    tracker.update(Channel::Three, &Controller::ModulationCoarse(data(0x7F)));
+    tracker.update(Channel::Three, &Controller::ExpressionFine(data(0x10)));
+    tracker.update(Channel::Three, &Controller::VolumeFine(data(0x10)));
+    assert_eq!(tracker.value(Channel::Three, data(0x01)), Some(0x7F << 7));
+    assert_eq!(
+        tracker.value(Channel::Three, data(0x2B)),
+        Some((127 << 7) | 0x10)
+    );
+
+    tracker.update(Channel::Three, &Controller::reset_all());
+    assert_eq!(tracker.value(Channel::Three, data(0x01)), Some(0));
+    assert_eq!(tracker.value(Channel::Three, data(0x0B)), Some(127 << 7));
+    // volume isn't reset
+    assert_eq!(
+        tracker.value(Channel::Three, data(0x07)),
+        Some((100 << 7) | 0x10)
+    );
+    assert_eq!(tracker.value(Channel::Three, data(0x40)), None);
+}
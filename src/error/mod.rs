@@ -24,7 +24,7 @@ use thiserror::Error;
 // }
 
 /// All the ways parsing can go wrong
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum ParseError {
     /// Invalid databyte (leading 1)
     #[error("Invalid Data Byte: {0:0X}")]
@@ -65,6 +65,9 @@ pub enum ParseError {
     /// Errors that occur after all reading has completed
     #[error("{0}")]
     File(FileError),
+    /// Something wrong with a RIFF MIDI container
+    #[error("RIFF: {0}")]
+    Riff(RiffError),
 }
 impl ParseError {
     pub(crate) fn channel_count(varlen: usize) -> Self {
@@ -81,7 +84,7 @@ impl ParseError {
     }
 }
 /// Problems reading a file's header
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum HeaderError {
     /// Type 0 MIDI Format (SingleMultiChannel) defines multiple tracks
     #[error("Type 0 MIDI Format (SingleMultiChannel) defined multiple tracks")]
@@ -107,7 +110,7 @@ impl From<HeaderError> for ParseError {
 }
 
 /// Problems reading a file's metamessages
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum MetaMessageError {
     /// contains varlen inner, should be 1.
     #[error("varlen for channel count was {0}. Expected 1.")]
@@ -129,11 +132,17 @@ impl From<MetaMessageError> for ParseError {
 }
 
 /// problems reading a track
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum TrackError {
     /// Invalid event
     #[error("Invalid event found: {0:0X}")]
     InvalidEvent(u8),
+    /// The track's body ended without an `EndOfTrack` meta message
+    #[error("Track has no EndOfTrack event")]
+    MissingEndOfTrack,
+    /// The track's `EndOfTrack` does not line up with the length of its chunk
+    #[error("Track length does not match the position of its EndOfTrack event")]
+    LengthMismatch,
 }
 
 impl From<TrackError> for ParseError {
@@ -143,7 +152,7 @@ impl From<TrackError> for ParseError {
 }
 
 /// Problems reading a chunk
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum ChunkError {
     /// Finding more than one header for a chunk
     #[error("Found more than one header for this chunk.")]
@@ -154,10 +163,22 @@ pub enum ChunkError {
     /// the format is singlemultichannel, yet multiple tracks were found
     #[error("There's more than one track for a SingleMultiChannel format")]
     MultipleTracksForSingleMultiChannel,
+    /// The chunk's length runs past the end of the file
+    #[error("Chunk length {0} runs past the end of the file")]
+    LengthPastEndOfFile(u32),
+    /// The chunk's type is not made up of four ASCII characters
+    #[error("Invalid chunk type: {0:02X?}")]
+    InvalidType([u8; 4]),
+}
+
+impl From<ChunkError> for ParseError {
+    fn from(value: ChunkError) -> Self {
+        Self::Chunk(value)
+    }
 }
 
 /// Problems with the file after reading it through
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum FileError {
     /// No format was found
     #[error("The file's format couldn't be determined")]
@@ -167,8 +188,25 @@ pub enum FileError {
     NoTiming,
 }
 
+/// Problems reading a RIFF MIDI (`.rmi`) container
+#[derive(Debug, Error, Clone)]
+pub enum RiffError {
+    /// The RIFF form type isn't `RMID`
+    #[error("Expected an RMID form type. Found {0:02X?}")]
+    NotRmid([u8; 4]),
+    /// The container has no `data` chunk holding a MIDI file
+    #[error("The RMID container has no data chunk")]
+    MissingData,
+}
+
+impl From<RiffError> for ParseError {
+    fn from(value: RiffError) -> Self {
+        Self::Riff(value)
+    }
+}
+
 /// An error related toe Smpte parsing
-#[derive(Debug, Error, PartialEq, Eq)]
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
 pub enum SmpteError {
     /// The hour part of the meta smpte message is invalid
     #[error("Invalid hour for offset. Expected 0-24. Got {0}")]
@@ -195,3 +233,16 @@ pub enum SmpteError {
     #[error("Invalid SMPTE time in header (only -24, -25, -29, and -30 allowed.) Interpreted {0}")]
     HeaderFrameTime(i8),
 }
+
+/// Problems encoding a message into bytes
+#[derive(Debug, Error, PartialEq, Eq, Clone, Copy)]
+pub enum EncodeError {
+    /// The provided buffer cannot hold the encoded message
+    #[error("Buffer of length {available} is too small. {required} bytes are required.")]
+    BufferTooSmall {
+        /// The number of bytes needed to encode the message
+        required: usize,
+        /// The length of the provided buffer
+        available: usize,
+    },
+}
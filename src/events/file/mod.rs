@@ -6,7 +6,7 @@ pub use chunk::*;
 #[doc = r#"
 An event that can be yielded from or put into a `.mid` file.
 
-This type is yielded by [`Reader::read_event`] and will be consumed by a Writer in the future.
+This type is yielded by [`Reader::read_event`] and consumed by [`Writer::write_event`].
 
 # Overview
 
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::prelude::*;
 
 /// Identifies something that can be interpreted from the bytes of a live MIDI stream
@@ -38,6 +40,61 @@ pub trait FromLiveEventBytes {
         Self: Sized;
 }
 
+#[doc = r#"
+Identifies something that can be written as the bytes of a live MIDI stream.
+
+This is the counterpart of [`FromLiveEventBytes`]. Encoding into a slice
+never allocates:
+
+```rust
+use midix::prelude::*;
+
+let event = LiveEvent::from(ChannelVoiceMessage::new(
+    Channel::Three,
+    VoiceEvent::note_on(key!(C, 4), Velocity::MAX),
+));
+
+let mut buf = [0; 3];
+let len = event.write_into(&mut buf).unwrap();
+assert_eq!(buf[..len], [0x92, 60, 127]);
+assert_eq!(LiveEvent::from_bytes(&buf[..len]).unwrap(), event);
+```
+"#]
+pub trait ToLiveEventBytes {
+    /// The number of bytes needed to represent the type in a live stream
+    fn byte_len(&self) -> usize;
+
+    /// Write the type into the start of `buf`, returning the number of bytes written.
+    ///
+    /// # Errors
+    /// If `buf` is shorter than [`ToLiveEventBytes::byte_len`]. Nothing is written in this case.
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Append the bytes of the type onto the end of `buf`, returning the number of bytes written.
+    fn extend_into(&self, buf: &mut Vec<u8>) -> usize {
+        let start = buf.len();
+        buf.resize(start + self.byte_len(), 0);
+        // the buffer is resized to fit exactly
+        self.write_into(&mut buf[start..]).unwrap()
+    }
+
+    /// Get the bytes of the type in a new vector
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.byte_len());
+        self.extend_into(&mut buf);
+        buf
+    }
+}
+
+/// Returns the buffer's first `required` bytes if it can hold them
+pub(crate) fn sized_buf(buf: &mut [u8], required: usize) -> Result<&mut [u8], EncodeError> {
+    let available = buf.len();
+    buf.get_mut(..required).ok_or(EncodeError::BufferTooSmall {
+        required,
+        available,
+    })
+}
+
 #[doc = r"
 An emittable message to/from a streaming MIDI device.
 
@@ -64,15 +121,23 @@ impl LiveEvent<'_> {
             _ => None,
         }
     }
+}
 
-    // /// Returns the event as a set of bytes. These bytes are to be interpreted by a MIDI live stream
-    // pub fn to_bytes(&self) -> Vec<u8> {
-    //     match self {
-    //         LiveEvent::ChannelVoice(c) => c.to_bytes(),
-    //         LiveEvent::SysCommon(s) => s.to_bytes(),
-    //         LiveEvent::SysRealTime(r) => vec![r.byte()],
-    //     }
-    // }
+impl ToLiveEventBytes for LiveEvent<'_> {
+    fn byte_len(&self) -> usize {
+        match self {
+            LiveEvent::ChannelVoice(c) => c.byte_len(),
+            LiveEvent::SysCommon(s) => s.byte_len(),
+            LiveEvent::SysRealTime(r) => r.byte_len(),
+        }
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        match self {
+            LiveEvent::ChannelVoice(c) => c.write_into(buf),
+            LiveEvent::SysCommon(s) => s.write_into(buf),
+            LiveEvent::SysRealTime(r) => r.write_into(buf),
+        }
+    }
 }
 
 impl From<ChannelVoiceMessage> for LiveEvent<'_> {
@@ -119,21 +184,36 @@ impl FromLiveEventBytes for LiveEvent<'_> {
     }
 }
 
-// #[test]
-// fn parse_note_on() {
-//     use crate::prelude::*;
-//     let message = [0b1001_0001, 0b0100_1000, 0b001_00001];
-//     let parsed = LiveEvent::from_bytes(&message).unwrap();
-//     //parsed: ChannelVoice(ChannelVoiceMessage { channel: Channel(1), message: NoteOn { key: Key(72), vel: Velocity(33) } })
-
-//     assert_eq!(
-//         parsed,
-//         LiveEvent::ChannelVoice(ChannelVoiceMessage::new(
-//             Channel::Two,
-//             VoiceEvent::NoteOn {
-//                 key: Key::from_databyte(72).unwrap(),
-//                 velocity: Velocity::new(33).unwrap()
-//             }
-//         ))
-//     );
-// }
+#[test]
+fn parse_note_on() {
+    let message = [0b1001_0001, 0b0100_1000, 0b001_00001];
+    let parsed = LiveEvent::from_bytes(&message).unwrap();
+
+    assert_eq!(
+        parsed,
+        LiveEvent::ChannelVoice(ChannelVoiceMessage::new(
+            Channel::Two,
+            VoiceEvent::NoteOn {
+                key: Key::from_databyte(72).unwrap(),
+                velocity: Velocity::new(33).unwrap()
+            }
+        ))
+    );
+    assert_eq!(parsed.to_bytes(), message);
+}
+
+#[test]
+fn write_into_small_buffer() {
+    let event = LiveEvent::from(SystemExclusiveMessage::new(
+        [0x7E, 0x7F, 0x09, 0x01].as_slice(),
+    ));
+    let mut buf = [0; 5];
+    assert_eq!(
+        event.write_into(&mut buf),
+        Err(EncodeError::BufferTooSmall {
+            required: 6,
+            available: 5
+        })
+    );
+    assert_eq!(buf, [0; 5]);
+}
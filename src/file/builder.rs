@@ -67,44 +67,7 @@ impl<'a> MidiFileBuilder<'a> {
 
                 Ok(())
             }
-            Track(t) => {
-                let events = t.events()?;
-
-                let track = super::Track::new(events);
-                let mut track_vec = Vec::new();
-                match &mut self.format {
-                    FormatStage::Unknown => {
-                        track_vec.push(track);
-                        self.format = FormatStage::KnownTracks(track_vec);
-                    }
-                    FormatStage::KnownFormat(t) => match t.format_type() {
-                        FormatType::Simultaneous => {
-                            track_vec.push(track);
-
-                            self.format = FormatStage::Formatted(Format::Simultaneous(track_vec))
-                        }
-                        FormatType::SingleMultiChannel => {
-                            self.format = FormatStage::Formatted(Format::SingleMultiChannel(track))
-                        }
-                        FormatType::SequentiallyIndependent => {
-                            track_vec.push(track);
-                            self.format =
-                                FormatStage::Formatted(Format::SequentiallyIndependent(track_vec))
-                        }
-                    },
-                    FormatStage::KnownTracks(tracks) => tracks.push(track),
-                    FormatStage::Formatted(format) => match format {
-                        Format::SequentiallyIndependent(tracks) => tracks.push(track),
-                        Format::SingleMultiChannel(_) => {
-                            return Err(ReaderErrorKind::chunk(
-                                ChunkError::MultipleTracksForSingleMultiChannel,
-                            ));
-                        }
-                        Format::Simultaneous(tracks) => tracks.push(track),
-                    },
-                }
-                Ok(())
-            }
+            Track(t) => self.handle_track(t.events()?),
             Unknown(data) => {
                 self.unknown_chunks.push(data);
                 Ok(())
@@ -112,6 +75,45 @@ impl<'a> MidiFileBuilder<'a> {
             EOF => Err(ReaderErrorKind::ReadError(ReadError::OutOfBounds)),
         }
     }
+
+    pub fn handle_track<'b: 'a>(
+        &mut self,
+        events: Vec<TrackEvent<'b>>,
+    ) -> Result<(), ReaderErrorKind> {
+        let track = super::Track::new(events);
+        let mut track_vec = Vec::new();
+        match &mut self.format {
+            FormatStage::Unknown => {
+                track_vec.push(track);
+                self.format = FormatStage::KnownTracks(track_vec);
+            }
+            FormatStage::KnownFormat(t) => match t.format_type() {
+                FormatType::Simultaneous => {
+                    track_vec.push(track);
+
+                    self.format = FormatStage::Formatted(Format::Simultaneous(track_vec))
+                }
+                FormatType::SingleMultiChannel => {
+                    self.format = FormatStage::Formatted(Format::SingleMultiChannel(track))
+                }
+                FormatType::SequentiallyIndependent => {
+                    track_vec.push(track);
+                    self.format = FormatStage::Formatted(Format::SequentiallyIndependent(track_vec))
+                }
+            },
+            FormatStage::KnownTracks(tracks) => tracks.push(track),
+            FormatStage::Formatted(format) => match format {
+                Format::SequentiallyIndependent(tracks) => tracks.push(track),
+                Format::SingleMultiChannel(_) => {
+                    return Err(ReaderErrorKind::chunk(
+                        ChunkError::MultipleTracksForSingleMultiChannel,
+                    ));
+                }
+                Format::Simultaneous(tracks) => tracks.push(track),
+            },
+        }
+        Ok(())
+    }
     pub fn build(self) -> Result<ParsedMidiFile<'a>, FileError> {
         let FormatStage::Formatted(format) = self.format else {
             return Err(FileError::NoFormat);
@@ -123,6 +125,7 @@ impl<'a> MidiFileBuilder<'a> {
         Ok(ParsedMidiFile {
             format,
             header: Header::new(timing),
+            rmid: None,
         })
     }
 }
@@ -14,11 +14,16 @@ mod header;
 pub use header::*;
 mod track;
 pub use track::*;
+mod tempo_map;
+pub use tempo_map::*;
+mod rmid;
+pub use rmid::*;
 
 use crate::{
     ParseError,
-    prelude::FormatType,
-    reader::{ReadResult, Reader, ReaderError, ReaderErrorKind},
+    prelude::{ChunkEvent, FileEvent, FormatType},
+    reader::{ReadResult, Reader, ReaderError, ReaderErrorKind, ReaderOptions},
+    writer::{WriteResult, Writer},
 };
 
 #[doc = r#"
@@ -27,19 +32,31 @@ TODO
 pub struct ParsedMidiFile<'a> {
     header: Header,
     format: Format<'a>,
+    rmid: Option<Rmid<'a>>,
 }
 
 impl<'a> ParsedMidiFile<'a> {
-    /// Parse a set of bytes into a file struct
+    /// Parse a set of bytes into a file struct.
+    ///
+    /// Bytes may either be a Standard MIDI File, or a RIFF MIDI (`.rmi`) container
+    /// (see [`ParsedMidiFile::rmid`]).
     pub fn parse<B>(bytes: B) -> ReadResult<Self>
     where
         B: Into<Cow<'a, [u8]>>,
     {
+        let bytes = bytes.into();
+        if bytes.starts_with(b"RIFF") {
+            let (rmid, offset, data) = Rmid::read(&bytes)?;
+            let mut file = Self::parse(data).map_err(|e| e.offset_by(offset))?;
+            file.rmid = Some(rmid);
+            return Ok(file);
+        }
+
         let mut reader = Reader::from_bytes(bytes);
         let mut builder = MidiFileBuilder::default();
 
         loop {
-            let val = reader.read_chunk().unwrap();
+            let val = reader.read_chunk()?;
 
             if val.is_eof() {
                 break;
@@ -49,12 +66,166 @@ impl<'a> ParsedMidiFile<'a> {
                 .map_err(|k| ReaderError::new(reader.buffer_position(), k))?;
         }
 
-        builder.build().map_err(|k| {
-            ReaderError::new(
-                reader.buffer_position(),
-                ReaderErrorKind::ParseError(ParseError::File(k)),
-            )
-        })
+        build(builder, reader.buffer_position())
+    }
+
+    /// Parse a set of bytes into a file struct with the given [`ReaderOptions`].
+    ///
+    /// Returns the file along with every problem that was recovered from.
+    /// When recovering, problems assembling the file (such as a duplicate header)
+    /// are also recorded rather than returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use midix::prelude::*;
+    ///
+    /// let bytes = include_bytes!("../../test-asset/PiDamaged.mid");
+    /// assert!(ParsedMidiFile::parse(bytes.as_slice()).is_err());
+    ///
+    /// let (file, diagnostics) =
+    ///     ParsedMidiFile::parse_with_options(bytes.as_slice(), ReaderOptions::lenient()).unwrap();
+    /// assert!(!file.tracks().is_empty());
+    /// assert!(!diagnostics.is_empty());
+    /// ```
+    pub fn parse_with_options<B>(
+        bytes: B,
+        options: ReaderOptions,
+    ) -> ReadResult<(Self, Vec<ReaderError>)>
+    where
+        B: Into<Cow<'a, [u8]>>,
+    {
+        let bytes = bytes.into();
+        if bytes.starts_with(b"RIFF") {
+            let (rmid, offset, data) = Rmid::read(&bytes)?;
+            let (mut file, diagnostics) =
+                Self::parse_with_options(data, options).map_err(|e| e.offset_by(offset))?;
+            file.rmid = Some(rmid);
+            let diagnostics = diagnostics
+                .into_iter()
+                .map(|e| e.offset_by(offset))
+                .collect();
+            return Ok((file, diagnostics));
+        }
+
+        let mut reader = Reader::from_bytes(bytes).with_options(options);
+        let mut builder = MidiFileBuilder::default();
+        let mut track = None;
+
+        loop {
+            let event = reader.read_event()?;
+            let position = reader.buffer_position();
+            if let FileEvent::TrackEvent(event) = event {
+                track.get_or_insert_with(Vec::new).push(event);
+                continue;
+            }
+
+            let is_eof = matches!(event, FileEvent::EOF);
+            let mut result = match track.take() {
+                Some(events) => builder.handle_track(events),
+                None => Ok(()),
+            };
+            match event {
+                FileEvent::Header(header) => {
+                    result = result.and_then(|_| builder.handle_chunk(ChunkEvent::Header(header)))
+                }
+                FileEvent::Track(_) => track = Some(Vec::new()),
+                FileEvent::Unknown(chunk) => {
+                    result = result.and_then(|_| builder.handle_chunk(ChunkEvent::Unknown(chunk)))
+                }
+                FileEvent::TrackEvent(_) | FileEvent::EOF => {}
+            }
+
+            if let Err(kind) = result {
+                let error = ReaderError::new(position, kind);
+                if !options.recovery() {
+                    return Err(error);
+                }
+                reader.diagnose(error);
+            }
+            if is_eof {
+                break;
+            }
+        }
+
+        let file = build(builder, reader.buffer_position())?;
+        Ok((file, reader.take_diagnostics()))
+    }
+
+    /// Parse a file from a [`Read`](std::io::Read) type
+    /// without buffering the whole stream first.
+    ///
+    /// RIFF MIDI containers are the exception, and are read into memory.
+    ///
+    /// See [`StreamReader`](crate::reader::StreamReader).
+    #[cfg(feature = "std")]
+    pub fn parse_reader<R: std::io::Read>(stream: R) -> ReadResult<ParsedMidiFile<'static>> {
+        let mut reader = crate::reader::StreamReader::new(stream);
+        if reader.peek_start(4)? == b"RIFF" {
+            // the container's chunks follow the midi file, so it is read whole
+            return ParsedMidiFile::parse(reader.read_to_end()?);
+        }
+        let mut builder = MidiFileBuilder::default();
+
+        loop {
+            let val = reader.read_chunk()?;
+
+            if val.is_eof() {
+                break;
+            }
+            builder
+                .handle_chunk(val)
+                .map_err(|k| ReaderError::new(reader.buffer_position(), k))?;
+        }
+
+        build(builder, reader.buffer_position())
+    }
+
+    /// Parse a file from an [`AsyncRead`](futures_io::AsyncRead) type
+    /// without buffering the whole stream first.
+    ///
+    /// RIFF MIDI containers are the exception, and are read into memory.
+    ///
+    /// See [`AsyncStreamReader`](crate::reader::AsyncStreamReader).
+    #[cfg(feature = "std")]
+    pub async fn parse_async_reader<R: futures_io::AsyncRead + Unpin>(
+        stream: R,
+    ) -> ReadResult<ParsedMidiFile<'static>> {
+        let mut reader = crate::reader::AsyncStreamReader::new(stream);
+        if reader.peek_start(4).await? == b"RIFF" {
+            // the container's chunks follow the midi file, so it is read whole
+            return ParsedMidiFile::parse(reader.read_to_end().await?);
+        }
+        let mut builder = MidiFileBuilder::default();
+
+        loop {
+            let val = reader.read_chunk().await?;
+
+            if val.is_eof() {
+                break;
+            }
+            builder
+                .handle_chunk(val)
+                .map_err(|k| ReaderError::new(reader.buffer_position(), k))?;
+        }
+
+        build(builder, reader.buffer_position())
+    }
+
+    /// Write the file into a set of bytes.
+    ///
+    /// See [`Writer::write_file`] for details.
+    pub fn to_bytes(&self) -> WriteResult<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new()).with_running_status(true);
+        writer.write_file(self)?;
+        writer.finish()
+    }
+
+    /// Returns the RIFF MIDI container the file was wrapped in, if any.
+    ///
+    /// This holds the container's `INFO` metadata and embedded sound banks.
+    /// Note that the container is not kept by [`ParsedMidiFile::to_bytes`].
+    pub fn rmid(&self) -> Option<&Rmid<'a>> {
+        self.rmid.as_ref()
     }
 
     /// Returns header info
@@ -79,3 +250,9 @@ impl<'a> ParsedMidiFile<'a> {
         }
     }
 }
+
+fn build(builder: MidiFileBuilder<'_>, position: usize) -> ReadResult<ParsedMidiFile<'_>> {
+    builder
+        .build()
+        .map_err(|k| ReaderError::new(position, ReaderErrorKind::ParseError(ParseError::File(k))))
+}
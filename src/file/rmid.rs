@@ -0,0 +1,251 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{
+    ChunkError, ParseError, RiffError,
+    prelude::BytesText,
+    reader::{MidiSource, ReadResult, ReaderError},
+};
+
+#[doc = r#"
+The contents of a RIFF MIDI (`.rmi`) container, other than the MIDI file itself.
+
+An RMID file wraps a Standard MIDI File in a `data` chunk. It may also carry
+an `INFO` list of metadata, and sound banks (DLS or SF2) that the file should be
+played with.
+
+Obtained from [`ParsedMidiFile::rmid`](super::ParsedMidiFile::rmid).
+"#]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rmid<'a> {
+    info: Vec<InfoEntry<'a>>,
+    sound_banks: Vec<SoundBank<'a>>,
+}
+
+impl<'a> Rmid<'a> {
+    /// Returns true if the bytes begin with a RIFF MIDI header
+    pub fn is_rmid(bytes: &[u8]) -> bool {
+        bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"RMID"
+    }
+
+    /// Reads the container, returning it along with the offset and bytes of its `data` chunk.
+    /// Assumes that the source begins with `RIFF`.
+    ///
+    /// The length of the outer `RIFF` chunk is not trusted, since it is often wrong.
+    pub(crate) fn read(source: &Cow<'a, [u8]>) -> ReadResult<(Self, usize, Cow<'a, [u8]>)> {
+        let Some(form) = source.get_slice(8, 12) else {
+            return Err(ReaderError::oob(source.len()));
+        };
+        if form.as_ref() != b"RMID" {
+            let mut found = [0; 4];
+            found.copy_from_slice(&form);
+            return Err(ReaderError::parse_error(
+                8,
+                RiffError::NotRmid(found).into(),
+            ));
+        }
+
+        let mut rmid = Self {
+            info: Vec::new(),
+            sound_banks: Vec::new(),
+        };
+        let mut data = None;
+        for chunk in riff_chunks(source, 12, source.len()) {
+            let RiffChunk { start, id, body } = chunk?;
+            let body_start = start + 8;
+            match &id {
+                b"data" if data.is_none() => {
+                    data = source
+                        .get_slice(body_start, body_start + body)
+                        .map(|data| (body_start, data));
+                }
+                b"LIST"
+                    if source.get_slice(body_start, body_start + 4).as_deref() == Some(b"INFO") =>
+                {
+                    for entry in riff_chunks(source, body_start + 4, body_start + body) {
+                        let RiffChunk { start, id, body } = entry?;
+                        let mut text = source.get_slice(start + 8, start + 8 + body).unwrap();
+                        // values are usually NUL-terminated
+                        while text.last() == Some(&0) {
+                            text.to_mut().pop();
+                        }
+                        rmid.info.push(InfoEntry {
+                            id,
+                            text: BytesText::new_from_bytes(text),
+                        });
+                    }
+                }
+                b"RIFF" => {
+                    let format = match source.get_slice(body_start, body_start + 4).as_deref() {
+                        Some(b"DLS ") => SoundBankFormat::Dls,
+                        Some(b"sfbk") => SoundBankFormat::Sf2,
+                        _ => continue,
+                    };
+                    rmid.sound_banks.push(SoundBank {
+                        format,
+                        data: source.get_slice(start, body_start + body).unwrap(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let Some((offset, data)) = data else {
+            return Err(ReaderError::parse_error(12, RiffError::MissingData.into()));
+        };
+        Ok((rmid, offset, data))
+    }
+
+    /// The entries of the `INFO` list, in the order they were found
+    pub fn info(&self) -> &[InfoEntry<'a>] {
+        &self.info
+    }
+
+    /// Get the text of an `INFO` entry by its four character id, such as `b"ICOP"`
+    pub fn info_text(&self, id: &[u8; 4]) -> Option<&BytesText<'a>> {
+        self.info
+            .iter()
+            .find(|entry| &entry.id == id)
+            .map(|entry| &entry.text)
+    }
+
+    /// The name of the piece (`INAM`)
+    pub fn name(&self) -> Option<&BytesText<'a>> {
+        self.info_text(b"INAM")
+    }
+
+    /// The artist of the piece (`IART`)
+    pub fn artist(&self) -> Option<&BytesText<'a>> {
+        self.info_text(b"IART")
+    }
+
+    /// The copyright notice of the piece (`ICOP`)
+    pub fn copyright(&self) -> Option<&BytesText<'a>> {
+        self.info_text(b"ICOP")
+    }
+
+    /// Any comments about the piece (`ICMT`)
+    pub fn comment(&self) -> Option<&BytesText<'a>> {
+        self.info_text(b"ICMT")
+    }
+
+    /// The sound banks embedded in the container
+    pub fn sound_banks(&self) -> &[SoundBank<'a>] {
+        &self.sound_banks
+    }
+}
+
+/// An entry of a RIFF `INFO` list
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InfoEntry<'a> {
+    id: [u8; 4],
+    text: BytesText<'a>,
+}
+
+impl<'a> InfoEntry<'a> {
+    /// The four character id of the entry, such as `b"IART"`
+    pub const fn id(&self) -> &[u8; 4] {
+        &self.id
+    }
+
+    /// The value of the entry, without its NUL terminator
+    pub const fn text(&self) -> &BytesText<'a> {
+        &self.text
+    }
+}
+
+/// The format of a [`SoundBank`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundBankFormat {
+    /// A Downloadable Sounds (`RIFF DLS `) collection
+    Dls,
+    /// A SoundFont 2 (`RIFF sfbk`) bank
+    Sf2,
+}
+
+/// A sound bank embedded in a RIFF MIDI container
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoundBank<'a> {
+    format: SoundBankFormat,
+    data: Cow<'a, [u8]>,
+}
+
+impl SoundBank<'_> {
+    /// The format of the bank
+    pub const fn format(&self) -> SoundBankFormat {
+        self.format
+    }
+
+    /// The bytes of the bank, including its `RIFF` header.
+    ///
+    /// These are the contents of a standalone `.dls` or `.sf2` file.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+struct RiffChunk {
+    /// offset of the chunk id
+    start: usize,
+    id: [u8; 4],
+    /// length of the body
+    body: usize,
+}
+
+/// Iterates over the RIFF chunks in `start..end`
+fn riff_chunks<'s, 'a>(
+    source: &'s Cow<'a, [u8]>,
+    start: usize,
+    end: usize,
+) -> impl Iterator<Item = ReadResult<RiffChunk>> + 's {
+    let end = end.min(source.len());
+    let mut position = start;
+    core::iter::from_fn(move || {
+        let header = source.get(position..position + 8)?;
+        if position + 8 > end {
+            return None;
+        }
+        let mut id = [0; 4];
+        id.copy_from_slice(&header[..4]);
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let body = length as usize;
+
+        if position + 8 + body > end {
+            let error = ReaderError::parse_error(
+                position + 4,
+                ParseError::Chunk(ChunkError::LengthPastEndOfFile(length)),
+            );
+            position = end;
+            return Some(Err(error));
+        }
+        let chunk = RiffChunk {
+            start: position,
+            id,
+            body,
+        };
+        // chunks are padded to an even length
+        position += 8 + body + (body & 1);
+        Some(Ok(chunk))
+    })
+}
+
+#[test]
+fn read_beethoven() {
+    let bytes: Cow<'_, [u8]> = include_bytes!("../../test-asset/Beethoven.rmi")
+        .as_slice()
+        .into();
+    let (rmid, offset, data) = Rmid::read(&bytes).unwrap();
+
+    assert_eq!(offset, 20);
+    assert_eq!(&data[..4], b"MThd");
+    assert_eq!(data.len(), 91570);
+    assert_eq!(
+        rmid.artist().unwrap().as_str().unwrap(),
+        "Ludwig von Beethoven"
+    );
+    assert_eq!(
+        rmid.copyright().unwrap().as_str().unwrap(),
+        "1995 Midisoft Corporation"
+    );
+    assert_eq!(rmid.info().len(), 3);
+    assert!(rmid.sound_banks().is_empty());
+}
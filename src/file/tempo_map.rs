@@ -0,0 +1,423 @@
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+
+#[doc = r#"
+Converts between ticks, microseconds, and bars/beats of a MIDI file,
+honoring every tempo and time signature change.
+
+# Overview
+Ticks in a MIDI file are interpreted using the file's [`Timing`]:
+
+- [`Timing::TicksPerQuarterNote`]: the length of a tick depends on the
+  current [`Tempo`], so each tempo change alters the rate at which ticks elapse.
+- [`Timing::Smpte`]: ticks are a fixed subdivision of a second. Tempo changes
+  do not affect the length of a tick, but still define where beats fall.
+
+If no tempo is provided at tick 0, the default of 120 beats per minute is used.
+If no time signature is provided at tick 0, 4/4 is used.
+
+Bars and beats are zero-indexed. A beat is the denominator of the current
+time signature (i.e. an eighth note in 6/8). A time signature change
+that occurs in the middle of a bar begins a new bar.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let mut map = TempoMap::new(Timing::new_ticks_per_quarter_note(96));
+// 120bpm until tick 192, then 60bpm
+map.insert_tempo(192, Tempo::new(1_000_000));
+
+assert_eq!(map.ticks_to_micros(96), 500_000);
+assert_eq!(map.ticks_to_micros(192), 1_000_000);
+assert_eq!(map.ticks_to_micros(288), 2_000_000);
+assert_eq!(map.micros_to_ticks(1_500_000), 240);
+
+let position = map.ticks_to_bar_beat(480);
+assert_eq!(position.bar, 1);
+assert_eq!(position.beat, 1.);
+assert_eq!(map.bar_beat_to_ticks(position), 480);
+```
+"#]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoMap {
+    timing: Timing,
+    /// Numerator of the microseconds in a tick. For ticks per quarter note,
+    /// this is multiplied by the tempo.
+    micros_num: u64,
+    /// Denominator of the microseconds in a tick
+    micros_den: u64,
+    tempos: Vec<TempoSegment>,
+    time_signatures: Vec<SignatureSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TempoSegment {
+    tick: u32,
+    /// The microseconds elapsed before this segment, multiplied by `micros_den`
+    scaled_micros: u128,
+    /// The quarter notes elapsed before this segment
+    quarter_notes: f64,
+    tempo: Tempo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SignatureSegment {
+    tick: u32,
+    quarter_notes: f64,
+    bar: u32,
+    time_signature: TimeSignature,
+}
+
+/// A position in terms of bars and beats. Both are zero-indexed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarBeat {
+    /// The bar, starting from 0
+    pub bar: u32,
+    /// The beat within the bar, starting from 0
+    pub beat: f64,
+}
+
+impl BarBeat {
+    /// Create a new position from a bar and beat
+    pub const fn new(bar: u32, beat: f64) -> Self {
+        Self { bar, beat }
+    }
+}
+
+impl TempoMap {
+    /// Create a tempo map with the default tempo and time signature
+    pub fn new(timing: Timing) -> Self {
+        let (micros_num, micros_den) = match timing {
+            Timing::TicksPerQuarterNote(tpqn) => {
+                // the tempo is multiplied in per segment
+                (1, tpqn.ticks_per_quarter_note().max(1) as u64)
+            }
+            Timing::Smpte(smpte) => {
+                let ticks_per_frame = smpte.ticks_per_frame().max(1) as u64;
+                match smpte.fps() {
+                    // 30_000 frames / 1001 seconds
+                    SmpteFps::TwentyNine => (100_100, 3 * ticks_per_frame),
+                    fps => (1_000_000, fps.as_division() as u64 * ticks_per_frame),
+                }
+            }
+        };
+        let mut map = Self {
+            timing,
+            micros_num,
+            micros_den,
+            tempos: Vec::new(),
+            time_signatures: Vec::new(),
+        };
+        map.insert_tempo(0, Tempo::default());
+        map.insert_time_signature(0, TimeSignature::default());
+        map
+    }
+
+    /// Create a tempo map from every tempo and time signature meta message of the tracks
+    pub fn from_tracks<'a, 't: 'a, I>(timing: Timing, tracks: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Track<'t>>,
+    {
+        let mut map = Self::new(timing);
+        for track in tracks {
+            for meta in track.meta_events() {
+                match meta.event() {
+                    MetaMessage::Tempo(tempo) => map.insert_tempo(meta.accumulated_ticks(), *tempo),
+                    MetaMessage::TimeSignature(sig) => {
+                        map.insert_time_signature(meta.accumulated_ticks(), *sig)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        map
+    }
+
+    /// The timing used to interpret ticks
+    pub const fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    /// Set the tempo from a tick onward. Replaces any tempo set at the same tick.
+    pub fn insert_tempo(&mut self, tick: u32, tempo: Tempo) {
+        let segment = TempoSegment {
+            tick,
+            scaled_micros: 0,
+            quarter_notes: 0.,
+            tempo,
+        };
+        match self.tempos.binary_search_by_key(&tick, |s| s.tick) {
+            Ok(i) => self.tempos[i] = segment,
+            Err(i) => self.tempos.insert(i, segment),
+        }
+        self.recalculate();
+    }
+
+    /// Set the time signature from a tick onward. Replaces any time signature set at the same tick.
+    pub fn insert_time_signature(&mut self, tick: u32, time_signature: TimeSignature) {
+        let segment = SignatureSegment {
+            tick,
+            quarter_notes: 0.,
+            bar: 0,
+            time_signature,
+        };
+        match self.time_signatures.binary_search_by_key(&tick, |s| s.tick) {
+            Ok(i) => self.time_signatures[i] = segment,
+            Err(i) => self.time_signatures.insert(i, segment),
+        }
+        self.recalculate();
+    }
+
+    /// Get the tempo at a tick
+    pub fn tempo_at(&self, tick: u32) -> Tempo {
+        self.tempo_segment(tick).tempo
+    }
+
+    /// Get the time signature at a tick
+    pub fn time_signature_at(&self, tick: u32) -> TimeSignature {
+        let i = self
+            .time_signatures
+            .partition_point(|s| s.tick <= tick)
+            .saturating_sub(1);
+        self.time_signatures[i].time_signature
+    }
+
+    /// Get the number of microseconds from the start of the track to the tick
+    pub fn ticks_to_micros(&self, tick: u32) -> u64 {
+        let segment = self.tempo_segment(tick);
+        ((segment.scaled_micros + self.scaled_micros_for(segment, tick - segment.tick))
+            / self.micros_den as u128) as u64
+    }
+
+    /// Get the tick at (or immediately before) some number of microseconds from the start of the track
+    pub fn micros_to_ticks(&self, micros: u64) -> u32 {
+        let scaled = micros as u128 * self.micros_den as u128;
+        let i = self
+            .tempos
+            .partition_point(|s| s.scaled_micros <= scaled)
+            .saturating_sub(1);
+        let segment = &self.tempos[i];
+        let per_tick = self.scaled_micros_for(segment, 1);
+        let ticks = (scaled - segment.scaled_micros) / per_tick.max(1);
+        segment
+            .tick
+            .saturating_add(ticks.min(u32::MAX as u128) as u32)
+    }
+
+    /// Get the number of quarter notes from the start of the track to the tick
+    pub fn ticks_to_quarter_notes(&self, tick: u32) -> f64 {
+        let segment = self.tempo_segment(tick);
+        segment.quarter_notes + (tick - segment.tick) as f64 * self.quarter_notes_per_tick(segment)
+    }
+
+    /// Get the tick nearest to some number of quarter notes from the start of the track
+    pub fn quarter_notes_to_ticks(&self, quarter_notes: f64) -> u32 {
+        let i = self
+            .tempos
+            .partition_point(|s| s.quarter_notes <= quarter_notes)
+            .saturating_sub(1);
+        let segment = &self.tempos[i];
+        let ticks = (quarter_notes - segment.quarter_notes) / self.quarter_notes_per_tick(segment);
+        segment.tick.saturating_add(round(ticks))
+    }
+
+    /// Get the bar and beat of a tick
+    pub fn ticks_to_bar_beat(&self, tick: u32) -> BarBeat {
+        let quarter_notes = self.ticks_to_quarter_notes(tick);
+        let i = self
+            .time_signatures
+            .partition_point(|s| s.tick <= tick)
+            .saturating_sub(1);
+        let segment = &self.time_signatures[i];
+        let sig = &segment.time_signature;
+
+        let beats = (quarter_notes - segment.quarter_notes) / quarter_notes_per_beat(sig);
+        let beats_per_bar = sig.num().max(1) as f64;
+        let bars = (beats / beats_per_bar) as u32;
+        BarBeat {
+            bar: segment.bar + bars,
+            beat: beats - bars as f64 * beats_per_bar,
+        }
+    }
+
+    /// Get the tick nearest to a bar and beat
+    pub fn bar_beat_to_ticks(&self, position: BarBeat) -> u32 {
+        let i = self
+            .time_signatures
+            .partition_point(|s| s.bar <= position.bar)
+            .saturating_sub(1);
+        let segment = &self.time_signatures[i];
+        let sig = &segment.time_signature;
+        let beats = (position.bar - segment.bar.min(position.bar)) as f64 * sig.num() as f64
+            + position.beat;
+        self.quarter_notes_to_ticks(segment.quarter_notes + beats * quarter_notes_per_beat(sig))
+    }
+
+    fn tempo_segment(&self, tick: u32) -> &TempoSegment {
+        let i = self
+            .tempos
+            .partition_point(|s| s.tick <= tick)
+            .saturating_sub(1);
+        &self.tempos[i]
+    }
+
+    /// Microseconds multiplied by `micros_den` for some number of ticks in a segment
+    fn scaled_micros_for(&self, segment: &TempoSegment, ticks: u32) -> u128 {
+        let per_tick = match self.timing {
+            Timing::TicksPerQuarterNote(_) => segment.tempo.micros_per_quarter_note() as u64,
+            Timing::Smpte(_) => self.micros_num,
+        };
+        ticks as u128 * per_tick as u128
+    }
+
+    fn quarter_notes_per_tick(&self, segment: &TempoSegment) -> f64 {
+        match self.timing {
+            Timing::TicksPerQuarterNote(_) => 1. / self.micros_den as f64,
+            Timing::Smpte(_) => {
+                self.micros_num as f64
+                    / self.micros_den as f64
+                    / segment.tempo.micros_per_quarter_note().max(1) as f64
+            }
+        }
+    }
+
+    /// Recalculates the position of every segment. The first segments always begin at tick 0.
+    fn recalculate(&mut self) {
+        if self.tempos.first().is_some_and(|s| s.tick != 0) {
+            self.tempos.insert(
+                0,
+                TempoSegment {
+                    tick: 0,
+                    scaled_micros: 0,
+                    quarter_notes: 0.,
+                    tempo: Tempo::default(),
+                },
+            );
+        }
+        for i in 1..self.tempos.len() {
+            let prev = &self.tempos[i - 1];
+            let ticks = self.tempos[i].tick - prev.tick;
+            let scaled_micros = prev.scaled_micros + self.scaled_micros_for(prev, ticks);
+            let quarter_notes =
+                prev.quarter_notes + ticks as f64 * self.quarter_notes_per_tick(prev);
+            let segment = &mut self.tempos[i];
+            segment.scaled_micros = scaled_micros;
+            segment.quarter_notes = quarter_notes;
+        }
+
+        if self.time_signatures.first().is_some_and(|s| s.tick != 0) {
+            self.time_signatures.insert(
+                0,
+                SignatureSegment {
+                    tick: 0,
+                    quarter_notes: 0.,
+                    bar: 0,
+                    time_signature: TimeSignature::default(),
+                },
+            );
+        }
+        for i in 1..self.time_signatures.len() {
+            let quarter_notes = self.ticks_to_quarter_notes(self.time_signatures[i].tick);
+            let prev = &self.time_signatures[i - 1];
+            let sig = &prev.time_signature;
+            let bars = (quarter_notes - prev.quarter_notes)
+                / (quarter_notes_per_beat(sig) * sig.num().max(1) as f64);
+            let bar = prev.bar + ceil(bars);
+            let segment = &mut self.time_signatures[i];
+            segment.quarter_notes = quarter_notes;
+            segment.bar = bar;
+        }
+    }
+}
+
+impl ParsedMidiFile<'_> {
+    /// Create a [`TempoMap`] from the tempo and time signature events of every track.
+    ///
+    /// Note that tracks of a [`FormatType::SequentiallyIndependent`] file each have
+    /// their own tempo map. See [`TempoMap::from_tracks`].
+    pub fn tempo_map(&self) -> TempoMap {
+        TempoMap::from_tracks(*self.header().timing(), self.tracks())
+    }
+}
+
+fn quarter_notes_per_beat(sig: &TimeSignature) -> f64 {
+    // the denominator is a negative power of two. 2 is a quarter note.
+    4. / (1u64 << sig.den().min(63)) as f64
+}
+
+/// Rounds a non-negative float to the nearest integer
+fn round(v: f64) -> u32 {
+    if v <= 0. { 0 } else { (v + 0.5) as u32 }
+}
+
+/// Rounds a non-negative float up, tolerating floating point error
+fn ceil(v: f64) -> u32 {
+    let truncated = v as u32;
+    if v - truncated as f64 > 1e-9 {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+#[test]
+fn tempo_changes_in_ticks_per_quarter_note() {
+    let mut map = TempoMap::new(Timing::new_ticks_per_quarter_note(480));
+    assert_eq!(map.ticks_to_micros(480), 500_000);
+
+    map.insert_tempo(960, Tempo::new(250_000));
+    map.insert_tempo(1920, Tempo::new(1_000_000));
+
+    assert_eq!(map.ticks_to_micros(960), 1_000_000);
+    assert_eq!(map.ticks_to_micros(1440), 1_250_000);
+    assert_eq!(map.ticks_to_micros(1920), 1_500_000);
+    assert_eq!(map.ticks_to_micros(2400), 2_500_000);
+
+    // ticks_to_micros rounds down to the microsecond
+    for tick in [0, 1, 479, 960, 1000, 1920, 2400, 10_000] {
+        assert_eq!(map.micros_to_ticks(map.ticks_to_micros(tick) + 1), tick);
+    }
+    assert_eq!(map.tempo_at(1919).micros_per_quarter_note(), 250_000);
+}
+
+#[test]
+fn smpte_ignores_tempo_for_micros() {
+    let mut map = TempoMap::new(Timing::new_smpte(
+        SmpteFps::TwentyFive,
+        DataByte::new_unchecked(40),
+    ));
+    map.insert_tempo(500, Tempo::new(250_000));
+
+    // 25 fps * 40 ticks = 1ms per tick
+    assert_eq!(map.ticks_to_micros(1000), 1_000_000);
+    assert_eq!(map.micros_to_ticks(1_000_000), 1000);
+
+    // 500ms at 120bpm is one quarter note, 500ms at 240bpm is two
+    assert_eq!(map.ticks_to_quarter_notes(1000), 3.);
+
+    let map = TempoMap::new(Timing::new_smpte(
+        SmpteFps::TwentyNine,
+        DataByte::new_unchecked(100),
+    ));
+    // 3000 ticks is 30 drop frames, or 1.001 seconds
+    assert_eq!(map.ticks_to_micros(3000), 1_001_000);
+}
+
+#[test]
+fn time_signature_changes() {
+    let mut map = TempoMap::new(Timing::new_ticks_per_quarter_note(96));
+    // two bars of 4/4, then 6/8
+    map.insert_time_signature(768, TimeSignature::new_from_bytes([6, 3, 36, 8]));
+
+    assert_eq!(map.ticks_to_bar_beat(384), BarBeat::new(1, 0.));
+    assert_eq!(map.ticks_to_bar_beat(768), BarBeat::new(2, 0.));
+    // an eighth note is a beat in 6/8
+    assert_eq!(map.ticks_to_bar_beat(768 + 48 * 7), BarBeat::new(3, 1.));
+    assert_eq!(map.bar_beat_to_ticks(BarBeat::new(3, 1.)), 768 + 48 * 7);
+
+    // a change in the middle of a bar starts a new bar
+    map.insert_time_signature(96, TimeSignature::new_from_bytes([3, 2, 24, 8]));
+    assert_eq!(map.ticks_to_bar_beat(96), BarBeat::new(1, 0.));
+}
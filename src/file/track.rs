@@ -4,16 +4,27 @@ use crate::{
     channel::Channel,
     events::LiveEvent,
     message::Ticked,
-    prelude::{BytesText, SmpteOffset, Tempo, TimeSignature, TrackEvent, TrackMessage},
+    prelude::{
+        BytesText, MetaMessage, SmpteOffset, SystemCommonMessage, Tempo, TimeSignature, TrackEvent,
+        TrackMessage,
+    },
 };
 
 #[doc = r#"
 A set of track events
+
+Channel voice and system exclusive messages are held as [`LiveEvent`]s,
+while every [`MetaMessage`] (including mid-track tempo changes, lyrics,
+markers and the alike) is kept separately. A summary of the meta messages
+is provided by [`Track::info`].
 "#]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Track<'a> {
     info: TrackInfo<'a>,
     events: Vec<Ticked<LiveEvent<'a>>>,
+    meta_events: Vec<Ticked<MetaMessage<'a>>>,
+    /// For each meta message, the number of live events that came before it in the track
+    meta_positions: Vec<usize>,
 }
 
 impl<'a> Track<'a> {
@@ -21,6 +32,8 @@ impl<'a> Track<'a> {
     pub fn new(events: Vec<TrackEvent<'a>>) -> Self {
         let mut info = TrackInfo::default();
         let mut track_events = Vec::with_capacity(events.len());
+        let mut meta_events = Vec::new();
+        let mut meta_positions = Vec::new();
 
         let mut time_accumulated = None;
 
@@ -38,7 +51,9 @@ impl<'a> Track<'a> {
                 TrackMessage::ChannelVoice(cvm) => cvm.into(),
                 TrackMessage::SystemExclusive(sysex) => sysex.into(),
                 TrackMessage::Meta(meta) => {
-                    meta.adjust_track_info(&mut info);
+                    meta.clone().adjust_track_info(&mut info);
+                    meta_events.push(Ticked::new(accumulated_ticks, meta));
+                    meta_positions.push(track_events.len());
                     continue;
                 }
             };
@@ -56,6 +71,8 @@ impl<'a> Track<'a> {
         Self {
             info,
             events: track_events,
+            meta_events,
+            meta_positions,
         }
     }
 
@@ -67,6 +84,70 @@ impl<'a> Track<'a> {
     pub fn events(&self) -> &[Ticked<LiveEvent<'a>>] {
         self.events.as_slice()
     }
+
+    /// Get every meta message of the track, in order, with its accumulated ticks.
+    ///
+    /// This includes the track's [`MetaMessage::EndOfTrack`], if it has one.
+    pub fn meta_events(&self) -> &[Ticked<MetaMessage<'a>>] {
+        self.meta_events.as_slice()
+    }
+
+    /// Converts the track back into a list of [`TrackEvent`]s with delta ticks.
+    ///
+    /// Meta messages and live events are kept in the order they were read,
+    /// except for [`MetaMessage::EndOfTrack`], which is always placed last.
+    ///
+    /// Live events that cannot be found in a MIDI file (such as realtime messages)
+    /// are skipped.
+    pub fn track_events(&self) -> Vec<TrackEvent<'a>> {
+        let mut events = Vec::with_capacity(self.events.len() + self.meta_events.len());
+        let mut end_of_track = None;
+
+        let mut metas = self
+            .meta_events
+            .iter()
+            .zip(self.meta_positions.iter().copied())
+            .peekable();
+        let mut lives = self.events.iter().enumerate().peekable();
+        let mut last_tick = 0;
+        let mut push = |tick: u32, message: TrackMessage<'a>| {
+            events.push(TrackEvent::new(tick.saturating_sub(last_tick), message));
+            last_tick = tick;
+        };
+
+        loop {
+            let next_meta = metas.peek().map(|(_, position)| *position);
+            let next_live = lives.peek().map(|(index, _)| *index);
+            let take_meta = match (next_meta, next_live) {
+                (Some(meta_position), Some(live_index)) => meta_position <= live_index,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if take_meta {
+                let (meta, _) = metas.next().unwrap();
+                if meta.event() == &MetaMessage::EndOfTrack {
+                    end_of_track = Some(meta.accumulated_ticks());
+                    continue;
+                }
+                push(meta.accumulated_ticks(), meta.event().clone().into());
+            } else {
+                let (_, live) = lives.next().unwrap();
+                let message: TrackMessage<'a> = match live.event() {
+                    LiveEvent::ChannelVoice(cvm) => (*cvm).into(),
+                    LiveEvent::SysCommon(SystemCommonMessage::SystemExclusive(sysex)) => {
+                        sysex.clone().into()
+                    }
+                    _ => continue,
+                };
+                push(live.accumulated_ticks(), message);
+            }
+        }
+        if let Some(tick) = end_of_track {
+            push(tick, MetaMessage::EndOfTrack.into());
+        }
+        events
+    }
 }
 
 /// Provides information about the track
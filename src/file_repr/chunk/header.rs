@@ -1,4 +1,8 @@
-use crate::{prelude::*, reader::ReaderError};
+use crate::{
+    prelude::*,
+    reader::ReaderError,
+    writer::{MidiSink, WriteResult, Writer},
+};
 
 #[doc = r#"
 The header chunk at the beginning of the file specifies some basic information about
@@ -42,6 +46,11 @@ pub struct RawHeaderChunk {
 }
 
 impl RawHeaderChunk {
+    /// Create a new header chunk from a format and timing
+    pub const fn new(format: RawFormat, timing: Timing) -> Self {
+        Self { format, timing }
+    }
+
     /// Assumes that the chunk type bytes ("MThd") have ALREADY been read
     pub(crate) fn read<'slc, 'r, R>(reader: &'r mut Reader<R>) -> ReadResult<Self>
     where
@@ -75,6 +84,20 @@ impl RawHeaderChunk {
         Ok(Self { format, timing })
     }
 
+    /// Writes the chunk type bytes ("MThd") as well as the body of the header
+    pub(crate) fn write<W: MidiSink>(&self, writer: &mut Writer<W>) -> WriteResult<()> {
+        let format: u16 = match self.format {
+            RawFormat::SingleMultiChannel => 0,
+            RawFormat::Simultaneous(_) => 1,
+            RawFormat::SequentiallyIndependent(_) => 2,
+        };
+        writer.write_bytes(b"MThd")?;
+        writer.write_bytes(&self.len().to_be_bytes())?;
+        writer.write_bytes(&format.to_be_bytes())?;
+        writer.write_bytes(&self.num_tracks().to_be_bytes())?;
+        writer.write_bytes(&self.timing.to_bytes())
+    }
+
     /// Get the length of the header. This is ALWAYS 6.
     #[allow(clippy::len_without_is_empty)]
     pub const fn len(&self) -> u32 {
@@ -193,6 +216,22 @@ impl Timing {
         })
     }
 
+    /// Returns the `<division>` word of the header as big endian bytes
+    pub const fn to_bytes(&self) -> [u8; 2] {
+        match self {
+            Self::TicksPerQuarterNote(t) => t.inner,
+            Self::Smpte(smpte) => {
+                let fps: i8 = match smpte.fps {
+                    SmpteFps::TwentyFour => -24,
+                    SmpteFps::TwentyFive => -25,
+                    SmpteFps::TwentyNine => -29,
+                    SmpteFps::Thirty => -30,
+                };
+                [fps as u8, smpte.ticks_per_frame.0]
+            }
+        }
+    }
+
     pub(crate) fn read<'slc, 'r, R: MidiSource<'slc>>(
         reader: &'r mut Reader<R>,
     ) -> ReadResult<Self> {
@@ -296,3 +335,21 @@ fn read_midi_header_smpte() {
     assert_eq!(smpte.ticks_per_frame(), 80);
     assert_eq!(result.num_tracks(), 1);
 }
+
+#[test]
+fn write_midi_header_smpte() {
+    let bytes = [
+        b'M', b'T', b'h', b'd', //chunk type
+        0x00, 0x00, 0x00, 0x06, //length
+        0x00, 0x01, //format
+        0x00, 0x03, //num_tracks
+        0xE3, 0x50, //timing
+    ];
+    let mut reader = Reader::from_byte_slice(&bytes[4..]);
+    let header = RawHeaderChunk::read(&mut reader).unwrap();
+
+    let mut writer = Writer::new(alloc::vec::Vec::new());
+    header.write(&mut writer).unwrap();
+
+    assert_eq!(writer.into_inner(), bytes);
+}
@@ -16,12 +16,20 @@ pub struct TrackChunkHeader {
 }
 
 impl TrackChunkHeader {
+    /// Create a new track chunk header with the length of its body.
+    ///
+    /// Note that the [`Writer`](crate::writer::Writer) will compute
+    /// the length of the track body itself.
+    pub const fn new(length: u32) -> Self {
+        Self { length }
+    }
+
     /// Assumes that the chunk type bytes (`"MTrk"`) have ALREADY been read
     pub(crate) fn read<'slc, 'r, R>(reader: &'r mut Reader<R>) -> ReadResult<Self>
     where
         R: MidiSource<'slc>,
     {
-        let length = u32::from_be_bytes(reader.read_exact_size()?);
+        let length = reader.read_chunk_length()?;
 
         Ok(Self { length })
     }
@@ -45,7 +53,7 @@ impl<'a> RawTrackChunk<'a> {
         R: MidiSource<'slc>,
         'slc: 'a,
     {
-        let length = u32::from_be_bytes(reader.read_exact_size()?);
+        let length = reader.read_chunk_length()?;
 
         let track_event_bytes = reader.read_exact(length as usize)?;
 
@@ -1,6 +1,9 @@
 use alloc::borrow::Cow;
 
-use crate::reader::{MidiSource, ReadResult, Reader};
+use crate::{
+    reader::{MidiSource, ReadResult, Reader},
+    writer::{MidiSink, WriteResult, Writer, WriterErrorKind},
+};
 
 #[doc = r#"
 Identifies a chunk of a MIDI file that cannot be parsed.
@@ -16,6 +19,18 @@ pub struct UnknownChunk<'a> {
 }
 
 impl<'a> UnknownChunk<'a> {
+    /// Create a new chunk from a four byte name and its body
+    pub fn new<N, D>(name: N, data: D) -> Self
+    where
+        N: Into<Cow<'a, [u8]>>,
+        D: Into<Cow<'a, [u8]>>,
+    {
+        Self {
+            name: name.into(),
+            inner: data.into(),
+        }
+    }
+
     /// Place the bytes of an unknown chunk
     pub(crate) fn read<'slc, 'r, R>(
         name: Cow<'a, [u8]>,
@@ -25,11 +40,31 @@ impl<'a> UnknownChunk<'a> {
         R: MidiSource<'slc>,
         'slc: 'a,
     {
-        let length = u32::from_be_bytes(reader.read_exact_size()?);
+        let length = reader.read_chunk_length()?;
         let data = reader.read_exact(length as usize)?;
         Ok(Self { name, inner: data })
     }
 
+    /// Writes the name, length and body of the chunk
+    pub(crate) fn write<W: MidiSink>(&self, writer: &mut Writer<W>) -> WriteResult<()> {
+        let Ok(length) = u32::try_from(self.inner.len()) else {
+            return Err(writer.error(WriterErrorKind::ChunkTooLarge(self.inner.len())));
+        };
+        writer.write_bytes(&self.name)?;
+        writer.write_bytes(&length.to_be_bytes())?;
+        writer.write_bytes(&self.inner)
+    }
+
+    /// Get the name of the chunk
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Get the body of the chunk
+    pub fn data(&self) -> &[u8] {
+        &self.inner
+    }
+
     /// Get the length of the unknown chunk
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -28,6 +28,16 @@ impl RawFormat {
         Self::SingleMultiChannel
     }
 
+    /// Create a [`RawFormat::Simultaneous`] with the given number of tracks
+    pub const fn simultaneous(num_tracks: u16) -> Self {
+        Self::Simultaneous(num_tracks.to_be_bytes())
+    }
+
+    /// Create a [`RawFormat::SequentiallyIndependent`] with the given number of tracks
+    pub const fn sequentially_independent(num_tracks: u16) -> Self {
+        Self::SequentiallyIndependent(num_tracks.to_be_bytes())
+    }
+
     /// Create a [`Format::Simultaneous`]
     pub(crate) const fn simultaneous_from_byte_slice(bytes: [u8; 2]) -> Self {
         Self::Simultaneous(bytes)
@@ -38,6 +38,11 @@ impl KeySignature {
     pub fn num_flats(&self) -> u8 {
         self.sharp_flat_count().neg().min(0).unsigned_abs()
     }
+    /// The key signature as the two bytes found in a MIDI file
+    pub const fn to_bytes(&self) -> [u8; 2] {
+        self.0
+    }
+
     /// True if the key is identified as minor
     pub const fn minor_key(&self) -> bool {
         self.0[1] == 1
@@ -16,7 +16,11 @@ pub use text::*;
 mod smpte_offset;
 pub use smpte_offset::*;
 
-use crate::{prelude::*, reader::ReaderError};
+use crate::{
+    prelude::*,
+    reader::ReaderError,
+    writer::{MidiSink, WriteResult, Writer},
+};
 /// A "meta message", as defined by the SMF spec.
 /// These are in tracks.
 /// These events carry metadata about the track, such as tempo, time signature, copyright, etc...
@@ -110,8 +114,7 @@ impl<'a> MetaMessage<'a> {
                 if data.len() != 1 {
                     return Err(inv_data(reader, ParseError::port(data.len())));
                 }
-                let port = reader.read_next()?;
-                MetaMessage::MidiPort(port)
+                MetaMessage::MidiPort(data[0])
             }
             0x2F => MetaMessage::EndOfTrack,
             0x51 => {
@@ -146,15 +149,96 @@ impl<'a> MetaMessage<'a> {
         })
     }
 
+    /// Returns the identifying type byte of the meta message (the byte following `0xFF`)
+    pub const fn type_byte(&self) -> u8 {
+        match self {
+            MetaMessage::TrackNumber(_) => 0x00,
+            MetaMessage::Text(_) => 0x01,
+            MetaMessage::Copyright(_) => 0x02,
+            MetaMessage::TrackName(_) => 0x03,
+            MetaMessage::InstrumentName(_) => 0x04,
+            MetaMessage::Lyric(_) => 0x05,
+            MetaMessage::Marker(_) => 0x06,
+            MetaMessage::CuePoint(_) => 0x07,
+            MetaMessage::ProgramName(_) => 0x08,
+            MetaMessage::DeviceName(_) => 0x09,
+            MetaMessage::MidiChannel(_) => 0x20,
+            MetaMessage::MidiPort(_) => 0x21,
+            MetaMessage::EndOfTrack => 0x2F,
+            MetaMessage::Tempo(_) => 0x51,
+            MetaMessage::SmpteOffset(_) => 0x54,
+            MetaMessage::TimeSignature(_) => 0x58,
+            MetaMessage::KeySignature(_) => 0x59,
+            MetaMessage::SequencerSpecific(_) => 0x7F,
+            MetaMessage::Unknown(type_byte, _) => *type_byte,
+        }
+    }
+
+    /// Writes `0xFF`, the type byte, the varlen length, and the data of the message
+    pub(crate) fn write<W: MidiSink>(&self, writer: &mut Writer<W>) -> WriteResult<()> {
+        let channel;
+        let port;
+        let tempo;
+        let smpte;
+        let time_sig;
+        let key_sig;
+        let data: &[u8] = match self {
+            MetaMessage::TrackNumber(d)
+            | MetaMessage::CuePoint(d)
+            | MetaMessage::SequencerSpecific(d)
+            | MetaMessage::Unknown(_, d) => d,
+            MetaMessage::Text(t)
+            | MetaMessage::Copyright(t)
+            | MetaMessage::TrackName(t)
+            | MetaMessage::InstrumentName(t)
+            | MetaMessage::Lyric(t)
+            | MetaMessage::Marker(t)
+            | MetaMessage::ProgramName(t)
+            | MetaMessage::DeviceName(t) => t.as_bytes(),
+            MetaMessage::MidiChannel(c) => {
+                channel = [c.to_byte()];
+                &channel
+            }
+            MetaMessage::MidiPort(p) => {
+                port = [*p];
+                &port
+            }
+            MetaMessage::EndOfTrack => &[],
+            MetaMessage::Tempo(t) => {
+                tempo = t.to_bytes();
+                &tempo
+            }
+            MetaMessage::SmpteOffset(o) => {
+                smpte = o.to_bytes();
+                &smpte
+            }
+            MetaMessage::TimeSignature(t) => {
+                time_sig = t.to_bytes();
+                &time_sig
+            }
+            MetaMessage::KeySignature(k) => {
+                key_sig = k.to_bytes();
+                &key_sig
+            }
+        };
+        writer.write_bytes(&[0xFF, self.type_byte()])?;
+        writer.write_varlen(data.len() as u32)?;
+        writer.write_bytes(data)
+    }
+
     /// Mutates the data of a track
     pub fn adjust_track_info(self, info: &mut TrackInfo<'a>) {
         match self {
+            MetaMessage::TrackNumber(number) if number.len() == 2 => {
+                info.track_info = Some(u16::from_be_bytes([number[0], number[1]]));
+            }
             MetaMessage::TrackName(name) => {
                 info.name = Some(name);
             }
             MetaMessage::DeviceName(device) => info.device = Some(device),
             MetaMessage::MidiChannel(channel) => info.channel = Some(channel),
             MetaMessage::Tempo(tempo) => info.tempo = tempo,
+            MetaMessage::TimeSignature(time_sig) => info.time_signature = time_sig,
             MetaMessage::SmpteOffset(offset) => info.smpte_offset = Some(offset),
             _ => {}
         }
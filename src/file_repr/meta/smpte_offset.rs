@@ -36,6 +36,23 @@ impl SmpteOffset {
             + ((self.subframe as u32) * 10_000) as f64 / self.fps.as_f64()
     }
 
+    /// The offset as the five bytes found in a MIDI file
+    pub const fn to_bytes(&self) -> [u8; 5] {
+        let rate: u8 = match self.fps {
+            SmpteFps::TwentyFour => 0,
+            SmpteFps::TwentyFive => 1,
+            SmpteFps::TwentyNine => 2,
+            SmpteFps::Thirty => 3,
+        };
+        [
+            (rate << 5) | (self.hour & 0b0001_1111),
+            self.minute,
+            self.second,
+            self.frame,
+            self.subframe,
+        ]
+    }
+
     /// Parse the offset given some slice with a length of 5
     pub const fn parse(data: &[u8]) -> Result<Self, SmpteError> {
         if data.len() != 5 {
@@ -109,3 +126,10 @@ fn parse_invalid_smpte_offset() {
     let err = SmpteOffset::parse(&bytes).unwrap_err();
     assert_eq!(err, SmpteError::MinuteOffset(80));
 }
+
+#[test]
+fn smpte_offset_to_bytes() {
+    let bytes = [0x41, 0x17, 0x2D, 0x0C, 0x22];
+    let offset = SmpteOffset::parse(&bytes).unwrap();
+    assert_eq!(offset.to_bytes(), bytes);
+}
@@ -26,10 +26,23 @@ impl Tempo {
         Self(u32::from_be_bytes(val))
     }
 
+    /// Create a tempo from the count of microseconds per midi quarter-note.
+    ///
+    /// The tempo is stored as a 24 bit number, so values above `0xFFFFFF` are truncated.
+    pub const fn new(micros_per_quarter_note: u32) -> Self {
+        Self(micros_per_quarter_note & 0x00FF_FFFF)
+    }
+
     /// The count of microseconds per midi quarter-note
     pub const fn micros_per_quarter_note(&self) -> u32 {
         self.0
     }
+
+    /// The tempo as the three big endian bytes found in a MIDI file
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        let [_, a, b, c] = self.0.to_be_bytes();
+        [a, b, c]
+    }
 }
 
 #[test]
@@ -18,6 +18,11 @@ impl<'a> BytesText<'a> {
         }
     }
 
+    /// Get the underlying bytes of the text
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+
     /// Get a mutable reference to the underlying string
     pub fn to_mut(&mut self) -> Result<&mut str, ParseError> {
         let inner_mut = self.inner.to_mut();
@@ -58,6 +58,10 @@ impl TimeSignature {
     pub const fn new_from_bytes(v: [u8; 4]) -> Self {
         Self(v)
     }
+    /// The time signature as the four bytes found in a MIDI file
+    pub const fn to_bytes(&self) -> [u8; 4] {
+        self.0
+    }
     /// numerator of the time signature
     pub const fn num(&self) -> u8 {
         self.0[0]
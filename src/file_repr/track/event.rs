@@ -1,6 +1,9 @@
 use core::fmt::{self, Debug};
 
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    writer::{MidiSink, WriteResult, Writer, WriterErrorKind},
+};
 
 #[doc = r#"
 Identifies some event emitted by a track in a MIDI file.
@@ -89,6 +92,30 @@ impl<'a> TrackEvent<'a> {
         })
     }
 
+    /// Writes the delta ticks and message into the writer's open track
+    pub(crate) fn write<W: MidiSink>(&self, writer: &mut Writer<W>) -> WriteResult<()> {
+        if !writer.in_track() {
+            return Err(writer.error(WriterErrorKind::NotInTrack));
+        }
+        writer.write_varlen(self.delta_ticks)?;
+        match &self.event {
+            TrackMessage::ChannelVoice(msg) => msg.write(writer)?,
+            TrackMessage::SystemExclusive(msg) => {
+                writer.clear_running_status();
+                msg.write(writer)?;
+            }
+            TrackMessage::Meta(msg) => {
+                writer.clear_running_status();
+                msg.write(writer)?;
+            }
+        }
+        writer.set_track_ended(matches!(
+            self.event,
+            TrackMessage::Meta(MetaMessage::EndOfTrack)
+        ));
+        Ok(())
+    }
+
     /// Get the difference in ticks from the last event
     ///
     /// The actual value should be interpreted by the MIDI file's
@@ -20,6 +20,16 @@ pub mod file_repr;
 
 pub mod reader;
 
+pub mod sequencer;
+
+#[cfg(feature = "synth")]
+pub mod synth;
+
+#[cfg(feature = "render")]
+pub mod render;
+
+pub mod writer;
+
 mod pitch_bend;
 pub use pitch_bend::*;
 
@@ -36,6 +46,9 @@ pub use key::*;
 mod controller;
 pub use controller::*;
 
+mod parameter;
+pub use parameter::*;
+
 mod byte;
 pub use byte::*;
 
@@ -55,9 +68,10 @@ pub mod prelude {
         Common re-exports when working with `midix`
     "#]
     pub use crate::{
-        ChunkError, Controller, CowExt, DataByte, Dynamic, FileError, HeaderError, Key, Note,
-        Octave, ParseError, PitchBend, Program, SmpteError, SongPositionPointer, StatusByte,
-        TrackError, Velocity,
+        ChunkError, Controller, ControllerTracker, CowExt, DataByte, Dynamic, EncodeError,
+        FileError, HeaderError, Key, MidiMessageBytes, Note, Octave, Parameter, ParameterDecoder,
+        ParameterNumber, ParseError, PitchBend, Program, RiffError, SmpteError,
+        SongPositionPointer, StatusByte, TrackError, Velocity,
         channel::*,
         events::*,
         file::*,
@@ -66,7 +80,19 @@ pub mod prelude {
         message::{MidiMessage, channel::*, system::*, time::*},
     };
 
-    pub use crate::reader::{MidiSource, ReadResult, Reader};
+    #[cfg(feature = "std")]
+    pub use crate::reader::{AsyncStreamReader, StreamReader};
+    pub use crate::reader::{MidiSource, ReadResult, Reader, ReaderOptions};
+
+    pub use crate::sequencer::{EventsBetween, PlaybackPosition, SequencedEvent, Sequencer};
+
+    pub use crate::writer::{MidiSink, WriteResult, Writer};
+
+    #[cfg(feature = "synth")]
+    pub use crate::synth::SynthesizerExt;
+
+    #[cfg(feature = "render")]
+    pub use crate::render::{RenderSettings, RenderedAudio, SampleFormat};
 
     #[allow(unused_imports)]
     pub(crate) use crate::reader::inv_data;
@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{events::sized_buf, prelude::*};
 
 #[doc = r#"
 The channel mode message, capable of reserved functions
@@ -42,9 +42,133 @@ the number of channels (Omni Off) or 0 (Omni On)
 c = 127, v = 0: Poly Mode On (Mono Off) (Note:
 These four messages also cause All Notes Off)
 "#]
-#[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct ChannelModeMessage {
-    controller: Controller,
-    value: DataByte,
+    status: StatusByte,
+    /// The mode change sent to the channel
+    pub mode: ChannelMode,
+}
+
+impl ChannelModeMessage {
+    /// Create a new channel mode message from the channel and associated mode
+    pub const fn new(channel: Channel, mode: ChannelMode) -> Self {
+        Self {
+            status: StatusByte::new_unchecked(0xB0 | channel.to_byte()),
+            mode,
+        }
+    }
+
+    /// Get the channel for the message
+    pub fn channel(&self) -> Channel {
+        Channel::from_status(self.status.byte())
+    }
+
+    /// References the status byte of the message.
+    ///
+    /// This is always `1011cccc`, where `cccc` is the channel.
+    pub fn status(&self) -> u8 {
+        self.status.byte()
+    }
+
+    /// References the mode for the message
+    pub fn mode(&self) -> &ChannelMode {
+        &self.mode
+    }
+}
+
+/// The reserved controller functions of a [`ChannelModeMessage`]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ChannelMode {
+    /// c = 120, v = 0: Mute all sounding notes immediately
+    AllSoundOff,
+    /// c = 121, v = 0: Reset all controllers to their default values
+    ResetAllControllers,
+    /// c = 122, v = 0: Local Control Off. v = 127: Local Control On
+    LocalControl(bool),
+    /// c = 123, v = 0: All Notes Off
+    AllNotesOff,
+    /// c = 124, v = 0: Omni Mode Off
+    OmniModeOff,
+    /// c = 125, v = 0: Omni Mode On
+    OmniModeOn,
+    /// c = 126, v = M: Mono Mode On (Poly Off) where M is
+    /// the number of channels (Omni Off) or 0 (Omni On)
+    MonoModeOn(DataByte),
+    /// c = 127, v = 0: Poly Mode On (Mono Off)
+    PolyModeOn,
+}
+
+impl ChannelMode {
+    /// Interpret the two data bytes of a control change as a channel mode.
+    ///
+    /// Returns `None` if the controller isn't reserved (120-127).
+    pub const fn from_bytes(controller: u8, value: DataByte) -> Option<Self> {
+        Some(match controller {
+            0x78 => Self::AllSoundOff,
+            0x79 => Self::ResetAllControllers,
+            0x7A => Self::LocalControl(value.0 != 0),
+            0x7B => Self::AllNotesOff,
+            0x7C => Self::OmniModeOff,
+            0x7D => Self::OmniModeOn,
+            0x7E => Self::MonoModeOn(value),
+            0x7F => Self::PolyModeOn,
+            _ => return None,
+        })
+    }
+
+    /// The controller and value data bytes of the mode
+    pub const fn to_bytes(&self) -> [u8; 2] {
+        match self {
+            Self::AllSoundOff => [0x78, 0],
+            Self::ResetAllControllers => [0x79, 0],
+            Self::LocalControl(on) => [0x7A, if *on { 0x7F } else { 0 }],
+            Self::AllNotesOff => [0x7B, 0],
+            Self::OmniModeOff => [0x7C, 0],
+            Self::OmniModeOn => [0x7D, 0],
+            Self::MonoModeOn(channels) => [0x7E, channels.0],
+            Self::PolyModeOn => [0x7F, 0],
+        }
+    }
+}
+
+impl FromLiveEventBytes for ChannelModeMessage {
+    const MIN_STATUS_BYTE: u8 = 0xB0;
+    const MAX_STATUS_BYTE: u8 = 0xBF;
+    fn from_status_and_data(status: u8, data: &[u8]) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let controller = data.first().copied().ok_or(ParseError::MissingData)?;
+        let value = DataByte::new(data.get(1).copied().ok_or(ParseError::MissingData)?)?;
+        let mode = ChannelMode::from_bytes(controller, value)
+            .ok_or(ParseError::InvalidDataByte(controller))?;
+        Ok(Self {
+            status: StatusByte::new(status)?,
+            mode,
+        })
+    }
+}
+
+impl ToLiveEventBytes for ChannelModeMessage {
+    fn byte_len(&self) -> usize {
+        3
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = sized_buf(buf, 3)?;
+        let [controller, value] = self.mode.to_bytes();
+        buf.copy_from_slice(&[self.status(), controller, value]);
+        Ok(3)
+    }
+}
+
+#[test]
+fn parse_local_control() {
+    let bytes = [0xB4, 0x7A, 0x7F];
+    let message = ChannelModeMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(message.channel(), Channel::Five);
+    assert_eq!(message.mode(), &ChannelMode::LocalControl(true));
+    assert_eq!(message.to_bytes(), bytes);
+
+    // not a reserved controller
+    assert!(ChannelModeMessage::from_bytes(&[0xB4, 0x07, 0x7F]).is_err());
 }
@@ -1,9 +1,11 @@
 use crate::{
-    Controller, Key, ParseError, PitchBend, Program, StatusByte, Velocity,
+    Controller, EncodeError, Key, MidiMessageBytes, ParseError, PitchBend, Program, StatusByte,
+    Velocity,
     channel::Channel,
-    events::FromLiveEventBytes,
+    events::{FromLiveEventBytes, ToLiveEventBytes},
     message::VoiceEvent,
-    reader::{MidiSource, ReadError, ReadResult, Reader, ReaderError, ReaderErrorKind, inv_data},
+    reader::{MidiSource, ReadResult, Reader, ReaderError, ReaderErrorKind, inv_data},
+    writer::{MidiSink, WriteResult, Writer},
 };
 
 /// Represents a MIDI voice message.
@@ -88,6 +90,15 @@ impl ChannelVoiceMessage {
         Ok(ChannelVoiceMessage { status, event: msg })
     }
 
+    /// Writes the status (respecting the writer's running status) and data bytes
+    pub(crate) fn write<W: MidiSink>(&self, writer: &mut Writer<W>) -> WriteResult<()> {
+        writer.write_status(self.status.byte())?;
+        match self.data_2_byte() {
+            Some(data_2) => writer.write_bytes(&[self.data_1_byte(), data_2]),
+            None => writer.write_bytes(&[self.data_1_byte()]),
+        }
+    }
+
     /// Get the channel for the event
     pub fn channel(&self) -> Channel {
         Channel::from_status(self.status.byte())
@@ -144,8 +155,7 @@ impl ChannelVoiceMessage {
         match &self.event {
             VoiceEvent::NoteOn { velocity, .. }
             | VoiceEvent::NoteOff { velocity, .. }
-            | VoiceEvent::Aftertouch { velocity, .. }
-            | VoiceEvent::ChannelPressureAfterTouch { velocity } => Some(velocity.byte()),
+            | VoiceEvent::Aftertouch { velocity, .. } => Some(velocity.byte()),
             VoiceEvent::ControlChange(c) => c.to_bytes().get(1).copied(),
             VoiceEvent::PitchBend(p) => Some(p.msb()),
             _ => None,
@@ -164,15 +174,15 @@ impl ChannelVoiceMessage {
     pub fn event(&self) -> &VoiceEvent {
         &self.event
     }
+}
 
-    // /// Get the raw midi packet for this message
-    // pub fn to_bytes(&self) -> Vec<u8> {
-    //     let mut packet = Vec::with_capacity(3);
-    //     packet.push(self.status());
-    //     packet.extend(self.event.to_raw());
-
-    //     packet
-    // }
+impl ToLiveEventBytes for ChannelVoiceMessage {
+    fn byte_len(&self) -> usize {
+        MidiMessageBytes::from(*self).byte_len()
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        MidiMessageBytes::from(*self).write_into(buf)
+    }
 }
 
 impl FromLiveEventBytes for ChannelVoiceMessage {
@@ -200,9 +210,7 @@ impl FromLiveEventBytes for ChannelVoiceMessage {
                 let mut temp = Reader::from_byte_slice(data);
                 let c = Controller::read(&mut temp).map_err(|e| match e.kind {
                     ReaderErrorKind::ParseError(p) => p,
-                    ReaderErrorKind::ReadError(p) => match p {
-                        ReadError::OutOfBounds => ParseError::MissingData,
-                    },
+                    ReaderErrorKind::ReadError(_) => ParseError::MissingData,
                 })?;
                 VoiceEvent::ControlChange(c)
             }
@@ -44,6 +44,8 @@ There are five total message types. The layout looks like this:
 
 "#]
 
+use crate::prelude::*;
+
 pub mod channel;
 pub use channel::*;
 pub mod system;
@@ -54,7 +56,7 @@ pub use time::*;
 #[doc = r#"
 An enumeration of all possible midi messages
 "#]
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub enum MidiMessage<'a> {
     /// A system common message
     SysCommon(SystemCommonMessage<'a>),
@@ -119,3 +121,53 @@ impl From<ChannelModeMessage> for MidiMessage<'_> {
         Self::ChannelMode(value)
     }
 }
+
+impl FromLiveEventBytes for MidiMessage<'_> {
+    const MIN_STATUS_BYTE: u8 = 0x80;
+    const MAX_STATUS_BYTE: u8 = 0xFF;
+    fn from_status_and_data(status: u8, data: &[u8]) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        match status {
+            0xB0..=0xBF if data.first().is_some_and(|c| *c >= 0x78) => Ok(Self::ChannelMode(
+                ChannelModeMessage::from_status_and_data(status, data)?,
+            )),
+            0x80..=0xEF => Ok(Self::ChannelVoice(
+                ChannelVoiceMessage::from_status_and_data(status, data)?,
+            )),
+            0xF0 => match SystemCommonMessage::from_status_and_data(status, data)? {
+                SystemCommonMessage::SystemExclusive(sysex) => Ok(Self::SysExclusive(sysex)),
+                common => Ok(Self::SysCommon(common)),
+            },
+            0xF1..=0xF7 => Ok(Self::SysCommon(SystemCommonMessage::from_status_and_data(
+                status, data,
+            )?)),
+            0xF8..=0xFF => Ok(Self::SysRealTime(
+                SystemRealTimeMessage::from_status_and_data(status, data)?,
+            )),
+            b => Err(ParseError::InvalidStatusByte(b)),
+        }
+    }
+}
+
+impl ToLiveEventBytes for MidiMessage<'_> {
+    fn byte_len(&self) -> usize {
+        match self {
+            Self::SysCommon(m) => m.byte_len(),
+            Self::SysRealTime(m) => m.byte_len(),
+            Self::SysExclusive(m) => m.byte_len(),
+            Self::ChannelVoice(m) => m.byte_len(),
+            Self::ChannelMode(m) => m.byte_len(),
+        }
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        match self {
+            Self::SysCommon(m) => m.write_into(buf),
+            Self::SysRealTime(m) => m.write_into(buf),
+            Self::SysExclusive(m) => m.write_into(buf),
+            Self::ChannelVoice(m) => m.write_into(buf),
+            Self::ChannelMode(m) => m.write_into(buf),
+        }
+    }
+}
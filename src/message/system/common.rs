@@ -1,6 +1,6 @@
 use alloc::vec::Vec;
 
-use crate::{prelude::*, utils::check_u7};
+use crate::{events::sized_buf, prelude::*, utils::check_u7};
 
 #[doc = r#"
 A System Common Message, used to relay data for ALL receivers, regardless of channel.
@@ -25,8 +25,8 @@ pub enum SystemCommonMessage<'a> {
     TuneRequest,
 }
 impl SystemCommonMessage<'_> {
-    #[allow(dead_code)]
-    const fn status(&self) -> u8 {
+    /// Get the status byte of the message
+    pub const fn status(&self) -> u8 {
         use SystemCommonMessage::*;
         match self {
             SystemExclusive(_) => 0xF0,
@@ -36,19 +36,35 @@ impl SystemCommonMessage<'_> {
             Undefined(v) => v.byte(),
         }
     }
+}
 
-    // /// Represents the message as an array of bytes for some live MIDI stream
-    // pub fn to_bytes(&self) -> Vec<u8> {
-    //     use SystemCommonMessage::*;
-    //     match self {
-    //         SystemExclusive(b) => b.to_live_bytes(),
-    //         SongPositionPointer(spp) => {
-    //             vec![self.status(), spp.lsb().value(), spp.msb().value()]
-    //         }
-    //         SongSelect(v) => vec![self.status(), *v],
-    //         TuneRequest | Undefined(_) => vec![self.status()],
-    //     }
-    // }
+impl ToLiveEventBytes for SystemCommonMessage<'_> {
+    fn byte_len(&self) -> usize {
+        use SystemCommonMessage::*;
+        match self {
+            SystemExclusive(sysex) => sysex.byte_len(),
+            SongPositionPointer(_) => 3,
+            SongSelect(_) => 2,
+            TuneRequest | Undefined(_) => 1,
+        }
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        use SystemCommonMessage::*;
+        if let SystemExclusive(sysex) = self {
+            return sysex.write_into(buf);
+        }
+        let buf = sized_buf(buf, self.byte_len())?;
+        buf[0] = self.status();
+        match self {
+            SongPositionPointer(spp) => {
+                buf[1] = spp.lsb().value();
+                buf[2] = spp.msb().value();
+            }
+            SongSelect(v) => buf[1] = *v,
+            _ => {}
+        }
+        Ok(buf.len())
+    }
 }
 
 impl FromLiveEventBytes for SystemCommonMessage<'_> {
@@ -1,5 +1,11 @@
 use alloc::borrow::Cow;
 
+use crate::{
+    EncodeError,
+    events::{ToLiveEventBytes, sized_buf},
+    writer::{MidiSink, WriteResult, Writer},
+};
+
 #[doc = r#"
 A System Exclusive messsage, found in
 both [`LiveEvent`](crate::prelude::LiveEvent)s and [`FileEvent`](crate::prelude::FileEvent)s.
@@ -51,6 +57,21 @@ impl<'a> SystemExclusiveMessage<'a> {
         Self(data.into())
     }
 
+    /// Get the data of the message, excluding the leading `0xF0` and trailing `0xF7`
+    pub fn data(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Writes the message as it would appear in a file:
+    ///
+    /// `F0 <varlen length> <data> F7`
+    pub(crate) fn write<W: MidiSink>(&self, writer: &mut Writer<W>) -> WriteResult<()> {
+        writer.write_bytes(&[0xF0])?;
+        writer.write_varlen(self.0.len() as u32 + 1)?;
+        writer.write_bytes(&self.0)?;
+        writer.write_bytes(&[0xF7])
+    }
+
     // /// Returns a mutable reference to the underlying data.
     // pub fn data_mut(&mut self) -> &mut Vec<u8> {
     //     self.0.to_mut()
@@ -65,15 +86,21 @@ impl<'a> SystemExclusiveMessage<'a> {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+}
 
-    // /// Interprets the sysex as a live-streamed set of bytes.
-    // ///
-    // /// Note that live bytes don't have an identifying length, unlike a file system common message.
-    // pub fn to_live_bytes(&self) -> Vec<u8> {
-    //     let mut bytes = Vec::with_capacity(self.len() + 2);
-    //     bytes.push(0xF0);
-    //     bytes.extend(self.0.iter());
-    //     bytes.push(0xF7);
-    //     bytes
-    // }
+/// Interprets the sysex as a live-streamed set of bytes: `F0 <data> F7`.
+///
+/// Note that live bytes don't have an identifying length, unlike a file system exclusive message.
+impl ToLiveEventBytes for SystemExclusiveMessage<'_> {
+    fn byte_len(&self) -> usize {
+        self.0.len() + 2
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = sized_buf(buf, self.byte_len())?;
+        let (last, rest) = buf.split_last_mut().unwrap();
+        rest[0] = 0xF0;
+        rest[1..].copy_from_slice(&self.0);
+        *last = 0xF7;
+        Ok(buf.len())
+    }
 }
@@ -22,6 +22,9 @@ pub use realtime::*;
 mod exclusive;
 pub use exclusive::*;
 
+mod tuning;
+pub use tuning::*;
+
 #[doc = r#"
 The set of possible System messages
 "#]
@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{events::sized_buf, prelude::*};
 
 #[doc = r#"
 One-byte messages that only occur in live MIDI events.
@@ -78,3 +78,13 @@ impl FromLiveEventBytes for SystemRealTimeMessage {
         }
     }
 }
+
+impl ToLiveEventBytes for SystemRealTimeMessage {
+    fn byte_len(&self) -> usize {
+        1
+    }
+    fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        sized_buf(buf, 1)?[0] = self.byte();
+        Ok(1)
+    }
+}
@@ -0,0 +1,472 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use super::SystemExclusiveMessage;
+
+#[doc = r#"
+A pitch in the MIDI Tuning Standard's frequency format:
+a semitone (where 69 is A440) and a 14-bit fraction of the next semitone,
+in steps of 100/16384 cents.
+"#]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+pub struct KeyTuning {
+    semitone: u8,
+    fraction: u16,
+}
+
+impl KeyTuning {
+    /// Create a pitch from a semitone and 14-bit fraction
+    pub const fn new(semitone: u8, fraction: u16) -> Self {
+        Self {
+            semitone: semitone & 0x7F,
+            fraction: fraction & 0x3FFF,
+        }
+    }
+
+    /// The equal tempered pitch of a key
+    pub const fn equal_temperament(key: u8) -> Self {
+        Self::new(key, 0)
+    }
+
+    /// Create a pitch from fractional semitones, where 69.0 is A440.
+    ///
+    /// The pitch is clamped to the representable range, `0.0` to just under `128.0`.
+    pub fn from_semitones(semitones: f32) -> Self {
+        let semitones = semitones.clamp(0., 127.99994);
+        let semitone = semitones as u8;
+        let fraction = ((semitones - semitone as f32) * 16384. + 0.5) as u16;
+        if fraction >= 0x4000 {
+            if semitone == 127 {
+                Self::new(127, 0x3FFF)
+            } else {
+                Self::new(semitone + 1, 0)
+            }
+        } else {
+            Self::new(semitone, fraction)
+        }
+    }
+
+    /// The whole semitone
+    pub const fn semitone(&self) -> u8 {
+        self.semitone
+    }
+
+    /// The fraction of a semitone above [`KeyTuning::semitone`], out of `0x4000`
+    pub const fn fraction(&self) -> u16 {
+        self.fraction
+    }
+
+    /// The pitch in fractional semitones, where 69.0 is A440
+    pub fn semitones(&self) -> f32 {
+        self.semitone as f32 + self.fraction as f32 / 16384.
+    }
+
+    /// Reads the three frequency bytes. `7F 7F 7F` means "no change"
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x7F, 0x7F, 0x7F] => None,
+            [semitone, msb, lsb] => Some(Self::new(
+                *semitone,
+                ((*msb as u16 & 0x7F) << 7) | (*lsb as u16 & 0x7F),
+            )),
+            _ => None,
+        }
+    }
+
+    fn to_bytes(tuning: Option<Self>) -> [u8; 3] {
+        match tuning {
+            Some(tuning) => [
+                tuning.semitone,
+                (tuning.fraction >> 7) as u8,
+                (tuning.fraction & 0x7F) as u8,
+            ],
+            None => [0x7F; 3],
+        }
+    }
+}
+
+#[doc = r#"
+The offsets of the 12 semitones of the octave (C to B) in a scale/octave tuning message
+"#]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ScaleOctaveOffsets {
+    /// One byte per semitone: cents from -64 (`0x00`) to +63 (`0x7F`), where `0x40` is no change
+    OneByte([u8; 12]),
+    /// Two bytes per semitone: a 14-bit value from -100 (`0x0000`) to almost +100 (`0x3FFF`) cents,
+    /// where `0x2000` is no change
+    TwoByte([u16; 12]),
+}
+
+impl ScaleOctaveOffsets {
+    /// The offset of each semitone, in cents
+    pub fn cents(&self) -> [f32; 12] {
+        match self {
+            Self::OneByte(offsets) => offsets.map(|offset| (offset & 0x7F) as f32 - 64.),
+            Self::TwoByte(offsets) => {
+                offsets.map(|offset| ((offset & 0x3FFF) as f32 - 8192.) * (100. / 8192.))
+            }
+        }
+    }
+}
+
+#[doc = r#"
+A MIDI Tuning Standard (MTS) message, which retunes keys for microtonal playback.
+
+MTS messages are universal system exclusive messages (sub-ID `08`). Receivers hold up to
+128 banks of 128 key-based tuning programs, selected per channel with RPN `0x0003` and `0x0004`
+(see [`ParameterNumber::TUNING_PROGRAM`](crate::ParameterNumber::TUNING_PROGRAM)).
+Scale/octave tunings are sent straight to a set of channels instead, and repeat every octave.
+
+Real-time messages retune sounding notes. Others only apply to the next notes played.
+
+# Example
+```rust
+use midix::prelude::*;
+
+// retune A4 a quarter tone sharp in tuning program 3
+let change = TuningMessage::SingleNoteChange {
+    device: TuningMessage::ALL_DEVICES,
+    realtime: true,
+    bank: None,
+    program: 3,
+    changes: vec![(69, KeyTuning::from_semitones(69.5))],
+};
+
+let sysex = change.to_sysex();
+assert_eq!(
+    sysex.data(),
+    &[0x7F, 0x7F, 0x08, 0x02, 0x03, 0x01, 69, 69, 0x40, 0x00]
+);
+assert_eq!(TuningMessage::from_sysex(&sysex), Some(change));
+```
+"#]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum TuningMessage {
+    /// Asks a device to send a [`TuningMessage::BulkDump`] of one of its tuning programs
+    BulkDumpRequest {
+        /// The device ID, or [`TuningMessage::ALL_DEVICES`]
+        device: u8,
+        /// The tuning bank. Without one, the request is for bank 0 in the original MTS form
+        bank: Option<u8>,
+        /// The tuning program
+        program: u8,
+    },
+    /// A whole tuning program: the pitch of every key
+    BulkDump {
+        /// The device ID, or [`TuningMessage::ALL_DEVICES`]
+        device: u8,
+        /// The tuning bank. Without one, the dump is for bank 0 in the original MTS form
+        bank: Option<u8>,
+        /// The tuning program
+        program: u8,
+        /// The name of the tuning, in ASCII
+        name: [u8; 16],
+        /// The pitch of each key, or `None` to leave a key unchanged
+        keys: Box<[Option<KeyTuning>; 128]>,
+    },
+    /// Retunes some keys of a tuning program
+    SingleNoteChange {
+        /// The device ID, or [`TuningMessage::ALL_DEVICES`]
+        device: u8,
+        /// Whether sounding notes are retuned.
+        ///
+        /// Without a bank, the message is always real-time, as that is the only form MTS defines.
+        realtime: bool,
+        /// The tuning bank. Without one, the change is to bank 0 in the original MTS form
+        bank: Option<u8>,
+        /// The tuning program
+        program: u8,
+        /// The keys, and the pitches they are retuned to
+        changes: Vec<(u8, KeyTuning)>,
+    },
+    /// Offsets each semitone of the octave on a set of channels
+    ScaleOctave {
+        /// The device ID, or [`TuningMessage::ALL_DEVICES`]
+        device: u8,
+        /// Whether sounding notes are retuned
+        realtime: bool,
+        /// The channels to retune. Bit `n` is channel `n + 1`
+        channels: u16,
+        /// The offset of each semitone, from C to B
+        offsets: ScaleOctaveOffsets,
+    },
+}
+
+impl TuningMessage {
+    /// The device ID that every device responds to
+    pub const ALL_DEVICES: u8 = 0x7F;
+
+    /// Read an MTS message from a system exclusive message.
+    ///
+    /// Returns `None` if the message isn't one of the MTS messages above, or is too short.
+    /// The checksum of bulk dumps isn't verified, as devices disagree on the bytes it covers.
+    pub fn from_sysex(message: &SystemExclusiveMessage<'_>) -> Option<Self> {
+        Self::parse(message.data())
+    }
+
+    /// Read an MTS message from the data of a system exclusive message,
+    /// without the leading `0xF0` and trailing `0xF7`. See [`TuningMessage::from_sysex`].
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let [universal, device, 0x08, format, rest @ ..] = data else {
+            return None;
+        };
+        let realtime = match universal {
+            0x7E => false,
+            0x7F => true,
+            _ => return None,
+        };
+        let device = *device & 0x7F;
+
+        let message = match (realtime, format) {
+            (false, 0x00) => Self::BulkDumpRequest {
+                device,
+                bank: None,
+                program: *rest.first()? & 0x7F,
+            },
+            (false, 0x03) => Self::BulkDumpRequest {
+                device,
+                bank: Some(*rest.first()? & 0x7F),
+                program: *rest.get(1)? & 0x7F,
+            },
+            (false, 0x01) => Self::bulk_dump(device, None, rest)?,
+            (false, 0x04) => {
+                let (bank, rest) = rest.split_first()?;
+                Self::bulk_dump(device, Some(*bank & 0x7F), rest)?
+            }
+            (true, 0x02) => Self::single_note_change(device, true, None, rest)?,
+            (_, 0x07) => {
+                let (bank, rest) = rest.split_first()?;
+                Self::single_note_change(device, realtime, Some(*bank & 0x7F), rest)?
+            }
+            (_, 0x08 | 0x09) => {
+                let channels = (*rest.first()? as u16 & 0x03) << 14
+                    | (*rest.get(1)? as u16 & 0x7F) << 7
+                    | (*rest.get(2)? as u16 & 0x7F);
+                let bytes = rest.get(3..)?;
+                let offsets = if *format == 0x08 {
+                    ScaleOctaveOffsets::OneByte(bytes.get(..12)?.try_into().ok()?)
+                } else {
+                    let bytes = bytes.get(..24)?;
+                    ScaleOctaveOffsets::TwoByte(core::array::from_fn(|i| {
+                        ((bytes[i * 2] as u16 & 0x7F) << 7) | (bytes[i * 2 + 1] as u16 & 0x7F)
+                    }))
+                };
+                Self::ScaleOctave {
+                    device,
+                    realtime,
+                    channels,
+                    offsets,
+                }
+            }
+            _ => return None,
+        };
+        Some(message)
+    }
+
+    fn bulk_dump(device: u8, bank: Option<u8>, data: &[u8]) -> Option<Self> {
+        let (program, data) = data.split_first()?;
+        let name = data.get(..16)?.try_into().ok()?;
+        let tunings = data.get(16..16 + 128 * 3)?;
+        let mut keys = Box::new([None; 128]);
+        for (key, bytes) in keys.iter_mut().zip(tunings.chunks_exact(3)) {
+            *key = KeyTuning::from_bytes(bytes);
+        }
+        Some(Self::BulkDump {
+            device,
+            bank,
+            program: *program & 0x7F,
+            name,
+            keys,
+        })
+    }
+
+    fn single_note_change(
+        device: u8,
+        realtime: bool,
+        bank: Option<u8>,
+        data: &[u8],
+    ) -> Option<Self> {
+        let [program, count, changes @ ..] = data else {
+            return None;
+        };
+        let changes = changes
+            .chunks_exact(4)
+            .take(*count as usize)
+            .filter_map(|change| Some((change[0] & 0x7F, KeyTuning::from_bytes(&change[1..])?)))
+            .collect();
+        Some(Self::SingleNoteChange {
+            device,
+            realtime,
+            bank,
+            program: *program & 0x7F,
+            changes,
+        })
+    }
+
+    /// Encode the message as a system exclusive message
+    pub fn to_sysex(&self) -> SystemExclusiveMessage<'static> {
+        let mut data = Vec::new();
+        match self {
+            Self::BulkDumpRequest {
+                device,
+                bank,
+                program,
+            } => {
+                data.extend([0x7E, *device, 0x08]);
+                match bank {
+                    Some(bank) => data.extend([0x03, *bank]),
+                    None => data.push(0x00),
+                }
+                data.push(*program);
+            }
+            Self::BulkDump {
+                device,
+                bank,
+                program,
+                name,
+                keys,
+            } => {
+                data.extend([0x7E, *device, 0x08]);
+                match bank {
+                    Some(bank) => data.extend([0x04, *bank]),
+                    None => data.push(0x01),
+                }
+                data.push(*program);
+                data.extend(name.iter().map(|c| c & 0x7F));
+                for key in keys.iter() {
+                    data.extend(KeyTuning::to_bytes(*key));
+                }
+                let checksum = data.iter().fold(0, |checksum, byte| checksum ^ byte);
+                data.push(checksum & 0x7F);
+            }
+            Self::SingleNoteChange {
+                device,
+                realtime,
+                bank,
+                program,
+                changes,
+            } => {
+                match bank {
+                    Some(bank) => {
+                        let universal = if *realtime { 0x7F } else { 0x7E };
+                        data.extend([universal, *device, 0x08, 0x07, *bank]);
+                    }
+                    None => data.extend([0x7F, *device, 0x08, 0x02]),
+                }
+                // the count is a data byte, so long lists are cut short
+                let changes = &changes[..changes.len().min(0x7F)];
+                data.extend([*program, changes.len() as u8]);
+                for (key, tuning) in changes {
+                    data.push(*key);
+                    data.extend(KeyTuning::to_bytes(Some(*tuning)));
+                }
+            }
+            Self::ScaleOctave {
+                device,
+                realtime,
+                channels,
+                offsets,
+            } => {
+                let universal = if *realtime { 0x7F } else { 0x7E };
+                let format = match offsets {
+                    ScaleOctaveOffsets::OneByte(_) => 0x08,
+                    ScaleOctaveOffsets::TwoByte(_) => 0x09,
+                };
+                data.extend([
+                    universal,
+                    *device,
+                    0x08,
+                    format,
+                    ((channels >> 14) & 0x03) as u8,
+                    ((channels >> 7) & 0x7F) as u8,
+                    (channels & 0x7F) as u8,
+                ]);
+                match offsets {
+                    ScaleOctaveOffsets::OneByte(offsets) => data.extend(offsets),
+                    ScaleOctaveOffsets::TwoByte(offsets) => {
+                        for offset in offsets {
+                            data.extend([(offset >> 7) as u8, (offset & 0x7F) as u8]);
+                        }
+                    }
+                }
+            }
+        }
+        // every field is a data byte
+        for byte in data.iter_mut() {
+            *byte &= 0x7F;
+        }
+        SystemExclusiveMessage::new(data)
+    }
+}
+
+#[test]
+fn bulk_dump() {
+    let mut keys = Box::new([None; 128]);
+    for (key, tuning) in keys.iter_mut().enumerate().skip(1) {
+        *tuning = Some(KeyTuning::from_semitones(key as f32 - 0.25));
+    }
+    let dump = TuningMessage::BulkDump {
+        device: 0x10,
+        bank: Some(2),
+        program: 5,
+        name: *b"quarter flat    ",
+        keys,
+    };
+
+    let sysex = dump.to_sysex();
+    let data = sysex.data();
+    assert_eq!(data.len(), 6 + 16 + 384 + 1);
+    assert_eq!(&data[..6], &[0x7E, 0x10, 0x08, 0x04, 2, 5]);
+    // key 0 is unchanged
+    assert_eq!(&data[22..25], &[0x7F, 0x7F, 0x7F]);
+    // key 1 is a quarter of a semitone flat
+    assert_eq!(&data[25..28], &[0, 0x60, 0]);
+    let checksum = data[..data.len() - 1].iter().fold(0, |c, b| c ^ b);
+    assert_eq!(data[data.len() - 1], checksum);
+
+    assert_eq!(TuningMessage::parse(data), Some(dump));
+}
+
+#[test]
+fn scale_octave() {
+    let cents = [0, -10, 4, 16, -14, -2, -12, 2, 14, -16, 18, -12];
+    let message = TuningMessage::ScaleOctave {
+        device: TuningMessage::ALL_DEVICES,
+        realtime: false,
+        channels: 0b1000_0000_0000_0001,
+        offsets: ScaleOctaveOffsets::OneByte(cents.map(|c: i8| (c + 64) as u8)),
+    };
+    let sysex = message.to_sysex();
+    assert_eq!(
+        &sysex.data()[..7],
+        &[0x7E, 0x7F, 0x08, 0x08, 0x02, 0x00, 0x01]
+    );
+    assert_eq!(TuningMessage::from_sysex(&sysex), Some(message.clone()));
+
+    let TuningMessage::ScaleOctave { offsets, .. } = message else {
+        unreachable!()
+    };
+    assert_eq!(offsets.cents()[1], -10.);
+
+    let two_byte = ScaleOctaveOffsets::TwoByte([0x2000, 0, 0x3000, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(&two_byte.cents()[..3], &[0., -100., 50.]);
+    let message = TuningMessage::ScaleOctave {
+        device: 0,
+        realtime: true,
+        channels: 0xFFFF,
+        offsets: two_byte,
+    };
+    assert_eq!(
+        TuningMessage::from_sysex(&message.to_sysex()),
+        Some(message)
+    );
+}
+
+#[test]
+fn key_tuning() {
+    assert_eq!(KeyTuning::from_semitones(69.), KeyTuning::new(69, 0));
+    assert_eq!(KeyTuning::from_semitones(60.5), KeyTuning::new(60, 0x2000));
+    assert_eq!(KeyTuning::from_semitones(60.99999), KeyTuning::new(61, 0));
+    assert_eq!(KeyTuning::from_semitones(200.), KeyTuning::new(127, 0x3FFF));
+    assert_eq!(KeyTuning::from_semitones(-1.), KeyTuning::new(0, 0));
+    assert_eq!(KeyTuning::new(60, 0x2000).semitones(), 60.5);
+}
@@ -0,0 +1,295 @@
+use crate::prelude::*;
+
+/// The registered parameters whose values are kept between selections
+const TRACKED_RPNS: usize = 7;
+
+/// The values of the tracked registered parameters after a reset
+const RPN_DEFAULTS: [u16; TRACKED_RPNS] = [
+    // pitch bend range: 2 semitones
+    2 << 7,
+    // fine tuning: A440
+    0x2000,
+    // coarse tuning: A440
+    0x40 << 7,
+    // tuning program and bank
+    0,
+    0,
+    // modulation depth range: 50 cents
+    0x40,
+    // no MPE zone
+    0,
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ChannelParameters {
+    registered: bool,
+    msb: u8,
+    lsb: u8,
+    rpns: [u16; TRACKED_RPNS],
+    /// The value of the selected parameter, if it isn't tracked
+    value: u16,
+}
+
+impl Default for ChannelParameters {
+    fn default() -> Self {
+        Self {
+            registered: true,
+            msb: 0x7F,
+            lsb: 0x7F,
+            rpns: RPN_DEFAULTS,
+            value: 0,
+        }
+    }
+}
+
+impl ChannelParameters {
+    fn selected(&self) -> ParameterNumber {
+        ParameterNumber::new(
+            self.registered,
+            DataByte::new_unchecked(self.msb),
+            DataByte::new_unchecked(self.lsb),
+        )
+    }
+
+    fn select(&mut self, registered: bool, msb: Option<u8>, lsb: Option<u8>) {
+        self.registered = registered;
+        if let Some(msb) = msb {
+            self.msb = msb;
+        }
+        if let Some(lsb) = lsb {
+            self.lsb = lsb;
+        }
+        self.value = 0;
+    }
+
+    fn value_mut(&mut self) -> &mut u16 {
+        let selected = self.selected();
+        match selected {
+            ParameterNumber::Registered(number) if (number as usize) < TRACKED_RPNS => {
+                &mut self.rpns[number as usize]
+            }
+            _ => &mut self.value,
+        }
+    }
+
+    /// Applies a data entry controller to the selected parameter
+    fn data_entry(&mut self, number: u8, data: u8) -> Option<Parameter> {
+        let selected = self.selected();
+        if selected.is_null() {
+            return None;
+        }
+        // some parameters only use the MSB, so they step by whole semitones, programs or channels
+        let step = if matches!(
+            selected,
+            ParameterNumber::COARSE_TUNING
+                | ParameterNumber::TUNING_PROGRAM
+                | ParameterNumber::TUNING_BANK
+                | ParameterNumber::MPE_CONFIGURATION
+        ) {
+            1 << 7
+        } else {
+            1
+        };
+
+        let value = self.value_mut();
+        *value = match number {
+            // as with any other controller pair, an MSB clears the LSB
+            0x06 => (data as u16) << 7,
+            0x26 => (*value & !0x7F) | data as u16,
+            0x60 => (*value + step).min(0x3FFF),
+            _ => value.saturating_sub(step),
+        };
+        Some(Parameter::new(selected, *value))
+    }
+}
+
+#[doc = r#"
+Decodes the (N)RPN sequences sent to each channel into [`Parameter`] changes.
+
+Select controllers (`0x65`/`0x64` for RPNs, `0x63`/`0x62` for NRPNs) pick the parameter of a channel,
+and every data entry (`0x06`/`0x26`) or data increment/decrement (`0x60`/`0x61`) that follows yields
+the parameter's new value.
+
+Following the MIDI spec:
+- a data entry MSB clears the LSB of the value
+- increment and decrement step the LSB, except for the coarse tuning, tuning program and bank,
+  and MPE configuration, which only use the MSB
+- nothing is changed while the null function (RPN `0x7F7F`) is selected, which is also the
+  selection after a reset or [`Controller::ResetAllControllers`]
+
+The values of RPNs `0x0000` to `0x0006` are kept for each channel, so changing only the LSB
+of the pitch bend range keeps its semitones. Other parameters start at zero each time
+they're selected.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let mut decoder = ParameterDecoder::new();
+let cc = |number, value| {
+    Channel::Three.send_event(VoiceEvent::ControlChange(Controller::new(
+        DataByte::new(number).unwrap(),
+        DataByte::new(value).unwrap(),
+    )))
+};
+
+assert_eq!(decoder.apply(&cc(0x65, 0x00)), None);
+assert_eq!(decoder.apply(&cc(0x64, 0x02)), None);
+assert_eq!(decoder.apply(&cc(0x06, 0x3E)), Some(Parameter::CoarseTuning(0x3E)));
+assert_eq!(decoder.apply(&cc(0x60, 0x00)), Some(Parameter::CoarseTuning(0x3F)));
+
+assert_eq!(decoder.apply(&cc(0x63, 0x01)), None);
+assert_eq!(decoder.apply(&cc(0x62, 0x08)), None);
+assert_eq!(
+    decoder.apply(&cc(0x06, 0x10)),
+    Some(Parameter::NonRegistered {
+        number: 0x88,
+        value: 0x10 << 7
+    })
+);
+```
+"#]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParameterDecoder {
+    channels: [ChannelParameters; 16],
+}
+
+impl Default for ParameterDecoder {
+    fn default() -> Self {
+        Self {
+            channels: core::array::from_fn(|_| ChannelParameters::default()),
+        }
+    }
+}
+
+impl ParameterDecoder {
+    /// A decoder with nothing selected on every channel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The parameter selected on a channel
+    pub fn selected(&self, channel: Channel) -> ParameterNumber {
+        self.channels[channel.to_byte() as usize].selected()
+    }
+
+    /// The current value of a registered parameter on a channel,
+    /// for RPNs `0x0000` to `0x0006`
+    pub fn registered(&self, channel: Channel, number: u16) -> Option<Parameter> {
+        let rpns = &self.channels[channel.to_byte() as usize].rpns;
+        rpns.get(number as usize)
+            .map(|value| Parameter::new(ParameterNumber::Registered(number), *value))
+    }
+
+    /// Update the decoder with a controller sent to a channel.
+    ///
+    /// Returns the parameter change if the controller sets the selected parameter.
+    pub fn update(&mut self, channel: Channel, controller: &Controller) -> Option<Parameter> {
+        let parameters = &mut self.channels[channel.to_byte() as usize];
+        let [number, value] = controller.to_bytes();
+        match number {
+            0x65 => parameters.select(true, Some(value), None),
+            0x64 => parameters.select(true, None, Some(value)),
+            0x63 => parameters.select(false, Some(value), None),
+            0x62 => parameters.select(false, None, Some(value)),
+            0x06 | 0x26 | 0x60 | 0x61 => return parameters.data_entry(number, value),
+            0x79 => parameters.select(true, Some(0x7F), Some(0x7F)),
+            _ => {}
+        }
+        None
+    }
+
+    /// Update the decoder with a message. Only control changes are decoded.
+    ///
+    /// Returns the parameter change if the message sets the selected parameter.
+    pub fn apply(&mut self, message: &ChannelVoiceMessage) -> Option<Parameter> {
+        match message.event() {
+            VoiceEvent::ControlChange(controller) => self.update(message.channel(), controller),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn parameter_round_trip() {
+    let parameters = [
+        Parameter::PitchBendRange {
+            semitones: 24,
+            cents: 50,
+        },
+        Parameter::FineTuning(0x1FFF),
+        Parameter::CoarseTuning(0x34),
+        Parameter::TuningProgram(3),
+        Parameter::TuningBank(1),
+        Parameter::ModulationDepthRange {
+            semitones: 1,
+            cents: 0,
+        },
+        Parameter::MpeConfiguration { member_channels: 7 },
+        Parameter::Registered {
+            number: 0x3D04,
+            value: 0x0123,
+        },
+        Parameter::NonRegistered {
+            number: 0x0001,
+            value: 0x3FFF,
+        },
+    ];
+
+    let mut decoder = ParameterDecoder::new();
+    for parameter in parameters {
+        let decoded = parameter
+            .messages(Channel::Sixteen)
+            .filter_map(|message| decoder.apply(&message))
+            .last();
+        assert_eq!(decoded, Some(parameter));
+    }
+    assert_eq!(decoder.registered(Channel::Sixteen, 0), Some(parameters[0]));
+    assert_eq!(
+        decoder.registered(Channel::One, 1),
+        Some(Parameter::FineTuning(0x2000))
+    );
+}
+
+#[test]
+fn data_entry() {
+    let mut decoder = ParameterDecoder::new();
+    let mut update = |controller| decoder.update(Channel::One, &controller);
+    let data = DataByte::new_unchecked;
+
+    // nothing is selected after a reset
+    assert_eq!(update(Controller::DataEntryCoarse(data(5))), None);
+
+    update(Controller::RPNCoarse(data(0)));
+    update(Controller::RPNFine(data(0)));
+    assert_eq!(
+        update(Controller::DataEntryFine(data(50))),
+        Some(Parameter::PitchBendRange {
+            semitones: 2,
+            cents: 50
+        })
+    );
+    assert_eq!(
+        update(Controller::DataIncrement(data(0))),
+        Some(Parameter::PitchBendRange {
+            semitones: 2,
+            cents: 51
+        })
+    );
+    assert_eq!(
+        update(Controller::DataEntryCoarse(data(12))),
+        Some(Parameter::PitchBendRange {
+            semitones: 12,
+            cents: 0
+        })
+    );
+
+    update(Controller::RPNFine(data(2)));
+    assert_eq!(
+        update(Controller::DataDecrement(data(0))),
+        Some(Parameter::CoarseTuning(0x3F))
+    );
+
+    update(Controller::reset_all());
+    assert_eq!(update(Controller::DataIncrement(data(0))), None);
+}
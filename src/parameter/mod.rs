@@ -0,0 +1,254 @@
+use crate::prelude::*;
+
+mod decoder;
+pub use decoder::*;
+
+#[doc = r#"
+Identifies a registered (RPN) or non-registered (NRPN) parameter.
+
+A parameter is selected by sending its number as two controllers:
+`0x65` and `0x64` for registered parameters, or `0x63` and `0x62` for
+non-registered ones. Its value is then set with data entry (`0x06` and `0x26`)
+or stepped with data increment and decrement (`0x60` and `0x61`).
+
+The numbers are 14-bit, with the upper 7 bits sent first.
+"#]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+pub enum ParameterNumber {
+    /// A parameter defined by the MIDI spec, selected with `0x65` and `0x64`
+    Registered(u16),
+    /// A manufacturer or device specific parameter, selected with `0x63` and `0x62`
+    NonRegistered(u16),
+}
+
+impl ParameterNumber {
+    /// RPN `0x0000`: the pitch bend range, in semitones and cents
+    pub const PITCH_BEND_SENSITIVITY: Self = Self::Registered(0x0000);
+    /// RPN `0x0001`: the fine tuning, where `0x2000` is A440
+    pub const FINE_TUNING: Self = Self::Registered(0x0001);
+    /// RPN `0x0002`: the coarse tuning, in semitones where `0x40` is A440
+    pub const COARSE_TUNING: Self = Self::Registered(0x0002);
+    /// RPN `0x0003`: the tuning program
+    pub const TUNING_PROGRAM: Self = Self::Registered(0x0003);
+    /// RPN `0x0004`: the tuning bank
+    pub const TUNING_BANK: Self = Self::Registered(0x0004);
+    /// RPN `0x0005`: the modulation depth range, in semitones and cents
+    pub const MODULATION_DEPTH_RANGE: Self = Self::Registered(0x0005);
+    /// RPN `0x0006`: the MPE configuration message
+    pub const MPE_CONFIGURATION: Self = Self::Registered(0x0006);
+    /// RPN `0x7F7F`: the null function, which deselects any parameter
+    pub const NULL: Self = Self::Registered(0x3FFF);
+
+    /// Create a parameter number from the values of its two select controllers
+    pub const fn new(registered: bool, msb: DataByte, lsb: DataByte) -> Self {
+        let number = ((msb.value() as u16) << 7) | lsb.value() as u16;
+        if registered {
+            Self::Registered(number)
+        } else {
+            Self::NonRegistered(number)
+        }
+    }
+
+    /// The 14-bit parameter number
+    pub const fn number(&self) -> u16 {
+        match self {
+            Self::Registered(number) | Self::NonRegistered(number) => *number & 0x3FFF,
+        }
+    }
+
+    /// Returns true for registered parameters (RPNs)
+    pub const fn is_registered(&self) -> bool {
+        matches!(self, Self::Registered(_))
+    }
+
+    /// Returns true for the null function
+    pub const fn is_null(&self) -> bool {
+        self.is_registered() && self.number() == 0x3FFF
+    }
+
+    /// The two controllers that select this parameter
+    pub const fn select(&self) -> [Controller; 2] {
+        let msb = DataByte::new_unchecked((self.number() >> 7) as u8);
+        let lsb = DataByte::new_unchecked((self.number() & 0x7F) as u8);
+        if self.is_registered() {
+            [Controller::RPNCoarse(msb), Controller::RPNFine(lsb)]
+        } else {
+            [Controller::NRPNCoarse(msb), Controller::NRPNFine(lsb)]
+        }
+    }
+}
+
+#[doc = r#"
+A parameter change, decoded from a sequence of (N)RPN select and data entry controllers
+by a [`ParameterDecoder`].
+
+The common registered parameters are typed. Any other parameter holds its
+14-bit data entry value, where the data entry MSB (`0x06`) is the upper 7 bits.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let range = Parameter::PitchBendRange {
+    semitones: 12,
+    cents: 0,
+};
+
+// select the parameter, then set the data entry MSB.
+// The LSB (cents) is left out, as it's zero.
+let controllers = range.controllers().collect::<Vec<_>>();
+assert_eq!(controllers.len(), 3);
+assert_eq!(controllers[0].to_bytes(), [0x65, 0]);
+assert_eq!(controllers[1].to_bytes(), [0x64, 0]);
+assert_eq!(controllers[2].to_bytes(), [0x06, 12]);
+
+let mut decoder = ParameterDecoder::new();
+let decoded = range
+    .messages(Channel::One)
+    .filter_map(|message| decoder.apply(&message))
+    .last();
+assert_eq!(decoded, Some(range));
+```
+"#]
+#[non_exhaustive]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Parameter {
+    /// RPN `0x0000`: how far a full pitch bend goes
+    PitchBendRange {
+        /// Whole semitones, from the data entry MSB
+        semitones: u8,
+        /// Cents, from the data entry LSB
+        cents: u8,
+    },
+    /// RPN `0x0001`: a 14-bit value where `0x2000` is A440,
+    /// `0x0000` is 100 cents flat and `0x3FFF` is almost 100 cents sharp
+    FineTuning(u16),
+    /// RPN `0x0002`: semitones, where `0x40` is A440
+    CoarseTuning(u8),
+    /// RPN `0x0003`: selects an MTS tuning program, from the data entry MSB.
+    ///
+    /// See [`TuningMessage`](crate::prelude::TuningMessage).
+    TuningProgram(u8),
+    /// RPN `0x0004`: selects an MTS tuning bank, from the data entry MSB
+    TuningBank(u8),
+    /// RPN `0x0005`: how deep the modulation wheel goes
+    ModulationDepthRange {
+        /// Whole semitones, from the data entry MSB
+        semitones: u8,
+        /// Cents, from the data entry LSB
+        cents: u8,
+    },
+    /// RPN `0x0006`: configures an MPE zone.
+    ///
+    /// Sent on the manager channel of the zone: channel 1 for the lower zone,
+    /// or channel 16 for the upper zone.
+    MpeConfiguration {
+        /// The number of member channels in the zone. Zero turns the zone off.
+        member_channels: u8,
+    },
+    /// Any other registered parameter
+    Registered {
+        /// The 14-bit parameter number
+        number: u16,
+        /// The 14-bit value
+        value: u16,
+    },
+    /// A non-registered parameter
+    NonRegistered {
+        /// The 14-bit parameter number
+        number: u16,
+        /// The 14-bit value
+        value: u16,
+    },
+}
+
+impl Parameter {
+    /// Create a parameter from its number and 14-bit data entry value
+    pub const fn new(number: ParameterNumber, value: u16) -> Self {
+        let msb = ((value >> 7) & 0x7F) as u8;
+        let lsb = (value & 0x7F) as u8;
+        match number {
+            ParameterNumber::PITCH_BEND_SENSITIVITY => Self::PitchBendRange {
+                semitones: msb,
+                cents: lsb,
+            },
+            ParameterNumber::FINE_TUNING => Self::FineTuning(value & 0x3FFF),
+            ParameterNumber::COARSE_TUNING => Self::CoarseTuning(msb),
+            ParameterNumber::TUNING_PROGRAM => Self::TuningProgram(msb),
+            ParameterNumber::TUNING_BANK => Self::TuningBank(msb),
+            ParameterNumber::MODULATION_DEPTH_RANGE => Self::ModulationDepthRange {
+                semitones: msb,
+                cents: lsb,
+            },
+            ParameterNumber::MPE_CONFIGURATION => Self::MpeConfiguration {
+                member_channels: msb,
+            },
+            ParameterNumber::Registered(number) => Self::Registered {
+                number: number & 0x3FFF,
+                value: value & 0x3FFF,
+            },
+            ParameterNumber::NonRegistered(number) => Self::NonRegistered {
+                number: number & 0x3FFF,
+                value: value & 0x3FFF,
+            },
+        }
+    }
+
+    /// The parameter's number
+    pub const fn number(&self) -> ParameterNumber {
+        match self {
+            Self::PitchBendRange { .. } => ParameterNumber::PITCH_BEND_SENSITIVITY,
+            Self::FineTuning(_) => ParameterNumber::FINE_TUNING,
+            Self::CoarseTuning(_) => ParameterNumber::COARSE_TUNING,
+            Self::TuningProgram(_) => ParameterNumber::TUNING_PROGRAM,
+            Self::TuningBank(_) => ParameterNumber::TUNING_BANK,
+            Self::ModulationDepthRange { .. } => ParameterNumber::MODULATION_DEPTH_RANGE,
+            Self::MpeConfiguration { .. } => ParameterNumber::MPE_CONFIGURATION,
+            Self::Registered { number, .. } => ParameterNumber::Registered(*number),
+            Self::NonRegistered { number, .. } => ParameterNumber::NonRegistered(*number),
+        }
+    }
+
+    /// The 14-bit data entry value
+    pub const fn value(&self) -> u16 {
+        match self {
+            Self::PitchBendRange { semitones, cents }
+            | Self::ModulationDepthRange { semitones, cents } => {
+                (((*semitones & 0x7F) as u16) << 7) | (*cents & 0x7F) as u16
+            }
+            Self::FineTuning(value) => *value & 0x3FFF,
+            Self::CoarseTuning(msb) | Self::TuningProgram(msb) | Self::TuningBank(msb) => {
+                ((*msb & 0x7F) as u16) << 7
+            }
+            Self::MpeConfiguration { member_channels } => ((*member_channels & 0x7F) as u16) << 7,
+            Self::Registered { value, .. } | Self::NonRegistered { value, .. } => *value & 0x3FFF,
+        }
+    }
+
+    /// The controllers that set this parameter: the two that select it,
+    /// then the data entry MSB, and the data entry LSB if it isn't zero.
+    ///
+    /// Receivers keep the parameter selected afterwards. To deselect it, follow these with
+    /// [`ParameterNumber::NULL`]'s [`select`](ParameterNumber::select) controllers.
+    pub fn controllers(&self) -> impl Iterator<Item = Controller> + use<> {
+        let [select_msb, select_lsb] = self.number().select();
+        let value = self.value();
+        let msb = DataByte::new_unchecked((value >> 7) as u8);
+        let lsb = DataByte::new_unchecked((value & 0x7F) as u8);
+        let count = if lsb.value() == 0 { 3 } else { 4 };
+        [
+            select_msb,
+            select_lsb,
+            Controller::DataEntryCoarse(msb),
+            Controller::DataEntryFine(lsb),
+        ]
+        .into_iter()
+        .take(count)
+    }
+
+    /// The messages that set this parameter on a channel. See [`Parameter::controllers`].
+    pub fn messages(&self, channel: Channel) -> impl Iterator<Item = ChannelVoiceMessage> + use<> {
+        self.controllers()
+            .map(move |controller| channel.send_event(VoiceEvent::ControlChange(controller)))
+    }
+}
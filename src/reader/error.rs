@@ -5,7 +5,7 @@ use thiserror::Error;
 #[doc = r#"
 A set of errors that can occur while reading something into a midi representation
 "#]
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 #[error("Reading at Position {position}, {kind}")]
 pub struct ReaderError {
     position: usize,
@@ -13,7 +13,7 @@ pub struct ReaderError {
 }
 
 /// A kind of error that a reader can produce
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum ReaderErrorKind {
     /// Parsing errors
     #[error("Parsing {0}")]
@@ -30,11 +30,22 @@ impl ReaderErrorKind {
 }
 
 /// Errors reading from some [`MidiSource`](crate::prelude::MidiSource)
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone)]
 pub enum ReadError {
     /// Read out of bounds
     #[error("Read out of bounds!")]
     OutOfBounds,
+    /// Errors from the underlying [`Read`](std::io::Read) type
+    #[cfg(feature = "std")]
+    #[error("IO: {0}")]
+    Io(std::sync::Arc<std::io::Error>),
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ReadError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(std::sync::Arc::new(error))
+    }
 }
 
 impl ReaderError {
@@ -42,6 +53,23 @@ impl ReaderError {
     pub const fn new(position: usize, kind: ReaderErrorKind) -> Self {
         Self { position, kind }
     }
+
+    /// The byte offset from the start of the source at which the error occurred
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Shifts the position of the error, for sources nested in a larger file
+    pub(crate) const fn offset_by(mut self, offset: usize) -> Self {
+        self.position += offset;
+        self
+    }
+
+    /// The kind of error that occurred
+    pub const fn kind(&self) -> &ReaderErrorKind {
+        &self.kind
+    }
+
     /// True if out of bounds or unexpected end of file
     pub const fn is_out_of_bounds(&self) -> bool {
         matches!(
@@ -9,13 +9,19 @@ Inspired by <https://docs.rs/quick-xml/latest/quick_xml/>
 "]
 
 mod error;
+mod options;
 mod source;
 mod state;
+#[cfg(feature = "std")]
+mod stream;
 
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, vec::Vec};
 pub use error::*;
+pub use options::*;
 pub use source::*;
 use state::{ParseState, ReaderState};
+#[cfg(feature = "std")]
+pub use stream::*;
 
 use crate::prelude::*;
 
@@ -40,10 +46,13 @@ in the disk file.
 
 # Shortcomings
 
-This reader will be able to yield events from any type
-that is [`Read`](std::io::Read) in a future minor release.
+This reader requires random access to the whole source. To read from any type
+that is [`Read`](std::io::Read) without buffering the whole file first, use a
+[`StreamReader`] (or an [`AsyncStreamReader`] for async sources).
 
-For now, construct a `Reader<&[u8]>` to gain access to [`Reader::read_event`].
+# Damaged Files
+By default, the reader returns an error on the first problem it finds.
+See [`ReaderOptions`] to recover from damaged files instead.
 
 # Common Pitfalls
 This parser will not error if an unknown chunk type is found. It will assume
@@ -84,6 +93,8 @@ assert_eq!(
 pub struct Reader<R> {
     reader: R,
     pub(crate) state: ReaderState,
+    options: ReaderOptions,
+    diagnostics: Vec<ReaderError>,
 }
 
 impl<R> Reader<R> {
@@ -92,9 +103,48 @@ impl<R> Reader<R> {
         Self {
             reader,
             state: ReaderState::default(),
+            options: ReaderOptions::strict(),
+            diagnostics: Vec::new(),
         }
     }
 
+    /// The options used by this reader
+    pub const fn options(&self) -> ReaderOptions {
+        self.options
+    }
+
+    /// Change the options used by this reader
+    pub const fn set_options(&mut self, options: ReaderOptions) {
+        self.options = options;
+    }
+
+    /// Builder-style variant of [`Reader::set_options`]
+    #[must_use]
+    pub const fn with_options(mut self, options: ReaderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The problems that have been recovered from so far.
+    ///
+    /// Always empty unless recovery is enabled via [`ReaderOptions`].
+    pub fn diagnostics(&self) -> &[ReaderError] {
+        &self.diagnostics
+    }
+
+    /// Take the problems that have been recovered from so far
+    pub fn take_diagnostics(&mut self) -> Vec<ReaderError> {
+        core::mem::take(&mut self.diagnostics)
+    }
+
+    pub(crate) const fn recovering(&self) -> bool {
+        self.options.recovery()
+    }
+
+    pub(crate) fn diagnose(&mut self, error: ReaderError) {
+        self.diagnostics.push(error);
+    }
+
     /// Consume self to grab the inner reader
     pub fn into_inner(self) -> R {
         self.reader
@@ -125,10 +175,7 @@ impl<'slc> Reader<&'slc [u8]> {
     /// [`Reader::read_event`] method.
     #[must_use]
     pub const fn from_byte_slice(slice: &'slc [u8]) -> Self {
-        Self {
-            reader: slice,
-            state: ReaderState::default(),
-        }
+        Self::new(slice)
     }
 }
 
@@ -137,10 +184,7 @@ impl<'slc> Reader<Cow<'slc, [u8]>> {
     /// [`Reader::read_event`] method.
     #[must_use]
     pub fn from_bytes<B: Into<Cow<'slc, [u8]>>>(slice: B) -> Self {
-        Self {
-            reader: slice.into(),
-            state: ReaderState::default(),
-        }
+        Self::new(slice.into())
     }
 }
 
@@ -151,9 +195,6 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
     where
         'slc: 'slf,
     {
-        if self.buffer_position() > self.reader.max_len() {
-            return Err(ReaderError::oob(self.buffer_position()));
-        }
         let start = self.buffer_position();
 
         let end = start + bytes;
@@ -162,9 +203,12 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
             return Err(ReaderError::oob(self.buffer_position()));
         }
 
-        self.state.increment_offset(bytes);
+        let slice = self
+            .reader
+            .get_slice(start, end)
+            .ok_or(ReaderError::oob(self.buffer_position()))?;
 
-        let slice = self.reader.get_slice(start, end).unwrap();
+        self.state.increment_offset(bytes);
 
         Ok(slice)
     }
@@ -215,6 +259,65 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
         let size = decode_varlen(self)?;
         self.read_exact(size as usize)
     }
+
+    /// Reads the 4-byte length of a chunk.
+    ///
+    /// When recovering, a length that runs past the end of the source is clamped.
+    pub(crate) fn read_chunk_length(&mut self) -> ReadResult<u32> {
+        let position = self.buffer_position();
+        let length = u32::from_be_bytes(self.read_exact_size()?);
+        if !self.recovering() {
+            return Ok(length);
+        }
+        let available = self.reader.max_len().saturating_sub(self.buffer_position());
+        if length as usize > available {
+            self.diagnose(ReaderError::parse_error(
+                position,
+                ChunkError::LengthPastEndOfFile(length).into(),
+            ));
+            return Ok(available as u32);
+        }
+        Ok(length)
+    }
+
+    /// Returns true if the bytes at `pos` are the type of a track chunk
+    fn at_track_chunk(&self, pos: usize) -> bool {
+        self.reader
+            .get_slice(pos, pos + 4)
+            .is_some_and(|name| name.as_ref() == b"MTrk")
+    }
+
+    /// Returns the position of the next track chunk in `start..end`
+    fn find_track_chunk(&self, start: usize, end: usize) -> Option<usize> {
+        let bytes = self
+            .reader
+            .get_slice(start, end.min(self.reader.max_len()))?;
+        bytes
+            .windows(4)
+            .position(|name| name == b"MTrk")
+            .map(|i| start + i)
+    }
+
+    /// When recovering, checks that a chunk type read at `chunk_start` is made up
+    /// of ASCII characters. If it isn't, skips to the next track chunk.
+    ///
+    /// Returns false if the chunk type should be read again.
+    fn check_chunk_type(&mut self, chunk_start: usize, name: &[u8]) -> bool {
+        if !self.recovering() || name.iter().all(|b| b.is_ascii_alphanumeric() || *b == b' ') {
+            return true;
+        }
+        let mut invalid = [0; 4];
+        invalid.copy_from_slice(name);
+        self.diagnose(ReaderError::parse_error(
+            chunk_start,
+            ChunkError::InvalidType(invalid).into(),
+        ));
+        let next = self
+            .find_track_chunk(chunk_start + 1, self.reader.max_len())
+            .unwrap_or(self.reader.max_len());
+        self.state.set_offset(next);
+        false
+    }
 }
 
 pub(crate) fn decode_varlen<'slc, R: MidiSource<'slc>>(reader: &mut Reader<R>) -> ReadResult<u32> {
@@ -252,7 +355,8 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
     ///
     /// # Errors
     ///
-    /// If the next set of bytes are invalid given the current state of the reader
+    /// If the next set of bytes are invalid given the current state of the reader.
+    /// When recovering, errors are only returned if the header is invalid.
     pub fn read_event<'a>(&mut self) -> ReadResult<FileEvent<'a>>
     where
         'slc: 'a,
@@ -265,6 +369,7 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
                     continue;
                 }
                 ParseState::InsideMidi => {
+                    let chunk_start = self.buffer_position();
                     // expect only a header or track chunk
                     let chunk = match self.read_exact(4) {
                         Ok(c) => c,
@@ -284,15 +389,20 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
                         }
                         b"MTrk" => {
                             let chunk = TrackChunkHeader::read(self)?;
+                            self.state.set_skipping(false);
                             //todo: set new state
                             self.state.set_parse_state(ParseState::InsideTrack {
                                 start: self.buffer_position(),
                                 length: chunk.len() as usize,
                                 prev_status: None,
+                                unbounded: false,
                             });
                             break FileEvent::Track(chunk);
                         }
-                        _ => {
+                        name => {
+                            if !self.check_chunk_type(chunk_start, name) {
+                                continue;
+                            }
                             //let chunk
                             let chunk = UnknownChunk::read(chunk, self)?;
 
@@ -304,16 +414,35 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
                     start,
                     length,
                     prev_status,
+                    unbounded,
                 } => {
-                    if start + length <= self.buffer_position() {
-                        //end of track events
-                        self.state.set_parse_state(ParseState::InsideMidi);
+                    let position = self.buffer_position();
+                    let at_end =
+                        start + length <= position || (unbounded && self.at_track_chunk(position));
+                    if at_end {
+                        if self.recovering() {
+                            self.recover_track_end(start, unbounded);
+                        } else {
+                            //end of track events
+                            self.state.set_parse_state(ParseState::InsideMidi);
+                        }
                         continue;
                     }
                     running_status = prev_status;
 
-                    let ev = TrackEvent::read(self, &mut running_status)?;
-                    break FileEvent::TrackEvent(ev);
+                    match TrackEvent::read(self, &mut running_status) {
+                        Ok(ev) => {
+                            self.state.set_skipping(false);
+                            if self.recovering()
+                                && ev.event() == &TrackMessage::Meta(MetaMessage::EndOfTrack)
+                            {
+                                self.recover_end_of_track(start + length, unbounded);
+                            }
+                            break FileEvent::TrackEvent(ev);
+                        }
+                        Err(e) if !self.recovering() => return Err(e),
+                        Err(e) => self.recover_track_event(position, start + length, unbounded, e),
+                    }
                 }
                 ParseState::Done => break FileEvent::EOF,
             }
@@ -325,11 +454,101 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
         Ok(event)
     }
 
+    /// The end of a track's chunk has been reached without an `EndOfTrack`.
+    ///
+    /// If the next bytes aren't a chunk, the track's length was too short,
+    /// so it is extended until its `EndOfTrack` or the next track chunk.
+    fn recover_track_end(&mut self, start: usize, unbounded: bool) {
+        let position = self.buffer_position();
+        let next_is_chunk = match self.reader.get_slice(position, position + 4) {
+            Some(name) => name.iter().all(|b| b.is_ascii_alphanumeric() || *b == b' '),
+            None => true,
+        };
+        if unbounded || next_is_chunk {
+            self.diagnose(ReaderError::parse_error(
+                position,
+                TrackError::MissingEndOfTrack.into(),
+            ));
+            self.state.set_parse_state(ParseState::InsideMidi);
+            return;
+        }
+        self.diagnose(ReaderError::parse_error(
+            position,
+            TrackError::LengthMismatch.into(),
+        ));
+        if let ParseState::InsideTrack {
+            length, unbounded, ..
+        } = self.state.parse_state_mut()
+        {
+            *length = self.reader.max_len() - start;
+            *unbounded = true;
+        }
+    }
+
+    /// An `EndOfTrack` has been read. Any bytes left in the chunk are skipped,
+    /// unless the next track begins right away (the track's length was too long).
+    fn recover_end_of_track(&mut self, end: usize, unbounded: bool) {
+        let position = self.buffer_position();
+        if position < end && !unbounded {
+            if self.at_track_chunk(position) {
+                self.diagnose(ReaderError::parse_error(
+                    position,
+                    TrackError::LengthMismatch.into(),
+                ));
+            } else {
+                self.state.set_offset(end);
+            }
+        }
+        self.state.set_parse_state(ParseState::InsideMidi);
+    }
+
+    /// An event starting at `event_start` could not be read.
+    ///
+    /// If the track's chunk contains another track chunk, the track's length was too long,
+    /// so reading resumes there. Otherwise, the invalid bytes are skipped.
+    fn recover_track_event(
+        &mut self,
+        event_start: usize,
+        end: usize,
+        unbounded: bool,
+        error: ReaderError,
+    ) {
+        if error.is_out_of_bounds() {
+            // the track was cut off by the end of the source
+            self.diagnose(error);
+            self.state.set_offset(self.reader.max_len());
+            self.state.set_parse_state(ParseState::InsideMidi);
+            return;
+        }
+        let resume = error.position().max(event_start + 1);
+        if self.state.skipping() {
+            // this run of bad bytes has already been reported
+            self.state.set_offset(resume);
+            return;
+        }
+        self.diagnose(error);
+        self.state.set_skipping(true);
+        let next = if unbounded {
+            None
+        } else {
+            self.find_track_chunk(event_start + 1, end)
+        };
+        match next {
+            Some(next) => {
+                self.state.set_offset(next);
+                self.state.set_parse_state(ParseState::InsideMidi);
+            }
+            None => self.state.set_offset(resume),
+        }
+    }
+
     /// Read the buffer and return a chunk
     ///
     /// # Errors
     ///
-    /// If the next set of bytes are invalid given the current state of the reader
+    /// If the next set of bytes are invalid given the current state of the reader.
+    /// When recovering, chunk lengths are clamped, and garbage between chunks is skipped.
+    /// The events of a track are not checked.
     pub fn read_chunk<'a>(&mut self) -> ReadResult<ChunkEvent<'a>>
     where
         'slc: 'a,
@@ -341,6 +560,7 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
                     continue;
                 }
                 ParseState::InsideMidi => {
+                    let chunk_start = self.buffer_position();
                     // expect only a header or track chunk
                     let chunk = match self.read_exact(4) {
                         Ok(c) => c,
@@ -365,7 +585,10 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
                             let chunk = RawTrackChunk::read(self)?;
                             break ChunkEvent::Track(chunk);
                         }
-                        _ => {
+                        name => {
+                            if !self.check_chunk_type(chunk_start, name) {
+                                continue;
+                            }
                             //let chunk
                             let chunk = UnknownChunk::read(chunk, self)?;
                             break ChunkEvent::Unknown(chunk);
@@ -376,6 +599,7 @@ impl<'slc, R: MidiSource<'slc>> Reader<R> {
                     start,
                     length,
                     prev_status: _,
+                    unbounded: _,
                 } => {
                     /*
                     If this happens, then read_event was previously called.
@@ -0,0 +1,56 @@
+#[doc = r#"
+Options that change how a [`Reader`](super::Reader) handles malformed files.
+
+By default, a reader is strict: the first problem found is returned as an error.
+
+With recovery enabled, the reader will instead try to make sense of damaged
+files the way most players do:
+- Chunk lengths that run past the end of the file are clamped.
+- Tracks whose length doesn't line up with their `EndOfTrack` are resynchronized
+  on the next `MTrk` chunk.
+- Invalid events are skipped.
+- Garbage between chunks is skipped up to the next `MTrk` chunk.
+
+Every problem is recorded as a diagnostic, which can be retrieved with
+[`Reader::diagnostics`](super::Reader::diagnostics).
+
+# Example
+```rust
+use midix::prelude::*;
+
+let bytes = include_bytes!("../../test-asset/PiDamaged.mid");
+
+let mut reader = Reader::from_byte_slice(bytes).with_options(ReaderOptions::lenient());
+while reader.read_event().unwrap() != FileEvent::EOF {}
+
+assert!(!reader.diagnostics().is_empty());
+```
+"#]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReaderOptions {
+    recover: bool,
+}
+
+impl ReaderOptions {
+    /// Options that fail on the first problem found. This is the default.
+    pub const fn strict() -> Self {
+        Self { recover: false }
+    }
+
+    /// Options that recover from problems where possible, recording them as diagnostics
+    pub const fn lenient() -> Self {
+        Self { recover: true }
+    }
+
+    /// Enable or disable recovery
+    #[must_use]
+    pub const fn with_recovery(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// Returns true if the reader should recover from malformed input
+    pub const fn recovery(&self) -> bool {
+        self.recover
+    }
+}
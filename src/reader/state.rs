@@ -13,6 +13,9 @@ pub(crate) enum ParseState {
         start: usize,
         length: usize,
         prev_status: Option<u8>,
+        /// The length of the track could not be trusted while recovering,
+        /// so the track ends at the next `MTrk` or `EndOfTrack` instead.
+        unbounded: bool,
     },
     /// Reader enters this state when `Eof` event generated or an error occurred.
     /// This is the last state, the reader stay in it forever.
@@ -24,6 +27,9 @@ pub(crate) struct ReaderState {
     offset: usize,
     last_error_offset: usize,
     state: ParseState,
+    /// True while recovering from an invalid event, so that
+    /// a run of bad bytes is only reported once
+    skipping: bool,
 }
 
 #[allow(dead_code)]
@@ -34,6 +40,7 @@ impl ReaderState {
             offset: 0,
             last_error_offset: 0,
             state: ParseState::Init,
+            skipping: false,
         }
     }
     #[must_use]
@@ -64,6 +71,12 @@ impl ReaderState {
     pub const fn set_last_error_offset(&mut self, offset: usize) {
         self.last_error_offset = offset;
     }
+    pub const fn skipping(&self) -> bool {
+        self.skipping
+    }
+    pub const fn set_skipping(&mut self, skipping: bool) {
+        self.skipping = skipping;
+    }
     pub const fn increment_last_error_offset(&mut self, offset: usize) {
         self.last_error_offset += offset;
     }
@@ -0,0 +1,354 @@
+use alloc::{borrow::Cow, vec::Vec};
+use core::{future::poll_fn, pin::Pin};
+use std::io::{self, Read};
+
+use futures_io::AsyncRead;
+
+use super::{MidiSource, ReadError, ReadResult, Reader, ReaderError};
+use crate::prelude::{ChunkEvent, FileEvent};
+
+/// The number of bytes requested from the underlying stream at a time
+const BLOCK_SIZE: usize = 8 * 1024;
+
+/// Bytes that have been pulled from a stream and not yet discarded.
+///
+/// Positions are absolute offsets from the start of the stream,
+/// so errors produced by a [`Reader`] over this buffer report
+/// the same offsets as they would for the whole file.
+#[derive(Default)]
+pub(crate) struct StreamBuffer {
+    /// absolute offset of `bytes[0]`
+    start: usize,
+    bytes: Vec<u8>,
+    eof: bool,
+}
+
+impl StreamBuffer {
+    fn end(&self) -> usize {
+        self.start + self.bytes.len()
+    }
+
+    /// Drops everything before `pos`. Bytes are only drained once a
+    /// full block is unused so that the copy is amortized.
+    fn discard_before(&mut self, pos: usize) {
+        let unused = pos.saturating_sub(self.start).min(self.bytes.len());
+        if unused >= BLOCK_SIZE {
+            self.bytes.drain(..unused);
+            self.start += unused;
+        }
+    }
+
+    /// Reserves a block at the end of the buffer, returning it for the stream to fill
+    fn spare_block(&mut self) -> &mut [u8] {
+        let len = self.bytes.len();
+        self.bytes.resize(len + BLOCK_SIZE, 0);
+        &mut self.bytes[len..]
+    }
+
+    /// Takes every byte pulled so far, assuming none have been discarded
+    fn take_bytes(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.bytes)
+    }
+
+    /// Trims the reserved block down to the `read` bytes that were filled
+    fn commit_block(&mut self, read: io::Result<usize>) -> io::Result<()> {
+        let len = self.bytes.len() - BLOCK_SIZE;
+        match read {
+            Ok(n) => {
+                self.bytes.truncate(len + n);
+                self.eof = n == 0;
+                Ok(())
+            }
+            Err(e) => {
+                self.bytes.truncate(len);
+                if e.kind() != io::ErrorKind::Interrupted {
+                    self.eof = true;
+                    return Err(e);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl MidiSource<'static> for StreamBuffer {
+    /// The absolute offset of the end of the bytes pulled so far
+    fn max_len(&self) -> usize {
+        self.end()
+    }
+
+    fn get_slice(&self, start: usize, end: usize) -> Option<Cow<'static, [u8]>> {
+        let start = start.checked_sub(self.start)?;
+        let end = end.checked_sub(self.start)?;
+        self.bytes.get(start..end).map(|s| s.to_vec().into())
+    }
+
+    fn get_byte(&self, pos: usize) -> Option<u8> {
+        self.bytes.get(pos.checked_sub(self.start)?).copied()
+    }
+}
+
+impl Reader<StreamBuffer> {
+    /// Parses with `read` over the buffered bytes.
+    ///
+    /// Returns `None` if more bytes are needed, leaving the reader
+    /// in the state it was in before the attempt.
+    fn try_read<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> ReadResult<T>,
+        is_eof: impl FnOnce(&T) -> bool,
+    ) -> Option<ReadResult<T>> {
+        let state = self.state.clone();
+        let position = self.buffer_position();
+        self.get_mut().discard_before(position);
+
+        let result = read(self);
+        let incomplete = match &result {
+            Ok(event) => is_eof(event),
+            Err(e) => e.is_out_of_bounds(),
+        };
+        if incomplete && !self.get_ref().eof {
+            self.state = state;
+            return None;
+        }
+        Some(result)
+    }
+
+    fn io_error(&self, e: io::Error) -> ReaderError {
+        ReaderError::new(self.buffer_position(), ReadError::from(e).into())
+    }
+}
+
+#[doc = r#"
+A [`Reader`] that pulls bytes from any [`Read`] type.
+
+Bytes are buffered in blocks as they are needed, and discarded once
+they have been read past, so a file does not need to be loaded into
+memory before it can be parsed. Events are owned, since they outlive
+the bytes they were read from.
+
+Error positions are offsets from the start of the stream.
+
+See [`AsyncStreamReader`] for [`AsyncRead`] types.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let file = std::fs::File::open("test-asset/Clementi.mid").unwrap();
+let mut reader = StreamReader::new(std::io::BufReader::new(file));
+
+let Ok(FileEvent::Header(header)) = reader.read_event() else {
+    panic!("Expected a header event");
+};
+assert_eq!(header.format_type(), FormatType::Simultaneous);
+```
+"#]
+pub struct StreamReader<R> {
+    stream: R,
+    reader: Reader<StreamBuffer>,
+}
+
+impl<R> StreamReader<R> {
+    /// Create a new reader over a stream
+    pub fn new(stream: R) -> Self {
+        Self {
+            stream,
+            reader: Reader::new(StreamBuffer::default()),
+        }
+    }
+
+    /// Consume self to grab the inner stream.
+    ///
+    /// Bytes that have been buffered but not yet parsed are lost.
+    pub fn into_inner(self) -> R {
+        self.stream
+    }
+
+    /// Gets a reference to the underlying stream
+    pub const fn get_ref(&self) -> &R {
+        &self.stream
+    }
+
+    /// Gets a mutable reference to the underlying stream
+    pub const fn get_mut(&mut self) -> &mut R {
+        &mut self.stream
+    }
+
+    /// Grab the current position of the reader "cursor", from the start of the stream
+    pub const fn buffer_position(&self) -> usize {
+        self.reader.buffer_position()
+    }
+}
+
+impl<R: Read> StreamReader<R> {
+    fn fill(&mut self) -> ReadResult<()> {
+        let buffer = self.reader.get_mut();
+        let read = self.stream.read(buffer.spare_block());
+        buffer
+            .commit_block(read)
+            .map_err(|e| self.reader.io_error(e))
+    }
+
+    /// Returns the first `len` bytes of the stream, or fewer if the stream is shorter.
+    ///
+    /// Must be called before any events are read.
+    pub(crate) fn peek_start(&mut self, len: usize) -> ReadResult<&[u8]> {
+        while self.reader.get_ref().end() < len && !self.reader.get_ref().eof {
+            self.fill()?;
+        }
+        let bytes = &self.reader.get_ref().bytes;
+        Ok(&bytes[..len.min(bytes.len())])
+    }
+
+    /// Reads the whole stream into memory.
+    ///
+    /// Must be called before any events are read.
+    pub(crate) fn read_to_end(mut self) -> ReadResult<Vec<u8>> {
+        while !self.reader.get_ref().eof {
+            self.fill()?;
+        }
+        Ok(self.reader.get_mut().take_bytes())
+    }
+
+    /// Read the stream and return an event
+    ///
+    /// # Errors
+    ///
+    /// If the next set of bytes are invalid given the current state of the reader,
+    /// or the stream fails.
+    pub fn read_event(&mut self) -> ReadResult<FileEvent<'static>> {
+        loop {
+            if let Some(result) = self
+                .reader
+                .try_read(Reader::read_event, |e| *e == FileEvent::EOF)
+            {
+                return result;
+            }
+            self.fill()?;
+        }
+    }
+
+    /// Read the stream and return a chunk
+    ///
+    /// # Errors
+    ///
+    /// If the next set of bytes are invalid given the current state of the reader,
+    /// or the stream fails.
+    pub fn read_chunk(&mut self) -> ReadResult<ChunkEvent<'static>> {
+        loop {
+            if let Some(result) = self.reader.try_read(Reader::read_chunk, ChunkEvent::is_eof) {
+                return result;
+            }
+            self.fill()?;
+        }
+    }
+}
+
+#[doc = r#"
+A [`Reader`] that pulls bytes from any [`AsyncRead`] type, such as
+Bevy's asset [`Reader`](https://docs.rs/bevy/latest/bevy/asset/io/trait.Reader.html).
+
+This is the async counterpart of [`StreamReader`].
+"#]
+pub struct AsyncStreamReader<R> {
+    stream: R,
+    reader: Reader<StreamBuffer>,
+}
+
+impl<R> AsyncStreamReader<R> {
+    /// Create a new reader over an async stream
+    pub fn new(stream: R) -> Self {
+        Self {
+            stream,
+            reader: Reader::new(StreamBuffer::default()),
+        }
+    }
+
+    /// Consume self to grab the inner stream.
+    ///
+    /// Bytes that have been buffered but not yet parsed are lost.
+    pub fn into_inner(self) -> R {
+        self.stream
+    }
+
+    /// Gets a reference to the underlying stream
+    pub const fn get_ref(&self) -> &R {
+        &self.stream
+    }
+
+    /// Gets a mutable reference to the underlying stream
+    pub const fn get_mut(&mut self) -> &mut R {
+        &mut self.stream
+    }
+
+    /// Grab the current position of the reader "cursor", from the start of the stream
+    pub const fn buffer_position(&self) -> usize {
+        self.reader.buffer_position()
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncStreamReader<R> {
+    async fn fill(&mut self) -> ReadResult<()> {
+        let buffer = self.reader.get_mut();
+        let block = buffer.spare_block();
+        let read = poll_fn(|cx| Pin::new(&mut self.stream).poll_read(cx, block)).await;
+        buffer
+            .commit_block(read)
+            .map_err(|e| self.reader.io_error(e))
+    }
+
+    /// Returns the first `len` bytes of the stream, or fewer if the stream is shorter.
+    ///
+    /// Must be called before any events are read.
+    pub(crate) async fn peek_start(&mut self, len: usize) -> ReadResult<&[u8]> {
+        while self.reader.get_ref().end() < len && !self.reader.get_ref().eof {
+            self.fill().await?;
+        }
+        let bytes = &self.reader.get_ref().bytes;
+        Ok(&bytes[..len.min(bytes.len())])
+    }
+
+    /// Reads the whole stream into memory.
+    ///
+    /// Must be called before any events are read.
+    pub(crate) async fn read_to_end(mut self) -> ReadResult<Vec<u8>> {
+        while !self.reader.get_ref().eof {
+            self.fill().await?;
+        }
+        Ok(self.reader.get_mut().take_bytes())
+    }
+
+    /// Read the stream and return an event
+    ///
+    /// # Errors
+    ///
+    /// If the next set of bytes are invalid given the current state of the reader,
+    /// or the stream fails.
+    pub async fn read_event(&mut self) -> ReadResult<FileEvent<'static>> {
+        loop {
+            if let Some(result) = self
+                .reader
+                .try_read(Reader::read_event, |e| *e == FileEvent::EOF)
+            {
+                return result;
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// Read the stream and return a chunk
+    ///
+    /// # Errors
+    ///
+    /// If the next set of bytes are invalid given the current state of the reader,
+    /// or the stream fails.
+    pub async fn read_chunk(&mut self) -> ReadResult<ChunkEvent<'static>> {
+        loop {
+            if let Some(result) = self.reader.try_read(Reader::read_chunk, ChunkEvent::is_eof) {
+                return result;
+            }
+            self.fill().await?;
+        }
+    }
+}
@@ -0,0 +1,270 @@
+#![doc = r#"
+Renders MIDI files to audio offline, with the [`midix_synth`] SoundFont synthesizer
+
+See [`render`] for more information
+"#]
+
+mod wav;
+pub use wav::*;
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::{
+    prelude::*,
+    synth::{BusLayout, SoundFont, Synthesizer, SynthesizerError, SynthesizerSettings},
+};
+
+/// How a file is rendered by [`render`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderSettings {
+    /// The sample rate of the audio, from 16000 to 192000
+    pub sample_rate: u32,
+    /// How long to keep rendering after the last event of the file,
+    /// so the released notes and the reverb can fade out
+    pub release_tail: Duration,
+    /// The most voices that can sound at once, from 8 to 256
+    pub maximum_polyphony: usize,
+    /// Adds the synthesizer's reverb and chorus
+    pub enable_reverb_and_chorus: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self::new(44_100)
+    }
+}
+
+impl RenderSettings {
+    /// Settings with a two second release tail, 64 voices, and reverb and chorus
+    pub const fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            release_tail: Duration::from_secs(2),
+            maximum_polyphony: 64,
+            enable_reverb_and_chorus: true,
+        }
+    }
+
+    /// The number of samples in a length of time
+    fn samples(&self, micros: u64) -> u64 {
+        (micros as u128 * self.sample_rate as u128 / 1_000_000) as u64
+    }
+}
+
+/// Stereo audio rendered by [`render`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderedAudio {
+    sample_rate: u32,
+    /// Left then right for each frame
+    samples: Vec<f32>,
+}
+
+impl RenderedAudio {
+    /// The number of channels, which is always 2
+    pub const CHANNELS: u16 = 2;
+
+    /// The sample rate of the audio
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The interleaved samples, left then right, from -1 to 1
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Take the interleaved samples
+    pub fn into_samples(self) -> Vec<f32> {
+        self.samples
+    }
+
+    /// The number of frames, each holding a sample for the left and right channels
+    pub fn frames(&self) -> usize {
+        self.samples.len() / Self::CHANNELS as usize
+    }
+
+    /// The length of the audio
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames() as f64 / self.sample_rate as f64)
+    }
+
+    /// The interleaved samples as 16-bit PCM. Samples outside of -1 to 1 are clipped.
+    pub fn to_pcm16(&self) -> Vec<i16> {
+        self.samples.iter().map(|sample| pcm16(*sample)).collect()
+    }
+
+    /// Write the audio as a WAV file
+    pub fn write_wav<W: std::io::Write>(
+        &self,
+        writer: W,
+        format: SampleFormat,
+    ) -> std::io::Result<()> {
+        wav::write(writer, self, format)
+    }
+}
+
+#[doc = r#"
+Renders a file with a SoundFont, as fast as the synthesizer can go.
+
+Every track is played at once, following the tempo map of the file. Each event is scheduled
+on the synthesizer at the sample it falls on, so notes start on their exact sample no matter
+how the synthesizer splits up its rendering. System exclusive messages are sent as well,
+so files can reset, retune or switch their drum parts.
+
+The audio lasts until the last event of the file, followed by the
+[`release_tail`](RenderSettings::release_tail).
+
+# Example
+```rust,no_run
+use midix::prelude::*;
+use midix::{render::render, synth::SoundFont};
+
+let bytes = std::fs::read("song.mid").unwrap();
+let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+let mut font = std::fs::File::open("font.sf2").unwrap();
+let sound_font = SoundFont::new(&mut font).unwrap();
+
+let audio = render(&file, &sound_font, &RenderSettings::default()).unwrap();
+let wav = std::fs::File::create("song.wav").unwrap();
+audio
+    .write_wav(std::io::BufWriter::new(wav), SampleFormat::Pcm16)
+    .unwrap();
+```
+"#]
+pub fn render(
+    file: &ParsedMidiFile<'_>,
+    sound_font: &SoundFont,
+    settings: &RenderSettings,
+) -> Result<RenderedAudio, SynthesizerError> {
+    let (mut synthesizer, frames) = prepare(file, sound_font, settings)?;
+    let mut samples = Vec::with_capacity(frames * RenderedAudio::CHANNELS as usize);
+
+    let mut left = [0_f32; CHUNK];
+    let mut right = [0_f32; CHUNK];
+    let mut remaining = frames;
+    while remaining > 0 {
+        let len = remaining.min(CHUNK);
+        synthesizer.render(&mut left[..len], &mut right[..len]);
+        interleave(&mut samples, &left[..len], &right[..len]);
+        remaining -= len;
+    }
+
+    Ok(RenderedAudio {
+        sample_rate: settings.sample_rate,
+        samples,
+    })
+}
+
+#[doc = r#"
+Renders a file to stems: the audio of each bus of a [`BusLayout`], such as one per MIDI channel.
+
+The file is played as [`render`] plays it, and the stems add up to its audio. With
+[`BusEffects::Shared`](crate::synth::BusEffects::Shared), the last stem holds the reverb and
+chorus of every channel.
+
+# Example
+```rust,no_run
+use midix::prelude::*;
+use midix::{render::render_stems, synth::{BusLayout, SoundFont}};
+
+let bytes = std::fs::read("song.mid").unwrap();
+let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+let mut font = std::fs::File::open("font.sf2").unwrap();
+let sound_font = SoundFont::new(&mut font).unwrap();
+
+let layout = BusLayout::per_channel();
+let stems = render_stems(&file, &sound_font, &RenderSettings::default(), layout).unwrap();
+for (i, stem) in stems.iter().enumerate() {
+    let wav = std::fs::File::create(format!("stem_{i}.wav")).unwrap();
+    stem.write_wav(std::io::BufWriter::new(wav), SampleFormat::Pcm16)
+        .unwrap();
+}
+```
+"#]
+pub fn render_stems(
+    file: &ParsedMidiFile<'_>,
+    sound_font: &SoundFont,
+    settings: &RenderSettings,
+    layout: BusLayout,
+) -> Result<Vec<RenderedAudio>, SynthesizerError> {
+    let (mut synthesizer, frames) = prepare(file, sound_font, settings)?;
+    synthesizer.set_bus_layout(Some(layout));
+
+    let outputs = layout.get_output_count();
+    let mut stems = (0..outputs)
+        .map(|_| Vec::with_capacity(frames * RenderedAudio::CHANNELS as usize))
+        .collect::<Vec<_>>();
+
+    let mut left = alloc::vec![[0_f32; CHUNK]; outputs];
+    let mut right = alloc::vec![[0_f32; CHUNK]; outputs];
+    let mut remaining = frames;
+    while remaining > 0 {
+        let len = remaining.min(CHUNK);
+        let mut left_buffers = left
+            .iter_mut()
+            .map(|left| &mut left[..len])
+            .collect::<Vec<_>>();
+        let mut right_buffers = right
+            .iter_mut()
+            .map(|right| &mut right[..len])
+            .collect::<Vec<_>>();
+        synthesizer.render_buses(&mut left_buffers, &mut right_buffers);
+        for ((samples, left), right) in stems.iter_mut().zip(&left).zip(&right) {
+            interleave(samples, &left[..len], &right[..len]);
+        }
+        remaining -= len;
+    }
+
+    Ok(stems
+        .into_iter()
+        .map(|samples| RenderedAudio {
+            sample_rate: settings.sample_rate,
+            samples,
+        })
+        .collect())
+}
+
+/// The number of frames rendered at a time
+const CHUNK: usize = 4096;
+
+/// Creates the synthesizer with every event of the file scheduled,
+/// and returns the number of frames to render
+fn prepare(
+    file: &ParsedMidiFile<'_>,
+    sound_font: &SoundFont,
+    settings: &RenderSettings,
+) -> Result<(Synthesizer, usize), SynthesizerError> {
+    let mut synth_settings = SynthesizerSettings::new(settings.sample_rate as i32);
+    synth_settings.maximum_polyphony = settings.maximum_polyphony;
+    synth_settings.enable_reverb_and_chorus = settings.enable_reverb_and_chorus;
+    let mut synthesizer = Synthesizer::new(sound_font.clone(), &synth_settings)?;
+
+    let sequencer = Sequencer::new(file);
+    for event in sequencer.events() {
+        let delay = settings.samples(event.micros()) as usize;
+        match event.event() {
+            LiveEvent::ChannelVoice(message) => synthesizer.schedule_voice(delay, message),
+            LiveEvent::SysCommon(SystemCommonMessage::SystemExclusive(sysex)) => {
+                synthesizer.schedule_sysex(delay, sysex.data())
+            }
+            _ => {}
+        }
+    }
+
+    let frames = settings.samples(sequencer.length_micros())
+        + settings.samples(settings.release_tail.as_micros() as u64);
+    Ok((synthesizer, frames as usize))
+}
+
+/// Appends left then right for each frame
+fn interleave(samples: &mut Vec<f32>, left: &[f32], right: &[f32]) {
+    for (left, right) in left.iter().zip(right) {
+        samples.extend([*left, *right]);
+    }
+}
+
+/// A sample as 16-bit PCM
+fn pcm16(sample: f32) -> i16 {
+    (sample.clamp(-1., 1.) * i16::MAX as f32) as i16
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Write};
+
+use super::{RenderedAudio, pcm16};
+
+/// How samples are stored in a WAV file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum SampleFormat {
+    /// 16-bit integers, which every player can read
+    #[default]
+    Pcm16,
+    /// 32-bit floats, which keep the full range of the synthesizer
+    Float32,
+}
+
+impl SampleFormat {
+    /// The format tag of the `fmt ` chunk
+    const fn tag(&self) -> u16 {
+        match self {
+            Self::Pcm16 => 1,
+            Self::Float32 => 3,
+        }
+    }
+
+    /// Formats other than integer PCM extend the `fmt ` chunk with its (empty) extension size,
+    /// and add a `fact` chunk
+    const fn is_extended(&self) -> bool {
+        matches!(self, Self::Float32)
+    }
+
+    /// The size of a sample in bytes
+    const fn bytes(&self) -> u16 {
+        match self {
+            Self::Pcm16 => 2,
+            Self::Float32 => 4,
+        }
+    }
+}
+
+/// Writes a RIFF WAVE file: the `fmt ` chunk, the `fact` chunk for float samples,
+/// then the `data` chunk
+pub(super) fn write<W: Write>(
+    mut writer: W,
+    audio: &RenderedAudio,
+    format: SampleFormat,
+) -> io::Result<()> {
+    let channels = RenderedAudio::CHANNELS;
+    let block_align = channels * format.bytes();
+    let too_long = || io::Error::new(io::ErrorKind::InvalidInput, "too long for a WAV file");
+    let fmt_len: u32 = if format.is_extended() { 18 } else { 16 };
+    let fact_len: u32 = if format.is_extended() { 8 + 4 } else { 0 };
+    let data_len = audio
+        .samples()
+        .len()
+        .checked_mul(format.bytes() as usize)
+        .and_then(|len| u32::try_from(len).ok())
+        .ok_or_else(too_long)?;
+    let riff_len = (4 + 8 + fmt_len + fact_len + 8)
+        .checked_add(data_len)
+        .ok_or_else(too_long)?;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&riff_len.to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&fmt_len.to_le_bytes())?;
+    writer.write_all(&format.tag().to_le_bytes())?;
+    writer.write_all(&channels.to_le_bytes())?;
+    writer.write_all(&audio.sample_rate().to_le_bytes())?;
+    writer.write_all(&(audio.sample_rate() * block_align as u32).to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&(format.bytes() * 8).to_le_bytes())?;
+    if format.is_extended() {
+        writer.write_all(&0_u16.to_le_bytes())?;
+
+        // the number of frames
+        writer.write_all(b"fact")?;
+        writer.write_all(&4_u32.to_le_bytes())?;
+        writer.write_all(&(audio.frames() as u32).to_le_bytes())?;
+    }
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())?;
+    for sample in audio.samples() {
+        match format {
+            SampleFormat::Pcm16 => writer.write_all(&pcm16(*sample).to_le_bytes())?,
+            SampleFormat::Float32 => writer.write_all(&sample.to_le_bytes())?,
+        }
+    }
+    writer.flush()
+}
+
+#[test]
+fn header() {
+    let audio = RenderedAudio {
+        sample_rate: 48_000,
+        samples: alloc::vec![0., 1., -1., 2.],
+    };
+
+    let mut bytes = alloc::vec::Vec::new();
+    audio.write_wav(&mut bytes, SampleFormat::Pcm16).unwrap();
+    assert_eq!(bytes.len(), 44 + 8);
+    assert_eq!(&bytes[..4], b"RIFF");
+    assert_eq!(bytes[4..8], 44_u32.to_le_bytes());
+    assert_eq!(bytes[20..22], 1_u16.to_le_bytes());
+    assert_eq!(bytes[24..28], 48_000_u32.to_le_bytes());
+    assert_eq!(bytes[28..32], (48_000_u32 * 4).to_le_bytes());
+    assert_eq!(bytes[34..36], 16_u16.to_le_bytes());
+    assert_eq!(bytes[40..44], 8_u32.to_le_bytes());
+    // the last sample is clipped
+    assert_eq!(bytes[44..], [0, 0, 0xFF, 0x7F, 0x01, 0x80, 0xFF, 0x7F]);
+
+    let mut bytes = alloc::vec::Vec::new();
+    audio.write_wav(&mut bytes, SampleFormat::Float32).unwrap();
+    // an extended `fmt ` chunk, and a `fact` chunk
+    assert_eq!(bytes.len(), 44 + 2 + 12 + 16);
+    assert_eq!(bytes[4..8], (4 + 26 + 12 + 8 + 16_u32).to_le_bytes());
+    assert_eq!(bytes[16..20], 18_u32.to_le_bytes());
+    assert_eq!(bytes[20..22], 3_u16.to_le_bytes());
+    assert_eq!(bytes[34..36], 32_u16.to_le_bytes());
+    assert_eq!(bytes[36..38], 0_u16.to_le_bytes());
+    assert_eq!(&bytes[38..42], b"fact");
+    assert_eq!(bytes[46..50], 2_u32.to_le_bytes());
+    assert_eq!(&bytes[50..54], b"data");
+    assert_eq!(bytes[54..58], 16_u32.to_le_bytes());
+    assert_eq!(bytes[70..], 2_f32.to_le_bytes());
+}
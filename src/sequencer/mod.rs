@@ -0,0 +1,544 @@
+#![doc = r#"
+Contains a pull-based sequencer for playing back MIDI files
+
+See the [`Sequencer`] docs for more information
+"#]
+
+mod position;
+pub use position::*;
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::prelude::*;
+
+#[doc = r#"
+Plays back a [`ParsedMidiFile`] against some external clock.
+
+# Overview
+The sequencer does not keep time itself. Instead, whatever drives it (an audio
+callback, a game loop, a thread) asks for the events that are due within a
+window of its own clock via [`Sequencer::events_between`]. Each call advances
+the playback position by the length of the window, scaled by the tempo scale.
+
+Events are yielded as [`Timed`] values, where the timestamp is the time at which
+the event should occur in the caller's clock, in microseconds.
+
+Transport controls:
+- [`Sequencer::play`] and [`Sequencer::pause`] start and stop the position from advancing.
+- [`Sequencer::seek`] moves the position to some tick, microsecond, or bar and beat.
+- [`Sequencer::set_tempo_scale`] speeds up or slows down playback.
+- [`Sequencer::set_loop`] repeats a region of the file.
+
+Every track is played at once, sharing the tempo map of the file.
+
+# Chasing
+When the position jumps, whether by seeking or looping, the receiver may have the wrong
+programs, controllers and pitch bends for the new position. The sequencer tracks the
+[`ChannelState`] it has played so far, and yields the messages needed to restore the state
+at the new position before any other event. Chased events belong to the first track.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let bytes = include_bytes!("../../test-asset/Clementi.mid");
+let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+
+let mut sequencer = Sequencer::new(&file);
+sequencer.play();
+
+// in an audio callback, for example
+let (start, end) = (0, 20_000);
+for event in sequencer.events_between(start, end) {
+    // the event should be handled `event.timestamp` micros into the caller's clock
+    assert!((start..end).contains(&event.timestamp));
+}
+assert_eq!(sequencer.position_micros(), 20_000);
+```
+"#]
+#[derive(Clone, Debug)]
+pub struct Sequencer<'a> {
+    tempo_map: TempoMap,
+    /// Sorted by time
+    events: Vec<SequencedEvent<'a>>,
+    /// The time of the last event in the file, in song micros
+    length: u64,
+    /// The playback position, in song micros
+    position: f64,
+    playing: bool,
+    tempo_scale: f64,
+    looping: Option<Loop<'a>>,
+    /// The channel state of the receiver, given the events yielded so far
+    sent: ChannelState,
+    /// The channel state to restore before the next window, after a seek
+    target: Option<ChannelState>,
+    /// Events that restore the channel state, yielded at the start of the last window
+    chase: Vec<SequencedEvent<'a>>,
+}
+
+/// A region being repeated
+#[derive(Clone, Debug)]
+struct Loop<'a> {
+    region: Range<u64>,
+    /// Events that restore the channel state at the start of the region from its end
+    chase: Vec<SequencedEvent<'a>>,
+    /// The channel state at the start of the region
+    state: ChannelState,
+}
+
+/// An event of a file, with its position in the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequencedEvent<'a> {
+    track: usize,
+    tick: u32,
+    micros: u64,
+    event: LiveEvent<'a>,
+}
+
+impl<'a> SequencedEvent<'a> {
+    /// The index of the track the event belongs to
+    pub const fn track(&self) -> usize {
+        self.track
+    }
+
+    /// The accumulated ticks of the event
+    pub const fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    /// The time of the event from the start of the file, at normal speed
+    pub const fn micros(&self) -> u64 {
+        self.micros
+    }
+
+    /// The event itself
+    pub const fn event(&self) -> &LiveEvent<'a> {
+        &self.event
+    }
+}
+
+impl<'a> Sequencer<'a> {
+    /// Create a paused sequencer at the beginning of the file
+    pub fn new(file: &ParsedMidiFile<'a>) -> Self {
+        let tempo_map = file.tempo_map();
+        let tracks = file.tracks();
+
+        let mut events = Vec::new();
+        let mut last_tick = 0;
+        for (track_index, track) in tracks.iter().enumerate() {
+            for event in track.events() {
+                let tick = event.accumulated_ticks();
+                events.push(SequencedEvent {
+                    track: track_index,
+                    tick,
+                    micros: tempo_map.ticks_to_micros(tick),
+                    event: event.event().clone(),
+                });
+            }
+            let track_end = track
+                .meta_events()
+                .last()
+                .map(Ticked::accumulated_ticks)
+                .into_iter()
+                .chain(track.events().last().map(Ticked::accumulated_ticks))
+                .max()
+                .unwrap_or(0);
+            last_tick = last_tick.max(track_end);
+        }
+        // stable, so events at the same time keep their track order
+        events.sort_by_key(|e| e.micros);
+
+        Self {
+            length: tempo_map.ticks_to_micros(last_tick),
+            tempo_map,
+            events,
+            position: 0.,
+            playing: false,
+            tempo_scale: 1.,
+            looping: None,
+            sent: ChannelState::new(),
+            target: None,
+            chase: Vec::new(),
+        }
+    }
+
+    /// Every event of the file, sorted by time
+    pub fn events(&self) -> &[SequencedEvent<'a>] {
+        &self.events
+    }
+
+    /// The tempo map used to convert positions
+    pub const fn tempo_map(&self) -> &TempoMap {
+        &self.tempo_map
+    }
+
+    /// Start advancing the position. If playback has finished, it restarts from the beginning.
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.seek(PlaybackPosition::Micros(0));
+        }
+        self.playing = true;
+    }
+
+    /// Stop advancing the position. The position is kept.
+    pub const fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Pause and return to the beginning of the file
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.seek(PlaybackPosition::Micros(0));
+    }
+
+    /// Returns true if the position is advancing
+    pub const fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Returns true if the position has reached the end of the file
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.length as f64
+    }
+
+    /// Move the position. Positions past the end of the file are clamped.
+    ///
+    /// The channel state at the new position is chased by the next call to [`Sequencer::events_between`].
+    pub fn seek(&mut self, position: impl Into<PlaybackPosition>) {
+        let micros = self.to_micros(position.into()).min(self.length);
+        self.position = micros as f64;
+        self.target = Some(self.state_at(micros, false));
+    }
+
+    /// The channel state at a position, replaying the events before it, or up to it if `inclusive`
+    fn state_at(&self, micros: u64, inclusive: bool) -> ChannelState {
+        let end = self
+            .events
+            .partition_point(|e| e.micros < micros || (inclusive && e.micros == micros));
+        let mut state = ChannelState::new();
+        for event in &self.events[..end] {
+            if let LiveEvent::ChannelVoice(message) = &event.event {
+                state.apply(message);
+            }
+        }
+        state
+    }
+
+    /// Wraps chase messages as events at `micros`
+    fn chase_events(
+        &self,
+        messages: Vec<ChannelVoiceMessage>,
+        micros: u64,
+    ) -> Vec<SequencedEvent<'a>> {
+        let tick = self.tempo_map.micros_to_ticks(micros);
+        messages
+            .into_iter()
+            .map(|message| SequencedEvent {
+                track: 0,
+                tick,
+                micros,
+                event: LiveEvent::ChannelVoice(message),
+            })
+            .collect()
+    }
+
+    /// The length of the file in microseconds, at normal speed
+    pub const fn length_micros(&self) -> u64 {
+        self.length
+    }
+
+    /// The current position in microseconds from the start of the file, at normal speed
+    pub fn position_micros(&self) -> u64 {
+        (self.position + 0.5) as u64
+    }
+
+    /// The current position in ticks
+    pub fn position_ticks(&self) -> u32 {
+        self.tempo_map.micros_to_ticks(self.position_micros())
+    }
+
+    /// The current position in bars and beats
+    pub fn position_bar_beat(&self) -> BarBeat {
+        self.tempo_map.ticks_to_bar_beat(self.position_ticks())
+    }
+
+    /// How fast the file is played. `1.` is normal speed, `2.` is double speed.
+    pub const fn tempo_scale(&self) -> f64 {
+        self.tempo_scale
+    }
+
+    /// Set how fast the file is played. `1.` is normal speed, `2.` is double speed.
+    ///
+    /// Scales that are not positive are clamped to the smallest positive value.
+    pub fn set_tempo_scale(&mut self, tempo_scale: f64) {
+        self.tempo_scale = if tempo_scale > 0. {
+            tempo_scale
+        } else {
+            f64::MIN_POSITIVE
+        };
+    }
+
+    /// The region being repeated, in microseconds from the start of the file
+    pub fn loop_region(&self) -> Option<Range<u64>> {
+        self.looping.as_ref().map(|looping| looping.region.clone())
+    }
+
+    /// Repeat the region between `start` and `end`.
+    ///
+    /// Once the position reaches `end`, it jumps back to `start`. If the position is
+    /// already past `end`, the region will not repeat until the position is moved before it.
+    /// Events at `end` are played before jumping back.
+    ///
+    /// The end is clamped to the end of the file. Empty regions clear the loop.
+    pub fn set_loop(
+        &mut self,
+        start: impl Into<PlaybackPosition>,
+        end: impl Into<PlaybackPosition>,
+    ) {
+        let start = self.to_micros(start.into());
+        let end = self.to_micros(end.into()).min(self.length);
+        if start >= end {
+            self.looping = None;
+            return;
+        }
+        let state = self.state_at(start, false);
+        let chase = state.diff(&self.state_at(end, true));
+        self.looping = Some(Loop {
+            region: start..end,
+            chase: self.chase_events(chase, start),
+            state,
+        });
+    }
+
+    /// Stop repeating the loop region
+    pub fn clear_loop(&mut self) {
+        self.looping = None;
+    }
+
+    fn to_micros(&self, position: PlaybackPosition) -> u64 {
+        match position {
+            PlaybackPosition::Ticks(ticks) => self.tempo_map.ticks_to_micros(ticks),
+            PlaybackPosition::Micros(micros) => micros,
+            PlaybackPosition::BarBeat(bar_beat) => self
+                .tempo_map
+                .ticks_to_micros(self.tempo_map.bar_beat_to_ticks(bar_beat)),
+        }
+    }
+
+    /// Returns the events due between `start` and `end` of the caller's clock, in microseconds,
+    /// and advances the position by the length of the window.
+    ///
+    /// Nothing is returned while paused. Reaching the end of the file pauses the sequencer.
+    /// After the position jumps, messages that chase the channel state come first.
+    ///
+    /// Windows should be contiguous: the `end` of one call should be the `start` of the next.
+    pub fn events_between(&mut self, start: u64, end: u64) -> EventsBetween<'_, 'a> {
+        self.chase.clear();
+        if self.playing
+            && let Some(target) = self.target.take()
+        {
+            let messages = target.diff(&self.sent);
+            self.chase = self.chase_events(messages, self.position_micros());
+            self.sent = target;
+        }
+
+        let walk = Walk {
+            position: self.position,
+            time: start as f64,
+            end: end as f64,
+            done: !self.playing,
+            finished: false,
+        };
+        let transport = Transport {
+            scale: self.tempo_scale,
+            loop_region: self.loop_region(),
+            length: self.length,
+        };
+
+        let mut advanced = walk.clone();
+        while let Some(segment) = advanced.next_segment(&transport) {
+            if segment.wraps {
+                if let Some(looping) = &self.looping {
+                    self.sent = looping.state.clone();
+                }
+                continue;
+            }
+            for event in &self.events[segment.range(&self.events)] {
+                if let LiveEvent::ChannelVoice(message) = &event.event {
+                    self.sent.apply(message);
+                }
+            }
+        }
+        self.position = advanced.position;
+        if advanced.finished {
+            self.playing = false;
+        }
+
+        EventsBetween {
+            events: &self.events,
+            loop_chase: self.looping.as_ref().map_or(&[], |looping| &looping.chase),
+            transport,
+            walk,
+            current: self.chase.iter(),
+            placement: Placement::At(start),
+            wrap: None,
+        }
+    }
+}
+
+/// Playback parameters that are fixed for the duration of a window
+#[derive(Clone)]
+struct Transport {
+    scale: f64,
+    loop_region: Option<Range<u64>>,
+    length: u64,
+}
+
+/// A span of the file that plays without a jump
+struct Segment {
+    song_start: f64,
+    song_end: f64,
+    /// True if events at `song_end` belong to this segment
+    inclusive: bool,
+    /// True if the position jumps back to the start of the loop region after this segment
+    wraps: bool,
+    /// The caller's time at `song_start`
+    time_start: f64,
+}
+
+/// Walks through a window of the caller's clock, splitting it into segments
+#[derive(Clone)]
+struct Walk {
+    position: f64,
+    time: f64,
+    end: f64,
+    done: bool,
+    /// The end of the file was reached
+    finished: bool,
+}
+
+impl Walk {
+    fn next_segment(&mut self, transport: &Transport) -> Option<Segment> {
+        if self.done || self.time >= self.end {
+            return None;
+        }
+        let song_start = self.position;
+        let time_start = self.time;
+        let target = song_start + (self.end - self.time) * transport.scale;
+
+        if let Some(region) = &transport.loop_region {
+            let (loop_start, loop_end) = (region.start as f64, region.end as f64);
+            if song_start < loop_end && target >= loop_end {
+                self.position = loop_start;
+                self.time += (loop_end - song_start) / transport.scale;
+                if self.time <= time_start {
+                    // the region is too short to make progress
+                    self.done = true;
+                }
+                return Some(Segment {
+                    song_start,
+                    song_end: loop_end,
+                    inclusive: true,
+                    wraps: true,
+                    time_start,
+                });
+            }
+        }
+
+        let length = transport.length as f64;
+        if target >= length {
+            self.position = length.max(song_start);
+            self.done = true;
+            self.finished = true;
+            return Some(Segment {
+                song_start,
+                song_end: length,
+                inclusive: true,
+                wraps: false,
+                time_start,
+            });
+        }
+
+        self.position = target;
+        self.time = self.end;
+        Some(Segment {
+            song_start,
+            song_end: target,
+            inclusive: false,
+            wraps: false,
+            time_start,
+        })
+    }
+}
+
+impl Segment {
+    /// The range of `events` that play within this segment
+    fn range(&self, events: &[SequencedEvent<'_>]) -> Range<usize> {
+        let start = events.partition_point(|e| (e.micros as f64) < self.song_start);
+        let end = if self.inclusive {
+            events.partition_point(|e| e.micros as f64 <= self.song_end)
+        } else {
+            events.partition_point(|e| (e.micros as f64) < self.song_end)
+        };
+        start..end.max(start)
+    }
+}
+
+/// How the events being yielded map onto the caller's clock
+enum Placement {
+    /// Every event happens at the same time
+    At(u64),
+    /// Events happen relative to the start of a segment
+    Segment { song_start: f64, time_start: f64 },
+}
+
+/// The events due within a window of time. See [`Sequencer::events_between`].
+pub struct EventsBetween<'s, 'a> {
+    events: &'s [SequencedEvent<'a>],
+    loop_chase: &'s [SequencedEvent<'a>],
+    transport: Transport,
+    walk: Walk,
+    current: core::slice::Iter<'s, SequencedEvent<'a>>,
+    placement: Placement,
+    /// The time at which the loop chase is yielded, once the current segment is done
+    wrap: Option<u64>,
+}
+
+impl<'s, 'a> Iterator for EventsBetween<'s, 'a> {
+    type Item = Timed<&'s SequencedEvent<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.current.next() {
+                let timestamp = match self.placement {
+                    Placement::At(time) => time,
+                    Placement::Segment {
+                        song_start,
+                        time_start,
+                    } => {
+                        let offset = (event.micros as f64 - song_start) / self.transport.scale;
+                        (time_start + offset.max(0.)) as u64
+                    }
+                };
+                return Some(Timed::new(timestamp, event));
+            }
+
+            if let Some(time) = self.wrap.take() {
+                self.current = self.loop_chase.iter();
+                self.placement = Placement::At(time);
+                continue;
+            }
+
+            let segment = self.walk.next_segment(&self.transport)?;
+            self.current = self.events[segment.range(self.events)].iter();
+            self.placement = Placement::Segment {
+                song_start: segment.song_start,
+                time_start: segment.time_start,
+            };
+            if segment.wraps {
+                self.wrap = Some(self.walk.time as u64);
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use crate::prelude::BarBeat;
+
+/// A position within a file, used to seek a [`Sequencer`](super::Sequencer)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackPosition {
+    /// Ticks from the start of the file
+    Ticks(u32),
+    /// Microseconds from the start of the file, at normal speed
+    Micros(u64),
+    /// A bar and beat, as described by the file's time signatures
+    BarBeat(BarBeat),
+}
+
+impl From<BarBeat> for PlaybackPosition {
+    fn from(value: BarBeat) -> Self {
+        Self::BarBeat(value)
+    }
+}
@@ -0,0 +1,143 @@
+#![doc = r#"
+Plays `midix` messages on the [`midix_synth`] SoundFont synthesizer
+
+See [`SynthesizerExt`] for more information
+"#]
+
+use core::convert::Infallible;
+
+pub use midix_synth::prelude::{
+    AudioEffect, BusEffects, BusLayout, ChorusParameters, EffectBus, ReverbParameters, SoundFont,
+    SoundFontError, Synthesizer, SynthesizerError, SynthesizerSettings, VoiceMessage,
+    VoiceStealing,
+};
+
+use crate::{MidiTarget, prelude::*};
+
+#[doc = r#"
+Hands typed messages to a [`Synthesizer`], without encoding them into bytes first.
+
+Every [`VoiceEvent`] and [`ChannelMode`] is applied, along with the system exclusive messages
+the synthesizer understands (GM/GS/XG resets, master volume, drum parts and MIDI Tuning Standard)
+and the system reset. Other system messages are ignored.
+
+The synthesizer also implements [`MidiTarget`], which takes any [`MidiMessage`].
+
+# Example
+```rust
+use midix::prelude::*;
+use midix::synth::{SoundFont, Synthesizer, SynthesizerSettings};
+
+fn play(sound_font: SoundFont) {
+    let mut synthesizer =
+        Synthesizer::new(sound_font, &SynthesizerSettings::new(44_100)).unwrap();
+
+    let note = VoiceEvent::note_on(key!(C, 4), Velocity::MAX);
+    synthesizer.handle_voice(&Channel::One.send_event(note));
+
+    let mut left = [0.; 441];
+    let mut right = [0.; 441];
+    synthesizer.render(&mut left, &mut right);
+}
+```
+"#]
+pub trait SynthesizerExt {
+    /// Apply a channel voice message now
+    fn handle_voice(&mut self, message: &ChannelVoiceMessage);
+
+    /// Apply a channel mode message now
+    fn handle_mode(&mut self, message: &ChannelModeMessage);
+
+    /// Apply an event now. Real-time messages other than a reset are ignored.
+    fn handle_live(&mut self, event: &LiveEvent<'_>);
+
+    /// Apply a channel voice message once `delay` more samples have been rendered.
+    ///
+    /// See [`Synthesizer::schedule_midi_message`] for how the timing is kept.
+    fn schedule_voice(&mut self, delay: usize, message: &ChannelVoiceMessage);
+}
+
+impl SynthesizerExt for Synthesizer {
+    fn handle_voice(&mut self, message: &ChannelVoiceMessage) {
+        let (channel, message) = voice_message(message);
+        self.process_voice_message(channel, message);
+    }
+
+    fn handle_mode(&mut self, message: &ChannelModeMessage) {
+        let channel = message.channel().to_byte();
+        match message.mode() {
+            ChannelMode::AllSoundOff => self.note_off_all_channel(channel, true),
+            ChannelMode::ResetAllControllers => self.reset_all_controllers_channel(channel),
+            ChannelMode::LocalControl(_) => {}
+            // changing the mode also turns every note off
+            ChannelMode::AllNotesOff
+            | ChannelMode::OmniModeOff
+            | ChannelMode::OmniModeOn
+            | ChannelMode::MonoModeOn(_)
+            | ChannelMode::PolyModeOn => self.note_off_all_channel(channel, false),
+        }
+    }
+
+    fn handle_live(&mut self, event: &LiveEvent<'_>) {
+        match event {
+            LiveEvent::ChannelVoice(message) => self.handle_voice(message),
+            LiveEvent::SysCommon(SystemCommonMessage::SystemExclusive(sysex)) => {
+                self.process_sysex(sysex.data())
+            }
+            LiveEvent::SysRealTime(SystemRealTimeMessage::Reset) => self.reset(),
+            _ => {}
+        }
+    }
+
+    fn schedule_voice(&mut self, delay: usize, message: &ChannelVoiceMessage) {
+        let (channel, message) = voice_message(message);
+        self.schedule_voice_message(delay, channel, message);
+    }
+}
+
+/// The synthesizer's form of a message, with its channel
+fn voice_message(voice: &ChannelVoiceMessage) -> (u8, VoiceMessage) {
+    let message = match voice.event() {
+        VoiceEvent::NoteOn { key, velocity } => VoiceMessage::NoteOn {
+            key: key.byte(),
+            velocity: velocity.byte(),
+        },
+        VoiceEvent::NoteOff { key, .. } => VoiceMessage::NoteOff { key: key.byte() },
+        VoiceEvent::Aftertouch { key, velocity } => VoiceMessage::KeyPressure {
+            key: key.byte(),
+            pressure: velocity.byte(),
+        },
+        VoiceEvent::ControlChange(controller) => {
+            let [controller, value] = controller.to_bytes();
+            VoiceMessage::ControlChange { controller, value }
+        }
+        VoiceEvent::ProgramChange { program } => VoiceMessage::ProgramChange {
+            program: program.byte(),
+        },
+        VoiceEvent::ChannelPressureAfterTouch { velocity } => VoiceMessage::ChannelPressure {
+            pressure: velocity.byte(),
+        },
+        VoiceEvent::PitchBend(bend) => VoiceMessage::PitchBend {
+            value: bend.value(),
+        },
+    };
+    (voice.channel().to_byte(), message)
+}
+
+impl MidiTarget for Synthesizer {
+    type Error = Infallible;
+
+    fn handle_event(&mut self, event: MidiMessage) -> Result<(), Self::Error> {
+        match event {
+            MidiMessage::ChannelVoice(message) => self.handle_voice(&message),
+            MidiMessage::ChannelMode(message) => self.handle_mode(&message),
+            MidiMessage::SysExclusive(sysex)
+            | MidiMessage::SysCommon(SystemCommonMessage::SystemExclusive(sysex)) => {
+                self.process_sysex(sysex.data())
+            }
+            MidiMessage::SysRealTime(SystemRealTimeMessage::Reset) => self.reset(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -14,6 +14,6 @@ pub mod prelude {
 
     pub(crate) use crate::{
         reader::*,
-        soundfont::{generator::*, zone::*},
+        soundfont::{generator::*, modulator::*, zone::*},
     };
 }
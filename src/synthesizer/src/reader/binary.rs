@@ -1,7 +1,10 @@
 #![allow(dead_code)]
 
 use crate::soundfont::FourCC;
-use bevy_platform::prelude::*;
+use std::{
+    io::{self, ErrorKind, Read},
+    slice,
+};
 
 #[allow(unused)]
 pub struct BinaryReader {}
@@ -118,6 +121,13 @@ impl BinaryReader {
         reader: &mut R,
         size: usize,
     ) -> Result<Vec<i16>, io::Error> {
+        if size % 2 == 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "16-bit sample data must have an even size",
+            ));
+        }
+
         let length = size / 2;
         let mut samples: Vec<i16> = vec![0; length];
 
@@ -1,5 +1,8 @@
-use bevy_platform::prelude::*;
-use bevy_platform::prelude::*;
+use std::{
+    error,
+    fmt::{self, Debug, Display, Formatter, Result, Write},
+    io,
+};
 /// Represents an error when loading a SoundFont.
 #[derive(Debug)]
 pub enum SoundFontError {
@@ -36,6 +39,7 @@ pub enum SoundFontError {
     InvalidZoneList,
     ZoneNotFound,
     InvalidGeneratorList,
+    InvalidModulatorList,
     RegionCheckFailed {
         inst_name: String,
         region_idx: usize,
@@ -46,6 +50,10 @@ pub enum SoundFontError {
         inst_name: String,
         region_idx: usize,
     },
+    SampleDecodeFailed {
+        sample_name: String,
+        msg: String,
+    },
 }
 
 impl error::Error for SoundFontError {
@@ -75,7 +83,9 @@ impl fmt::Display for SoundFontError {
                 write!(f, "the INFO list contains an unknown ID '{id}'")
             }
             SoundFontError::SampleDataNotFound => write!(f, "no valid sample data was found"),
-            SoundFontError::UnsupportedSampleFormat => write!(f, "SoundFont3 is not yet supported"),
+            SoundFontError::UnsupportedSampleFormat => {
+                write!(f, "SoundFont3 requires the `sf3` feature")
+            }
             SoundFontError::SubChunkNotFound(id) => {
                 write!(f, "the '{id}' sub-chunk was not found")
             }
@@ -112,6 +122,7 @@ impl fmt::Display for SoundFontError {
             SoundFontError::InvalidZoneList => write!(f, "the zone list is invalid"),
             SoundFontError::ZoneNotFound => write!(f, "no valid zone was found"),
             SoundFontError::InvalidGeneratorList => write!(f, "the generator list is invalid"),
+            SoundFontError::InvalidModulatorList => write!(f, "the modulator list is invalid"),
             SoundFontError::RegionCheckFailed {
                 inst_name,
                 region_idx,
@@ -128,6 +139,9 @@ impl fmt::Display for SoundFontError {
                     "Error at inst {inst_name}, zone {region_idx}: Sample out of bounds"
                 )
             }
+            SoundFontError::SampleDecodeFailed { sample_name, msg } => {
+                write!(f, "failed to decode the sample '{sample_name}': {msg}")
+            }
         }
     }
 }
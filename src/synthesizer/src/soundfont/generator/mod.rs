@@ -4,7 +4,7 @@ mod r#type;
 pub(crate) use r#type::*;
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::Read;
 
 #[derive(Clone, Copy)]
 pub(crate) struct Generator {
@@ -27,7 +27,7 @@ impl Generator {
         reader: &mut R,
         size: usize,
     ) -> Result<Vec<Generator>, SoundFontError> {
-        if size % 4 != 0 {
+        if !size.is_multiple_of(4) {
             return Err(SoundFontError::InvalidGeneratorList);
         }
 
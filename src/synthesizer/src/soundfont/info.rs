@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::Read;
 
 /// The information of a SoundFont.
 #[derive(Clone, Debug)]
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::Read;
 
 pub(crate) struct InstrumentInfo {
     pub(crate) name: String,
@@ -25,7 +25,7 @@ impl InstrumentInfo {
         reader: &mut R,
         size: usize,
     ) -> Result<Vec<InstrumentInfo>, SoundFontError> {
-        if size % 22 != 0 {
+        if !size.is_multiple_of(22) {
             return Err(SoundFontError::InvalidInstrumentList);
         }
 
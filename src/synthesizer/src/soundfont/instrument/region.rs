@@ -18,6 +18,7 @@ fn set_parameter(gs: &mut [i16; GeneratorType::COUNT], generator: &Generator) {
 #[derive(Clone, Debug)]
 pub struct InstrumentRegion {
     pub(crate) gs: [i16; GeneratorType::COUNT],
+    pub(crate) modulators: Vec<Modulator>,
     pub(crate) sample_start: i32,
     pub(crate) sample_end: i32,
     pub(crate) sample_start_loop: i32,
@@ -63,6 +64,10 @@ impl InstrumentRegion {
             set_parameter(&mut gs, generator);
         }
 
+        // Local modulators replace identical global ones.
+        let mut modulators = global.modulators.clone();
+        Modulator::merge(&mut modulators, &local.modulators);
+
         let sample_id = gs[GeneratorType::SAMPLE_ID as usize] as usize;
         if sample_id >= samples.len() {
             return Err(SoundFontError::InvalidSampleId {
@@ -74,6 +79,7 @@ impl InstrumentRegion {
 
         Ok(Self {
             gs,
+            modulators,
             sample_start: sample.start,
             sample_end: sample.end,
             sample_start_loop: sample.start_loop,
@@ -1,10 +1,11 @@
 #![allow(dead_code)]
 
-use bevy_platform::prelude::*;
+use std::{io::Read, sync::Arc};
 use tracing::error;
 
 pub mod generator;
 pub mod instrument;
+pub mod modulator;
 pub mod preset;
 pub mod zone;
 
@@ -20,6 +21,8 @@ mod error;
 pub use error::*;
 mod sample_header;
 pub use sample_header::*;
+#[cfg(feature = "sf3")]
+mod vorbis;
 
 use crate::prelude::*;
 
@@ -28,7 +31,7 @@ use crate::prelude::*;
 pub struct SoundFont {
     pub(crate) info: SoundFontInfo,
     pub(crate) bits_per_sample: i32,
-    pub(crate) wave_data: Arc<Vec<i16>>,
+    pub(crate) wave_data: Arc<WaveData>,
     pub(crate) sample_headers: Vec<SampleHeader>,
     pub(crate) presets: Vec<Preset>,
     pub(crate) instruments: Vec<Instrument>,
@@ -57,12 +60,12 @@ impl SoundFont {
         }
 
         let info = SoundFontInfo::new(reader)?;
-        let sample_data = SoundFontSampleData::new(reader)?;
-        let parameters = SoundFontParameters::new(reader)?;
+        let mut sample_data = SoundFontSampleData::new(reader, &info.version)?;
+        let parameters = SoundFontParameters::new(reader, &mut sample_data)?;
 
         let sound_font = Self {
             info,
-            bits_per_sample: 16,
+            bits_per_sample: sample_data.bits_per_sample,
             wave_data: Arc::new(sample_data.wave_data),
             sample_headers: parameters.sample_headers,
             presets: parameters.presets,
@@ -129,8 +132,8 @@ impl SoundFont {
     }
 
     /// Gets the sample data.
-    pub fn get_wave_data(&self) -> &[i16] {
-        &self.wave_data[..]
+    pub fn get_wave_data(&self) -> &WaveData {
+        &self.wave_data
     }
 
     /// Gets the samples of the SoundFont.
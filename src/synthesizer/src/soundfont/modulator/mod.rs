@@ -0,0 +1,203 @@
+#![allow(dead_code)]
+
+mod source;
+pub(crate) use source::*;
+
+use crate::prelude::*;
+use std::io::Read;
+
+/// A modulator routes a controller to a generator (`SFModList` in the SoundFont spec).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Modulator {
+    pub(crate) source: ModulatorSource,
+    pub(crate) destination: u16,
+    pub(crate) amount: i16,
+    pub(crate) amount_source: ModulatorSource,
+    pub(crate) transform: u16,
+}
+
+impl Modulator {
+    /// The default pitch wheel modulator targets the initial pitch, which has no generator of its own.
+    /// Like other SoundFont players, the unused generator slot stands in for it.
+    pub(crate) const INITIAL_PITCH: u16 = GeneratorType::UNUSED_5;
+
+    const ABSOLUTE_VALUE_TRANSFORM: u16 = 2;
+
+    const fn default_modulator(
+        source: u16,
+        destination: u16,
+        amount: i16,
+        amount_source: u16,
+    ) -> Self {
+        Self {
+            source: ModulatorSource(source),
+            destination,
+            amount,
+            amount_source: ModulatorSource(amount_source),
+            transform: 0,
+        }
+    }
+
+    /// The default modulators from section 8.4 of the SoundFont 2.04 spec.
+    ///
+    /// The flag is `true` for the routings that the channel already applies to every voice
+    /// (velocity, volume, expression, pan, effect sends, modulation wheel and pitch bend).
+    /// Those only need evaluating when a SoundFont overrides them.
+    pub(crate) const DEFAULTS: [(Modulator, bool); 10] = [
+        // Note-on velocity to initial attenuation
+        (
+            Self::default_modulator(0x0502, GeneratorType::INITIAL_ATTENUATION, 960, 0),
+            true,
+        ),
+        // Note-on velocity to filter cutoff
+        (
+            Self::default_modulator(
+                0x0102,
+                GeneratorType::INITIAL_FILTER_CUTOFF_FREQUENCY,
+                -2400,
+                0,
+            ),
+            false,
+        ),
+        // Channel pressure to vibrato LFO pitch depth
+        (
+            Self::default_modulator(0x000D, GeneratorType::VIBRATO_LFO_TO_PITCH, 50, 0),
+            false,
+        ),
+        // CC1 (modulation wheel) to vibrato LFO pitch depth
+        (
+            Self::default_modulator(0x0081, GeneratorType::VIBRATO_LFO_TO_PITCH, 50, 0),
+            true,
+        ),
+        // CC7 (volume) to initial attenuation
+        (
+            Self::default_modulator(0x0587, GeneratorType::INITIAL_ATTENUATION, 960, 0),
+            true,
+        ),
+        // CC10 (pan) to pan
+        (
+            Self::default_modulator(0x028A, GeneratorType::PAN, 1000, 0),
+            true,
+        ),
+        // CC11 (expression) to initial attenuation
+        (
+            Self::default_modulator(0x058B, GeneratorType::INITIAL_ATTENUATION, 960, 0),
+            true,
+        ),
+        // CC91 to reverb send
+        (
+            Self::default_modulator(0x00DB, GeneratorType::REVERB_EFFECTS_SEND, 200, 0),
+            true,
+        ),
+        // CC93 to chorus send
+        (
+            Self::default_modulator(0x00DD, GeneratorType::CHORUS_EFFECTS_SEND, 200, 0),
+            true,
+        ),
+        // Pitch wheel to initial pitch, scaled by the pitch wheel sensitivity
+        (
+            Self::default_modulator(0x020E, Self::INITIAL_PITCH, 12700, 0x0010),
+            true,
+        ),
+    ];
+
+    /// Pressure routings beyond the spec's defaults, so aftertouch swells and brightens notes,
+    /// and key pressure adds vibrato like channel pressure does.
+    /// They have no effect without pressure, and SoundFonts can override them like the defaults.
+    pub(crate) const PRESSURE_DEFAULTS: [Modulator; 5] = [
+        // Key pressure to vibrato LFO pitch depth
+        Self::default_modulator(0x000A, GeneratorType::VIBRATO_LFO_TO_PITCH, 50, 0),
+        // Channel and key pressure to initial attenuation, up to 6 dB louder
+        Self::default_modulator(0x000D, GeneratorType::INITIAL_ATTENUATION, -60, 0),
+        Self::default_modulator(0x000A, GeneratorType::INITIAL_ATTENUATION, -60, 0),
+        // Channel and key pressure to filter cutoff, up to an octave brighter
+        Self::default_modulator(
+            0x000D,
+            GeneratorType::INITIAL_FILTER_CUTOFF_FREQUENCY,
+            1200,
+            0,
+        ),
+        Self::default_modulator(
+            0x000A,
+            GeneratorType::INITIAL_FILTER_CUTOFF_FREQUENCY,
+            1200,
+            0,
+        ),
+    ];
+
+    fn new<R: Read + ?Sized>(reader: &mut R) -> Result<Self, SoundFontError> {
+        let source = ModulatorSource(BinaryReader::read_u16(reader)?);
+        let destination = BinaryReader::read_u16(reader)?;
+        let amount = BinaryReader::read_i16(reader)?;
+        let amount_source = ModulatorSource(BinaryReader::read_u16(reader)?);
+        let transform = BinaryReader::read_u16(reader)?;
+
+        Ok(Self {
+            source,
+            destination,
+            amount,
+            amount_source,
+            transform,
+        })
+    }
+
+    pub(crate) fn read_from_chunk<R: Read + ?Sized>(
+        reader: &mut R,
+        size: usize,
+    ) -> Result<Vec<Modulator>, SoundFontError> {
+        if !size.is_multiple_of(10) || size == 0 {
+            return Err(SoundFontError::InvalidModulatorList);
+        }
+
+        let count = size / 10 - 1;
+
+        let mut modulators: Vec<Modulator> = Vec::new();
+        for _i in 0..count {
+            modulators.push(Modulator::new(reader)?);
+        }
+
+        // The last one is the terminator.
+        Modulator::new(reader)?;
+
+        Ok(modulators)
+    }
+
+    /// Two modulators are identical if they have the same sources, destination and transform.
+    /// An identical modulator in a more specific zone replaces the other one.
+    pub(crate) fn is_identical(&self, other: &Modulator) -> bool {
+        self.source == other.source
+            && self.destination == other.destination
+            && self.amount_source == other.amount_source
+            && self.transform == other.transform
+    }
+
+    /// Returns `false` for modulators this synthesizer ignores: linked modulators,
+    /// unknown destinations and controllers the spec doesn't allow as sources.
+    pub(crate) fn is_supported(&self) -> bool {
+        self.source.is_valid()
+            && self.amount_source.is_valid()
+            && (self.destination as usize) < GeneratorType::COUNT
+    }
+
+    /// Computes the modulator's contribution to its destination generator,
+    /// from the values of its two sources normalized to `[0, 1]`.
+    pub(crate) fn evaluate(&self, source: f32, amount_source: f32) -> f32 {
+        let value =
+            self.amount as f32 * self.source.map(source) * self.amount_source.map(amount_source);
+        if self.transform == Self::ABSOLUTE_VALUE_TRANSFORM {
+            value.abs()
+        } else {
+            value
+        }
+    }
+
+    /// Adds the modulators of a zone to a list, replacing identical ones.
+    pub(crate) fn merge(modulators: &mut Vec<Modulator>, zone: &[Modulator]) {
+        for modulator in zone {
+            match modulators.iter_mut().find(|m| m.is_identical(modulator)) {
+                Some(existing) => *existing = *modulator,
+                None => modulators.push(*modulator),
+            }
+        }
+    }
+}
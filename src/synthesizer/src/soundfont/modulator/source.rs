@@ -0,0 +1,146 @@
+#![allow(dead_code)]
+
+/// The shape of a modulator source, as stored in bits 10-15 of the source operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ModulatorCurve {
+    Linear,
+    Concave,
+    Convex,
+    Switch,
+}
+
+/// A modulator source operator (`SFModulator` in the SoundFont spec).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ModulatorSource(pub(crate) u16);
+
+impl ModulatorSource {
+    /// No controller. The source always evaluates to 1.
+    pub(crate) const NONE: u16 = 0;
+    pub(crate) const NOTE_ON_VELOCITY: u16 = 2;
+    pub(crate) const NOTE_ON_KEY_NUMBER: u16 = 3;
+    pub(crate) const POLY_PRESSURE: u16 = 10;
+    pub(crate) const CHANNEL_PRESSURE: u16 = 13;
+    pub(crate) const PITCH_WHEEL: u16 = 14;
+    pub(crate) const PITCH_WHEEL_SENSITIVITY: u16 = 16;
+    pub(crate) const LINK: u16 = 127;
+
+    /// The controller index, either a general controller or a MIDI CC number.
+    pub(crate) fn index(&self) -> u16 {
+        self.0 & 0x7F
+    }
+
+    /// Returns `true` if the index is a MIDI CC number.
+    pub(crate) fn is_midi_controller(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// Returns `true` if the source goes from max to min.
+    pub(crate) fn is_negative(&self) -> bool {
+        self.0 & 0x100 != 0
+    }
+
+    /// Returns `true` if the source goes from -1 to 1 instead of 0 to 1.
+    pub(crate) fn is_bipolar(&self) -> bool {
+        self.0 & 0x200 != 0
+    }
+
+    pub(crate) fn curve(&self) -> Option<ModulatorCurve> {
+        match self.0 >> 10 {
+            0 => Some(ModulatorCurve::Linear),
+            1 => Some(ModulatorCurve::Concave),
+            2 => Some(ModulatorCurve::Convex),
+            3 => Some(ModulatorCurve::Switch),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the source is the output of another modulator.
+    pub(crate) fn is_link(&self) -> bool {
+        !self.is_midi_controller() && self.index() == Self::LINK
+    }
+
+    /// Returns `false` for links and for the controllers the spec doesn't allow as sources:
+    /// bank select, data entry, the LSB controllers, (N)RPN selection and channel mode messages.
+    pub(crate) fn is_valid(&self) -> bool {
+        if self.is_midi_controller() {
+            !matches!(self.index(), 0 | 6 | 32..=63 | 98..=101 | 120..=127)
+        } else {
+            !self.is_link() && self.curve().is_some()
+        }
+    }
+
+    /// Maps a normalized controller value in `[0, 1]` through the direction, polarity and curve.
+    pub(crate) fn map(&self, value: f32) -> f32 {
+        if !self.is_midi_controller() && self.index() == Self::NONE {
+            return 1_f32;
+        }
+
+        let Some(curve) = self.curve() else {
+            return 0_f32;
+        };
+
+        let value = if self.is_negative() {
+            1_f32 - value
+        } else {
+            value
+        };
+
+        if self.is_bipolar() {
+            let value = 2_f32 * value - 1_f32;
+            let shaped = match curve {
+                ModulatorCurve::Switch => 1_f32,
+                _ => apply_curve(curve, value.abs()),
+            };
+            shaped.copysign(value)
+        } else {
+            apply_curve(curve, value)
+        }
+    }
+}
+
+fn concave(value: f32) -> f32 {
+    if value >= 1_f32 {
+        1_f32
+    } else {
+        // -20/96 * log10((1 - x)^2), from the SoundFont spec.
+        ((-40_f32 / 96_f32) * (1_f32 - value).log10()).clamp(0_f32, 1_f32)
+    }
+}
+
+fn apply_curve(curve: ModulatorCurve, value: f32) -> f32 {
+    match curve {
+        ModulatorCurve::Linear => value,
+        ModulatorCurve::Concave => concave(value),
+        ModulatorCurve::Convex => 1_f32 - concave(1_f32 - value),
+        ModulatorCurve::Switch => {
+            if value >= 0.5_f32 {
+                1_f32
+            } else {
+                0_f32
+            }
+        }
+    }
+}
+
+#[test]
+fn source_curves() {
+    // CC7, concave, negative, unipolar
+    let volume = ModulatorSource(0x0587);
+    assert_eq!(volume.index(), 7);
+    assert!(volume.is_midi_controller() && volume.is_negative() && !volume.is_bipolar());
+    assert_eq!(volume.map(1_f32), 0_f32);
+    assert_eq!(volume.map(0_f32), 1_f32);
+    assert!(volume.map(0.5_f32) < 0.5_f32);
+
+    // pitch wheel, linear, bipolar
+    let pitch_wheel = ModulatorSource(0x020E);
+    assert_eq!(pitch_wheel.map(0.5_f32), 0_f32);
+    assert_eq!(pitch_wheel.map(0_f32), -1_f32);
+    assert_eq!(pitch_wheel.map(1_f32), 1_f32);
+
+    assert_eq!(ModulatorSource(0).map(0_f32), 1_f32);
+
+    let switch = ModulatorSource(0x0C81);
+    assert_eq!(switch.map(0.4_f32), 0_f32);
+    assert_eq!(switch.map(0.6_f32), 1_f32);
+}
@@ -1,8 +1,8 @@
 #![allow(dead_code)]
 
-use bevy_platform::prelude::*;
 use instrument::info::InstrumentInfo;
 use preset::info::PresetInfo;
+use std::io::Read;
 use zone::ZoneInfo;
 
 use crate::prelude::*;
@@ -14,7 +14,12 @@ pub(crate) struct SoundFontParameters {
 }
 
 impl SoundFontParameters {
-    pub(crate) fn new<R: Read + ?Sized>(reader: &mut R) -> Result<Self, SoundFontError> {
+    /// Reads the `pdta` list.
+    /// Compressed sample data is decoded here, as the sample headers are needed to find the samples.
+    pub(crate) fn new<R: Read + ?Sized>(
+        reader: &mut R,
+        sample_data: &mut SoundFontSampleData,
+    ) -> Result<Self, SoundFontError> {
         let chunk_id = BinaryReader::read_four_cc(reader)?;
         if chunk_id != b"LIST" {
             return Err(SoundFontError::ListChunkNotFound);
@@ -33,9 +38,11 @@ impl SoundFontParameters {
 
         let mut preset_infos: Option<Vec<PresetInfo>> = None;
         let mut preset_bag: Option<Vec<ZoneInfo>> = None;
+        let mut preset_modulators: Option<Vec<Modulator>> = None;
         let mut preset_generators: Option<Vec<Generator>> = None;
         let mut instrument_infos: Option<Vec<InstrumentInfo>> = None;
         let mut instrument_bag: Option<Vec<ZoneInfo>> = None;
+        let mut instrument_modulators: Option<Vec<Modulator>> = None;
         let mut instrument_generators: Option<Vec<Generator>> = None;
         let mut sample_headers: Option<Vec<SampleHeader>> = None;
 
@@ -46,11 +53,11 @@ impl SoundFontParameters {
             match id.as_bytes() {
                 b"phdr" => preset_infos = Some(PresetInfo::read_from_chunk(reader, size)?),
                 b"pbag" => preset_bag = Some(ZoneInfo::read_from_chunk(reader, size)?),
-                b"pmod" => BinaryReader::discard_data(reader, size)?,
+                b"pmod" => preset_modulators = Some(Modulator::read_from_chunk(reader, size)?),
                 b"pgen" => preset_generators = Some(Generator::read_from_chunk(reader, size)?),
                 b"inst" => instrument_infos = Some(InstrumentInfo::read_from_chunk(reader, size)?),
                 b"ibag" => instrument_bag = Some(ZoneInfo::read_from_chunk(reader, size)?),
-                b"imod" => BinaryReader::discard_data(reader, size)?,
+                b"imod" => instrument_modulators = Some(Modulator::read_from_chunk(reader, size)?),
                 b"igen" => instrument_generators = Some(Generator::read_from_chunk(reader, size)?),
                 b"shdr" => sample_headers = Some(SampleHeader::read_from_chunk(reader, size)?),
                 _ => return Err(SoundFontError::ListContainsUnknownId(id)),
@@ -81,15 +88,25 @@ impl SoundFontParameters {
             SoundFontError::SubChunkNotFound(FourCC::from_bytes(*b"IGEN")),
         )?;
 
-        let sample_headers = sample_headers.ok_or(SoundFontError::SubChunkNotFound(
+        let mut sample_headers = sample_headers.ok_or(SoundFontError::SubChunkNotFound(
             FourCC::from_bytes(*b"SHDR"),
         ))?;
 
-        let instrument_zones = Zone::create(&instrument_bag, &instrument_generators)?;
+        sample_data.decompress(&mut sample_headers)?;
+
+        // The modulator lists are required by the spec, but some files omit them.
+        let preset_modulators = preset_modulators.unwrap_or_default();
+        let instrument_modulators = instrument_modulators.unwrap_or_default();
+
+        let instrument_zones = Zone::create(
+            &instrument_bag,
+            &instrument_generators,
+            &instrument_modulators,
+        )?;
         let instruments =
             Instrument::create(&instrument_infos, &instrument_zones, &sample_headers)?;
 
-        let preset_zones = Zone::create(&preset_bag, &preset_generators)?;
+        let preset_zones = Zone::create(&preset_bag, &preset_generators, &preset_modulators)?;
         let presets = Preset::create(&preset_infos, &preset_zones, &instruments)?;
 
         Ok(Self {
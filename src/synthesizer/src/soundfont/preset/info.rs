@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::Read;
 
 pub(crate) struct PresetInfo {
     pub(crate) name: String,
@@ -40,7 +40,7 @@ impl PresetInfo {
         reader: &mut R,
         size: usize,
     ) -> Result<Vec<PresetInfo>, SoundFontError> {
-        if size % 38 != 0 {
+        if !size.is_multiple_of(38) {
             return Err(SoundFontError::InvalidPresetList);
         }
 
@@ -18,6 +18,7 @@ fn set_parameter(gs: &mut [i16; GeneratorType::COUNT], generator: &Generator) {
 #[derive(Clone, Debug)]
 pub struct PresetRegion {
     pub(crate) gs: [i16; GeneratorType::COUNT],
+    pub(crate) modulators: Vec<Modulator>,
     pub(crate) instrument: usize,
 }
 
@@ -40,6 +41,10 @@ impl PresetRegion {
             set_parameter(&mut gs, generator);
         }
 
+        // Local modulators replace identical global ones.
+        let mut modulators = global.modulators.clone();
+        Modulator::merge(&mut modulators, &local.modulators);
+
         let instrument_id = gs[GeneratorType::INSTRUMENT as usize] as usize;
         if instrument_id >= samples.len() {
             return Err(SoundFontError::InvalidInstrumentId {
@@ -50,6 +55,7 @@ impl PresetRegion {
 
         Ok(Self {
             gs,
+            modulators,
             instrument: instrument_id,
         })
     }
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::Read;
 
 /// Represents a sample in the SoundFont.
 #[derive(Clone, Debug)]
@@ -49,7 +49,7 @@ impl SampleHeader {
         reader: &mut R,
         size: usize,
     ) -> Result<Vec<SampleHeader>, SoundFontError> {
-        if size % 46 != 0 {
+        if !size.is_multiple_of(46) {
             return Err(SoundFontError::InvalidSampleHeaderList);
         }
 
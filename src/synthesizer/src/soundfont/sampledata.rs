@@ -1,15 +1,54 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::{io::Read, slice};
+
+/// The sample data of a SoundFont.
+#[derive(Clone, Debug)]
+pub enum WaveData {
+    /// 16-bit samples, from the `smpl` chunk.
+    Pcm16(Vec<i16>),
+    /// 24-bit samples, merged from the `smpl` chunk and the low bytes in the `sm24` chunk.
+    Pcm24(Vec<i32>),
+}
+
+impl WaveData {
+    /// Gets the number of samples.
+    pub fn len(&self) -> usize {
+        match self {
+            WaveData::Pcm16(samples) => samples.len(),
+            WaveData::Pcm24(samples) => samples.len(),
+        }
+    }
+
+    /// Returns `true` if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the bits per sample.
+    pub fn bits_per_sample(&self) -> i32 {
+        match self {
+            WaveData::Pcm16(_) => 16,
+            WaveData::Pcm24(_) => 24,
+        }
+    }
+}
 
 pub struct SoundFontSampleData {
     pub bits_per_sample: i32,
-    pub wave_data: Vec<i16>,
+    pub wave_data: WaveData,
+    /// The Ogg Vorbis streams of a SoundFont 3 file, decoded once the sample headers are read.
+    pub(crate) compressed: Option<Vec<u8>>,
 }
 
 impl SoundFontSampleData {
-    pub(crate) fn new<R: Read + ?Sized>(reader: &mut R) -> Result<Self, SoundFontError> {
+    /// Reads the `sdta` list.
+    /// The `sm24` chunk is only used by SoundFont 2.04 and later, as the spec requires.
+    pub(crate) fn new<R: Read + ?Sized>(
+        reader: &mut R,
+        version: &SoundFontVersion,
+    ) -> Result<Self, SoundFontError> {
         let chunk_id = BinaryReader::read_four_cc(reader)?;
         if chunk_id != b"LIST" {
             return Err(SoundFontError::ListChunkNotFound);
@@ -27,14 +66,28 @@ impl SoundFontSampleData {
         }
 
         let mut wave_data: Option<Vec<i16>> = None;
+        // Only compressed sample data can have an odd size.
+        let mut odd_byte: Option<u8> = None;
+        let mut low_bytes: Option<Vec<u8>> = None;
 
         while reader.bytes_read() < end {
             let id = BinaryReader::read_four_cc(reader)?;
             let size = BinaryReader::read_u32(reader)? as usize;
 
             match id.as_bytes() {
-                b"smpl" => wave_data = Some(BinaryReader::read_wave_data(reader, size)?),
-                b"sm24" => BinaryReader::discard_data(reader, size)?,
+                b"smpl" => {
+                    wave_data = Some(BinaryReader::read_wave_data(reader, size & !1)?);
+                    if size % 2 == 1 {
+                        odd_byte = Some(BinaryReader::read_u8(reader)?);
+                        // The pad byte of the chunk
+                        BinaryReader::read_u8(reader)?;
+                    }
+                }
+                b"sm24" => {
+                    let mut data: Vec<u8> = vec![0; size];
+                    reader.read_exact(&mut data)?;
+                    low_bytes = Some(data);
+                }
                 _ => return Err(SoundFontError::ListContainsUnknownId(id)),
             }
         }
@@ -45,14 +98,67 @@ impl SoundFontSampleData {
         };
 
         let ptr = wave_data.as_ptr() as *const u8;
-        let four_cc = unsafe { slice::from_raw_parts(ptr, 4) };
-        if four_cc == b"OggS" {
-            return Err(SoundFontError::UnsupportedSampleFormat);
+        let bytes = unsafe { slice::from_raw_parts(ptr, 2 * wave_data.len()) };
+        if bytes.starts_with(b"OggS") {
+            if cfg!(not(feature = "sf3")) {
+                return Err(SoundFontError::UnsupportedSampleFormat);
+            }
+
+            let mut compressed = bytes.to_vec();
+            compressed.extend(odd_byte);
+
+            return Ok(Self {
+                bits_per_sample: 16,
+                wave_data: WaveData::Pcm16(Vec::new()),
+                compressed: Some(compressed),
+            });
         }
 
+        let supports_sm24 = (version.major, version.minor) >= (2, 4);
+
+        let wave_data = match low_bytes {
+            // The chunk is padded to an even size, and is ignored if it doesn't match the samples.
+            Some(low_bytes)
+                if supports_sm24
+                    && low_bytes.len() >= wave_data.len()
+                    && low_bytes.len() <= wave_data.len() + 1 =>
+            {
+                WaveData::Pcm24(
+                    wave_data
+                        .iter()
+                        .zip(low_bytes)
+                        .map(|(high, low)| ((*high as i32) << 8) | low as i32)
+                        .collect(),
+                )
+            }
+            _ => WaveData::Pcm16(wave_data),
+        };
+
         Ok(Self {
-            bits_per_sample: 16,
+            bits_per_sample: wave_data.bits_per_sample(),
             wave_data,
+            compressed: None,
         })
     }
+
+    /// Decodes compressed samples, pointing the sample headers at the decoded samples.
+    #[cfg(feature = "sf3")]
+    pub(crate) fn decompress(
+        &mut self,
+        headers: &mut [SampleHeader],
+    ) -> Result<(), SoundFontError> {
+        if let Some(compressed) = self.compressed.take() {
+            self.wave_data = WaveData::Pcm16(super::vorbis::decode(&compressed, headers)?);
+        }
+        Ok(())
+    }
+
+    /// Without the `sf3` feature, compressed sample data is rejected when it is read.
+    #[cfg(not(feature = "sf3"))]
+    pub(crate) fn decompress(
+        &mut self,
+        _headers: &mut [SampleHeader],
+    ) -> Result<(), SoundFontError> {
+        Ok(())
+    }
 }
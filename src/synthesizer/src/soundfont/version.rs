@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::{self, Read};
 
 /// Reperesents the version of a SoundFont.
 #[derive(Clone, Debug)]
@@ -0,0 +1,62 @@
+use std::io::Cursor;
+
+use lewton::inside_ogg::OggStreamReader;
+
+use crate::prelude::*;
+
+/// Set in the sample type of samples compressed with Ogg Vorbis.
+const VORBIS_SAMPLE_TYPE: u16 = 0x10;
+
+/// Decodes the Ogg Vorbis streams of a SoundFont 3 file into 16-bit samples.
+///
+/// In SoundFont 3, the start and end of a compressed sample are byte offsets of its stream,
+/// and its loop points are relative to the start of the decoded sample.
+/// The headers are rewritten to point into the decoded samples.
+pub(crate) fn decode(
+    data: &[u8],
+    headers: &mut [SampleHeader],
+) -> Result<Vec<i16>, SoundFontError> {
+    let mut wave_data: Vec<i16> = Vec::new();
+
+    for header in headers.iter_mut() {
+        if header.sample_type & VORBIS_SAMPLE_TYPE == 0 {
+            continue;
+        }
+
+        let failed = |msg: String| SoundFontError::SampleDecodeFailed {
+            sample_name: header.name.clone(),
+            msg,
+        };
+
+        let stream = usize::try_from(header.start)
+            .ok()
+            .zip(usize::try_from(header.end).ok())
+            .and_then(|(start, end)| data.get(start..end))
+            .ok_or_else(|| failed("the stream is out of bounds".into()))?;
+
+        let mut reader =
+            OggStreamReader::new(Cursor::new(stream)).map_err(|e| failed(e.to_string()))?;
+        let channels = reader.ident_hdr.audio_channels as usize;
+
+        let start = wave_data.len() as i32;
+        while let Some(packet) = reader
+            .read_dec_packet_itl()
+            .map_err(|e| failed(e.to_string()))?
+        {
+            // Only the first channel is used, as SoundFont samples are mono.
+            wave_data.extend(packet.into_iter().step_by(channels.max(1)));
+        }
+        let end = wave_data.len() as i32;
+
+        // The spec asks for 46 zero samples after each sample.
+        wave_data.extend([0; 46]);
+
+        header.start = start;
+        header.end = end;
+        header.start_loop += start;
+        header.end_loop += start;
+        header.sample_type &= !VORBIS_SAMPLE_TYPE;
+    }
+
+    Ok(wave_data)
+}
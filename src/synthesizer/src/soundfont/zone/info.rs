@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::io::Read;
 
 pub(crate) struct ZoneInfo {
     pub(crate) generator_index: i32,
@@ -25,7 +25,7 @@ impl ZoneInfo {
         reader: &mut R,
         size: usize,
     ) -> Result<Vec<ZoneInfo>, SoundFontError> {
-        if size % 4 != 0 {
+        if !size.is_multiple_of(4) {
             return Err(SoundFontError::InvalidZoneList);
         }
 
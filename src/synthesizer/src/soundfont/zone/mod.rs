@@ -6,30 +6,40 @@ use crate::prelude::*;
 
 pub(crate) struct Zone {
     pub(crate) generators: Vec<Generator>,
+    pub(crate) modulators: Vec<Modulator>,
 }
 
 impl Zone {
     pub(crate) fn empty() -> Self {
         Self {
             generators: Vec::new(),
+            modulators: Vec::new(),
         }
     }
 
-    fn new(info: &ZoneInfo, generators: &[Generator]) -> Self {
+    fn new(info: &ZoneInfo, generators: &[Generator], modulators: &[Modulator]) -> Self {
         let mut segment: Vec<Generator> = Vec::new();
 
         for i in 0..info.generator_count {
             segment.push(generators[(info.generator_index + i) as usize]);
         }
 
+        // Modulators that fall outside the list are ignored.
+        let modulators = (0..info.modulator_count)
+            .filter_map(|i| modulators.get((info.modulator_index + i) as usize))
+            .copied()
+            .collect();
+
         Self {
             generators: segment,
+            modulators,
         }
     }
 
     pub(crate) fn create(
         infos: &[ZoneInfo],
         generators: &[Generator],
+        modulators: &[Modulator],
     ) -> Result<Vec<Zone>, SoundFontError> {
         if infos.len() <= 1 {
             return Err(SoundFontError::ZoneNotFound);
@@ -40,7 +50,7 @@ impl Zone {
 
         let mut zones: Vec<Zone> = Vec::new();
         for info in infos.iter().take(count) {
-            zones.push(Zone::new(info, generators));
+            zones.push(Zone::new(info, generators, modulators));
         }
 
         Ok(zones)
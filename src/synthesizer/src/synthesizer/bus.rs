@@ -0,0 +1,127 @@
+use super::{Effects, SynthesizerError};
+
+/// Specifies where the reverb and chorus go when rendering to buses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BusEffects {
+    /// One reverb and chorus for every channel, as [`Synthesizer::render`](super::Synthesizer::render)
+    /// uses. Their output gets a bus of its own, after the others.
+    #[default]
+    Shared,
+    /// Each bus has its own reverb and chorus, mixed into it.
+    PerBus,
+}
+
+/// Specifies which bus each MIDI channel is rendered to,
+/// for [`Synthesizer::render_buses`](super::Synthesizer::render_buses).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BusLayout {
+    channel_buses: [usize; 16],
+    effects: BusEffects,
+}
+
+impl BusLayout {
+    /// The most buses a layout can have, one for each channel.
+    pub const MAXIMUM_BUS_COUNT: usize = 16;
+
+    /// A layout from the bus of each channel. Buses are numbered from 0 to 15, and
+    /// channels can share a bus.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_buses` - The bus of each channel, from channel 0 to 15.
+    pub fn new(channel_buses: [usize; 16]) -> Result<Self, SynthesizerError> {
+        if let Some(bus) = channel_buses
+            .iter()
+            .find(|bus| **bus >= Self::MAXIMUM_BUS_COUNT)
+        {
+            return Err(SynthesizerError::BusOutOfRange(*bus));
+        }
+
+        Ok(Self {
+            channel_buses,
+            effects: BusEffects::default(),
+        })
+    }
+
+    /// A layout that gives every channel its own bus.
+    pub fn per_channel() -> Self {
+        Self {
+            channel_buses: core::array::from_fn(|channel| channel),
+            effects: BusEffects::default(),
+        }
+    }
+
+    /// A layout with a bus for each group of channels, in order.
+    /// The channels that aren't in any group share a bus after the groups.
+    ///
+    /// Fails if a channel would be on a bus past the 16th.
+    pub fn groups(groups: &[&[u8]]) -> Result<Self, SynthesizerError> {
+        let mut channel_buses = [groups.len(); 16];
+        for (bus, group) in groups.iter().enumerate() {
+            for channel in group.iter() {
+                if let Some(channel_bus) = channel_buses.get_mut(*channel as usize) {
+                    *channel_bus = bus;
+                }
+            }
+        }
+        Self::new(channel_buses)
+    }
+
+    /// Sets where the reverb and chorus go.
+    pub fn with_effects(mut self, effects: BusEffects) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    /// Gets the bus of a channel.
+    pub fn get_bus(&self, channel: u8) -> usize {
+        self.channel_buses[channel as usize]
+    }
+
+    /// Gets where the reverb and chorus go.
+    pub fn get_effects(&self) -> BusEffects {
+        self.effects
+    }
+
+    /// Gets the number of buses the channels are rendered to.
+    pub fn get_bus_count(&self) -> usize {
+        self.channel_buses.iter().max().map_or(0, |bus| bus + 1)
+    }
+
+    /// Gets the number of stereo outputs, including the bus of the shared effects.
+    pub fn get_output_count(&self) -> usize {
+        match self.effects {
+            BusEffects::Shared => self.get_bus_count() + 1,
+            BusEffects::PerBus => self.get_bus_count(),
+        }
+    }
+}
+
+/// The block buffers of each output of a bus layout.
+pub(super) struct Buses {
+    pub(super) layout: BusLayout,
+    pub(super) outputs: Vec<BusOutput>,
+}
+
+pub(super) struct BusOutput {
+    pub(super) left: Vec<f32>,
+    pub(super) right: Vec<f32>,
+    // Only used by per-bus effects
+    pub(super) effects: Option<Effects>,
+}
+
+impl Buses {
+    pub(super) fn new(layout: BusLayout, block_size: usize, effects: impl Fn() -> Effects) -> Self {
+        let outputs = (0..layout.get_output_count())
+            .map(|_| BusOutput {
+                left: vec![0_f32; block_size],
+                right: vec![0_f32; block_size],
+                effects: match layout.effects {
+                    BusEffects::Shared => None,
+                    BusEffects::PerBus => Some(effects()),
+                },
+            })
+            .collect();
+        Self { layout, outputs }
+    }
+}
@@ -16,18 +16,44 @@ pub(crate) struct Channel {
     pan: u16,
     expression: u16,
     hold_pedal: bool,
+    sostenuto: bool,
+    soft_pedal: bool,
+    legato: bool,
+
+    portamento: bool,
+    portamento_time: u16,
+    portamento_source: Option<u8>,
+    // The last key played, where portamento glides from.
+    last_key: Option<u8>,
 
     reverb_send: u8,
     chorus_send: u8,
 
     rpn: u16,
     pitch_bend_range: u16,
-    coarse_tune: u16,
+    /// Semitones, where 0 is A440
+    coarse_tune: i16,
     fine_tune: u16,
+    tuning_bank: u8,
+    // None until a tuning program is selected, so the keys are equal tempered.
+    tuning_program: Option<u8>,
+    /// The scale/octave tuning of each semitone from C, in semitones.
+    scale_tuning: [f32; 12],
 
     pitch_bend: f32,
 
+    /// The last value of every controller, read by the modulators.
+    controllers: [u8; 128],
+    channel_pressure: u8,
+    key_pressure: [u8; 128],
+
     last_data_type: DataType,
+
+    // Set by the synthesizer rather than by messages, so resets keep them.
+    /// The most notes the channel can sound at once.
+    polyphony: Option<usize>,
+    /// Which channel loses voices first, lowest first.
+    priority: u8,
 }
 
 impl Channel {
@@ -41,14 +67,29 @@ impl Channel {
             pan: 0,
             expression: 0,
             hold_pedal: false,
+            sostenuto: false,
+            soft_pedal: false,
+            legato: false,
+            portamento: false,
+            portamento_time: 0,
+            portamento_source: None,
+            last_key: None,
             reverb_send: 0,
             chorus_send: 0,
             rpn: 0,
             pitch_bend_range: 0,
             coarse_tune: 0,
             fine_tune: 0,
+            tuning_bank: 0,
+            tuning_program: None,
+            scale_tuning: [0_f32; 12],
             pitch_bend: 0_f32,
+            controllers: [0; 128],
+            channel_pressure: 0,
+            key_pressure: [0; 128],
             last_data_type: DataType::None,
+            polyphony: None,
+            priority: 0,
         };
 
         channel.reset();
@@ -65,6 +106,14 @@ impl Channel {
         self.pan = 64 << 7;
         self.expression = 127 << 7;
         self.hold_pedal = false;
+        self.sostenuto = false;
+        self.soft_pedal = false;
+        self.legato = false;
+
+        self.portamento = false;
+        self.portamento_time = 0;
+        self.portamento_source = None;
+        self.last_key = None;
 
         self.reverb_send = 40;
         self.chorus_send = 0;
@@ -73,18 +122,60 @@ impl Channel {
         self.pitch_bend_range = 2 << 7;
         self.coarse_tune = 0;
         self.fine_tune = 8192;
+        self.tuning_bank = 0;
+        self.tuning_program = None;
+        self.scale_tuning = [0_f32; 12];
 
         self.pitch_bend = 0_f32;
+
+        self.controllers = [0; 128];
+        self.controllers[0x07] = 100;
+        self.controllers[0x0A] = 64;
+        self.controllers[0x0B] = 127;
+        self.controllers[0x5B] = 40;
+
+        self.channel_pressure = 0;
+        self.key_pressure = [0; 128];
+    }
+
+    pub(crate) fn get_polyphony(&self) -> Option<usize> {
+        self.polyphony
+    }
+
+    pub(crate) fn set_polyphony(&mut self, polyphony: Option<usize>) {
+        self.polyphony = polyphony;
+    }
+
+    pub(crate) fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
+    pub(crate) fn set_priority(&mut self, priority: u8) {
+        self.priority = priority;
     }
 
     pub(crate) fn reset_all_controllers(&mut self) {
         self.modulation = 0;
         self.expression = 127 << 7;
         self.hold_pedal = false;
+        self.sostenuto = false;
+        self.soft_pedal = false;
+        self.portamento = false;
 
         self.rpn = 0xFFFF;
 
         self.pitch_bend = 0_f32;
+
+        self.controllers[0x01] = 0;
+        self.controllers[0x0B] = 127;
+        self.controllers[0x40..=0x43].fill(0);
+
+        self.channel_pressure = 0;
+        self.key_pressure = [0; 128];
+    }
+
+    pub(crate) fn set_controller(&mut self, number: u8, value: u8) {
+        self.controllers[number as usize & 0x7F] = value;
     }
 
     pub(crate) fn set_bank(&mut self, value: u8) {
@@ -95,6 +186,14 @@ impl Channel {
         }
     }
 
+    /// Switches the channel between melodic instruments and drum kits, keeping its bank select.
+    pub(crate) fn set_percussion(&mut self, percussion: bool) {
+        if self.is_percussion_channel != percussion {
+            self.is_percussion_channel = percussion;
+            self.bank_number = self.bank_number.wrapping_add(128);
+        }
+    }
+
     pub(crate) fn set_patch(&mut self, value: u8) {
         self.patch_number = value;
     }
@@ -135,6 +234,48 @@ impl Channel {
         self.hold_pedal = value >= 64;
     }
 
+    /// Returns `true` if the sostenuto pedal was pressed by this message.
+    pub(crate) fn set_sostenuto(&mut self, value: u8) -> bool {
+        let pressed = !self.sostenuto && value >= 64;
+        self.sostenuto = value >= 64;
+        pressed
+    }
+
+    pub(crate) fn set_soft_pedal(&mut self, value: u8) {
+        self.soft_pedal = value >= 64;
+    }
+
+    pub(crate) fn set_legato(&mut self, value: u8) {
+        self.legato = value >= 64;
+    }
+
+    pub(crate) fn set_portamento(&mut self, value: u8) {
+        self.portamento = value >= 64;
+    }
+
+    pub(crate) fn set_portamento_time_coarse(&mut self, value: u8) {
+        self.portamento_time = (self.portamento_time & 0x7F) | ((value as u16) << 7);
+    }
+
+    pub(crate) fn set_portamento_time_fine(&mut self, value: u8) {
+        self.portamento_time = (self.portamento_time & 0xFF80) | value as u16;
+    }
+
+    pub(crate) fn set_portamento_source(&mut self, key: u8) {
+        self.portamento_source = Some(key);
+    }
+
+    /// Records a new note, returning the key to glide from, if any.
+    /// A key set by portamento control is used once, even if portamento is off.
+    pub(crate) fn start_note(&mut self, key: u8) -> Option<u8> {
+        let previous = self.last_key.replace(key);
+        match self.portamento_source.take() {
+            Some(source) => Some(source),
+            None if self.portamento => previous,
+            None => None,
+        }
+    }
+
     pub(crate) fn set_reverb_send(&mut self, value: u8) {
         self.reverb_send = value;
     }
@@ -171,8 +312,11 @@ impl Channel {
         } else if self.rpn == 1 {
             self.fine_tune = (self.fine_tune & 0x7F) | ((value as u16) << 7);
         } else if self.rpn == 2 {
-            //TODO: what
-            self.coarse_tune = (value as u16) - 64;
+            self.coarse_tune = value as i16 - 64;
+        } else if self.rpn == 3 {
+            self.tuning_program = Some(value);
+        } else if self.rpn == 4 {
+            self.tuning_bank = value;
         }
     }
 
@@ -188,6 +332,14 @@ impl Channel {
         }
     }
 
+    pub(crate) fn set_channel_pressure(&mut self, value: u8) {
+        self.channel_pressure = value;
+    }
+
+    pub(crate) fn set_key_pressure(&mut self, key: u8, value: u8) {
+        self.key_pressure[key as usize & 0x7F] = value;
+    }
+
     pub(crate) fn set_pitch_bend(&mut self, lsb: u8, msb: u8) {
         self.pitch_bend = (1_f32 / 8192_f32) * ((lsb as i32 | ((msb as i32) << 7)) - 8192) as f32;
     }
@@ -220,6 +372,24 @@ impl Channel {
         self.hold_pedal
     }
 
+    pub(crate) fn get_sostenuto(&self) -> bool {
+        self.sostenuto
+    }
+
+    pub(crate) fn get_soft_pedal(&self) -> bool {
+        self.soft_pedal
+    }
+
+    pub(crate) fn get_legato(&self) -> bool {
+        self.legato
+    }
+
+    /// The portamento time in seconds, on a square curve reaching 8 seconds.
+    pub(crate) fn get_portamento_time(&self) -> f32 {
+        let x = (1_f32 / 16384_f32) * self.portamento_time as f32;
+        8_f32 * x * x
+    }
+
     pub(crate) fn get_reverb_send(&self) -> f32 {
         (1_f32 / 127_f32) * self.reverb_send as f32
     }
@@ -233,7 +403,38 @@ impl Channel {
     }
 
     pub(crate) fn get_tune(&self) -> f32 {
-        self.coarse_tune as f32 + (1_f32 / 8192_f32) * (self.fine_tune - 8192) as f32
+        self.coarse_tune as f32 + (1_f32 / 8192_f32) * (self.fine_tune as i32 - 8192) as f32
+    }
+
+    /// The selected tuning bank and program, if any.
+    pub(crate) fn get_tuning_program(&self) -> Option<(u8, u8)> {
+        self.tuning_program
+            .map(|program| (self.tuning_bank, program))
+    }
+
+    pub(crate) fn set_scale_tuning(&mut self, offsets: [f32; 12]) {
+        self.scale_tuning = offsets;
+    }
+
+    pub(crate) fn get_scale_tuning(&self, key: u8) -> f32 {
+        self.scale_tuning[key as usize % 12]
+    }
+
+    pub(crate) fn get_controller(&self, number: u16) -> u8 {
+        self.controllers[number as usize & 0x7F]
+    }
+
+    pub(crate) fn get_channel_pressure(&self) -> u8 {
+        self.channel_pressure
+    }
+
+    pub(crate) fn get_key_pressure(&self, key: u8) -> u8 {
+        self.key_pressure[key as usize & 0x7F]
+    }
+
+    /// The pitch wheel position, from 0 to 1 with the center at 0.5.
+    pub(crate) fn get_pitch_wheel(&self) -> f32 {
+        0.5_f32 * (self.pitch_bend + 1_f32)
     }
 
     pub(crate) fn get_pitch_bend(&self) -> f32 {
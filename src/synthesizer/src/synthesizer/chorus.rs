@@ -1,4 +1,4 @@
-use bevy_platform::prelude::*;
+use std::f64::consts;
 pub(crate) struct Chorus {
     buffer_l: Vec<f32>,
     buffer_r: Vec<f32>,
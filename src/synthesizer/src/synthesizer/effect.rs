@@ -0,0 +1,287 @@
+use super::{ArrayMath, Chorus, Reverb, Synthesizer, voice::Voice};
+
+/// An effect that processes blocks of stereo audio in place, such as a limiter, an EQ
+/// or a convolution reverb.
+///
+/// Effects are added to an [`EffectBus`] with [`Synthesizer::add_effect`],
+/// and run on the thread that renders.
+pub trait AudioEffect: Send {
+    /// Processes a block. Both channels have the synthesizer's block size.
+    fn process(&mut self, left: &mut [f32], right: &mut [f32]);
+
+    /// Clears the state of the effect, such as its tail, when the synthesizer is reset.
+    fn reset(&mut self) {}
+}
+
+/// Specifies where an [`AudioEffect`] is inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EffectBus {
+    /// The final mix, after the reverb and chorus.
+    Master,
+    /// The reverb send, after the built-in reverb.
+    Reverb,
+    /// The chorus send, after the built-in chorus.
+    Chorus,
+}
+
+/// Specifies the parameters of the built-in reverb, each from 0 to 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReverbParameters {
+    /// The size of the room, which lengthens the tail.
+    pub room_size: f32,
+    /// How quickly the high frequencies fade.
+    pub damping: f32,
+    /// The level of the reverb.
+    pub wet: f32,
+    /// The stereo width of the reverb.
+    pub width: f32,
+}
+
+impl Default for ReverbParameters {
+    fn default() -> Self {
+        Self {
+            room_size: Reverb::INITIAL_ROOM,
+            damping: Reverb::INITIAL_DAMP,
+            wet: Reverb::INITIAL_WET,
+            width: Reverb::INITIAL_WIDTH,
+        }
+    }
+}
+
+impl ReverbParameters {
+    pub(super) fn clamped(&self) -> Self {
+        Self {
+            room_size: self.room_size.clamp(0_f32, 1_f32),
+            damping: self.damping.clamp(0_f32, 1_f32),
+            wet: self.wet.clamp(0_f32, 1_f32),
+            width: self.width.clamp(0_f32, 1_f32),
+        }
+    }
+}
+
+/// Specifies the parameters of the built-in chorus.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChorusParameters {
+    /// The delay of the chorus in seconds, from 0 to 0.1.
+    pub delay: f64,
+    /// How far the delay swings either way in seconds, up to the delay.
+    pub depth: f64,
+    /// How often the delay swings in Hz, from 0.01 to 20.
+    pub frequency: f64,
+}
+
+impl Default for ChorusParameters {
+    fn default() -> Self {
+        Self {
+            delay: 0.002,
+            depth: 0.0019,
+            frequency: 0.4,
+        }
+    }
+}
+
+impl ChorusParameters {
+    pub(super) fn clamped(&self) -> Self {
+        let delay = self.delay.clamp(0.0, 0.1);
+        Self {
+            delay,
+            depth: self.depth.clamp(0.0, delay),
+            frequency: self.frequency.clamp(0.01, 20.0),
+        }
+    }
+}
+
+/// The reverb and chorus sends, with the built-in effects and the ones added to them.
+pub(super) struct Effects {
+    sample_rate: i32,
+
+    pub(super) reverb: Option<Reverb>,
+    pub(super) reverb_effects: Vec<Box<dyn AudioEffect>>,
+    reverb_input_left: Vec<f32>,
+    reverb_input_right: Vec<f32>,
+    reverb_input: Vec<f32>,
+    reverb_output_left: Vec<f32>,
+    reverb_output_right: Vec<f32>,
+
+    pub(super) chorus: Option<Chorus>,
+    pub(super) chorus_effects: Vec<Box<dyn AudioEffect>>,
+    chorus_input_left: Vec<f32>,
+    chorus_input_right: Vec<f32>,
+    chorus_output_left: Vec<f32>,
+    chorus_output_right: Vec<f32>,
+}
+
+impl Effects {
+    pub(super) fn new(
+        sample_rate: i32,
+        block_size: usize,
+        reverb: Option<ReverbParameters>,
+        chorus: Option<ChorusParameters>,
+    ) -> Self {
+        let mut effects = Self {
+            sample_rate,
+            reverb: None,
+            reverb_effects: Vec::new(),
+            reverb_input_left: vec![0_f32; block_size],
+            reverb_input_right: vec![0_f32; block_size],
+            reverb_input: vec![0_f32; block_size],
+            reverb_output_left: vec![0_f32; block_size],
+            reverb_output_right: vec![0_f32; block_size],
+            chorus: None,
+            chorus_effects: Vec::new(),
+            chorus_input_left: vec![0_f32; block_size],
+            chorus_input_right: vec![0_f32; block_size],
+            chorus_output_left: vec![0_f32; block_size],
+            chorus_output_right: vec![0_f32; block_size],
+        };
+        effects.set_reverb(reverb);
+        effects.set_chorus(chorus);
+        effects
+    }
+
+    /// Retunes the reverb, keeping its tail, or adds or removes it.
+    pub(super) fn set_reverb(&mut self, parameters: Option<ReverbParameters>) {
+        match parameters {
+            Some(parameters) => self
+                .reverb
+                .get_or_insert_with(|| Reverb::new(self.sample_rate))
+                .set_parameters(&parameters),
+            None => self.reverb = None,
+        }
+    }
+
+    /// Replaces the chorus, since its delay line depends on the parameters.
+    pub(super) fn set_chorus(&mut self, parameters: Option<ChorusParameters>) {
+        self.chorus = parameters.map(|parameters| {
+            Chorus::new(
+                self.sample_rate,
+                parameters.delay,
+                parameters.depth,
+                parameters.frequency,
+            )
+        });
+    }
+
+    /// Adds the chorus and reverb of the voices to a block.
+    /// A send is skipped when it has no effects.
+    pub(super) fn process<'a>(
+        &mut self,
+        voices: impl Iterator<Item = &'a Voice> + Clone,
+        master_volume: f32,
+        left: &mut [f32],
+        right: &mut [f32],
+        inverse_block_size: f32,
+    ) {
+        if self.chorus.is_some() || !self.chorus_effects.is_empty() {
+            self.chorus_input_left.fill(0_f32);
+            self.chorus_input_right.fill(0_f32);
+            for voice in voices.clone() {
+                let previous_gain_left = voice.previous_chorus_send * voice.previous_mix_gain_left;
+                let current_gain_left = voice.current_chorus_send * voice.current_mix_gain_left;
+                Synthesizer::write_block(
+                    previous_gain_left,
+                    current_gain_left,
+                    &voice.block[..],
+                    &mut self.chorus_input_left,
+                    inverse_block_size,
+                );
+                let previous_gain_right =
+                    voice.previous_chorus_send * voice.previous_mix_gain_right;
+                let current_gain_right = voice.current_chorus_send * voice.current_mix_gain_right;
+                Synthesizer::write_block(
+                    previous_gain_right,
+                    current_gain_right,
+                    &voice.block[..],
+                    &mut self.chorus_input_right,
+                    inverse_block_size,
+                );
+            }
+
+            let (send_left, send_right) = match self.chorus.as_mut() {
+                Some(chorus) => {
+                    chorus.process(
+                        &self.chorus_input_left,
+                        &self.chorus_input_right,
+                        &mut self.chorus_output_left,
+                        &mut self.chorus_output_right,
+                    );
+                    (&mut self.chorus_output_left, &mut self.chorus_output_right)
+                }
+                None => (&mut self.chorus_input_left, &mut self.chorus_input_right),
+            };
+            for effect in self.chorus_effects.iter_mut() {
+                effect.process(send_left, send_right);
+            }
+            ArrayMath::multiply_add(master_volume, send_left, left);
+            ArrayMath::multiply_add(master_volume, send_right, right);
+        }
+
+        if self.reverb.is_some() || !self.reverb_effects.is_empty() {
+            self.reverb_input_left.fill(0_f32);
+            self.reverb_input_right.fill(0_f32);
+            for voice in voices {
+                let previous_gain_left = voice.previous_reverb_send * voice.previous_mix_gain_left;
+                let current_gain_left = voice.current_reverb_send * voice.current_mix_gain_left;
+                Synthesizer::write_block(
+                    previous_gain_left,
+                    current_gain_left,
+                    &voice.block[..],
+                    &mut self.reverb_input_left,
+                    inverse_block_size,
+                );
+                let previous_gain_right =
+                    voice.previous_reverb_send * voice.previous_mix_gain_right;
+                let current_gain_right = voice.current_reverb_send * voice.current_mix_gain_right;
+                Synthesizer::write_block(
+                    previous_gain_right,
+                    current_gain_right,
+                    &voice.block[..],
+                    &mut self.reverb_input_right,
+                    inverse_block_size,
+                );
+            }
+
+            let (send_left, send_right) = match self.reverb.as_mut() {
+                Some(reverb) => {
+                    // the built-in reverb takes a mono input
+                    let gain = reverb.get_input_gain();
+                    for (input, (left, right)) in self.reverb_input.iter_mut().zip(
+                        self.reverb_input_left
+                            .iter()
+                            .zip(self.reverb_input_right.iter()),
+                    ) {
+                        *input = gain * (left + right);
+                    }
+                    reverb.process(
+                        &self.reverb_input,
+                        &mut self.reverb_output_left,
+                        &mut self.reverb_output_right,
+                    );
+                    (&mut self.reverb_output_left, &mut self.reverb_output_right)
+                }
+                None => (&mut self.reverb_input_left, &mut self.reverb_input_right),
+            };
+            for effect in self.reverb_effects.iter_mut() {
+                effect.process(send_left, send_right);
+            }
+            ArrayMath::multiply_add(master_volume, send_left, left);
+            ArrayMath::multiply_add(master_volume, send_right, right);
+        }
+    }
+
+    pub(super) fn reset(&mut self) {
+        if let Some(reverb) = self.reverb.as_mut() {
+            reverb.mute();
+        }
+        if let Some(chorus) = self.chorus.as_mut() {
+            chorus.mute();
+        }
+        for effect in self
+            .reverb_effects
+            .iter_mut()
+            .chain(self.chorus_effects.iter_mut())
+        {
+            effect.reset();
+        }
+    }
+}
@@ -1,10 +1,11 @@
-use bevy_platform::prelude::*;
+use std::{error, fmt};
 /// Represents an error when initializing a synthesizer.
 #[derive(Debug)]
 pub enum SynthesizerError {
     SampleRateOutOfRange(i32),
     BlockSizeOutOfRange(usize),
     MaximumPolyphonyOutOfRange(usize),
+    BusOutOfRange(usize),
 }
 
 impl error::Error for SynthesizerError {}
@@ -26,6 +27,9 @@ impl fmt::Display for SynthesizerError {
                     "the maximum number of polyphony must be between 8 and 256, but was {value}",
                 )
             }
+            SynthesizerError::BusOutOfRange(value) => {
+                write!(f, "a bus must be between 0 and 15, but was {value}")
+            }
         }
     }
 }
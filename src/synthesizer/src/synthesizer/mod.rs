@@ -18,12 +18,37 @@ use array_math::*;
 mod loop_mode;
 pub use loop_mode::*;
 
+mod voice_stealing;
+pub use voice_stealing::*;
+
+mod bus;
+pub use bus::*;
+
+mod effect;
+pub use effect::*;
+
 mod channel;
 use channel::*;
+
+mod tuning;
+use tuning::*;
+
+mod sysex;
+use sysex::*;
+
+mod voice_message;
+pub use voice_message::*;
+
+mod schedule;
+use schedule::*;
 use voice::{RegionPair, Voice};
 
 use crate::{prelude::*, utils};
-use bevy_platform::prelude::*;
+use std::{
+    cmp,
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
 
 /// An instance of the SoundFont synthesizer.
 pub struct Synthesizer {
@@ -41,6 +66,9 @@ pub struct Synthesizer {
 
     voices: Vec<Voice>,
 
+    // MTS key-based tuning programs, by bank and program
+    tunings: HashMap<(u8, u8), Tuning>,
+
     block_left: Vec<f32>,
     block_right: Vec<f32>,
 
@@ -48,9 +76,27 @@ pub struct Synthesizer {
 
     block_read: usize,
 
-    master_volume: f32,
+    // The samples rendered so far
+    position: u64,
+    // Messages due at a later sample, sorted by that sample
+    scheduled: VecDeque<(u64, ScheduledMessage)>,
 
-    effects: Option<Effects>,
+    master_volume: f32,
+    // Set by master volume sysex messages
+    device_volume: f32,
+
+    effects: Effects,
+    // Parameters of the built-in effects, or `None` when they are turned off
+    reverb_parameters: Option<ReverbParameters>,
+    chorus_parameters: Option<ChorusParameters>,
+    master_effects: Vec<Box<dyn AudioEffect>>,
+    // Set when rendering to buses
+    buses: Option<Buses>,
+
+    voice_stealing: VoiceStealing,
+    stolen_voice_count: u64,
+    // The notes started so far, which numbers the voices of each note
+    note_count: u64,
 }
 
 impl Synthesizer {
@@ -109,11 +155,18 @@ impl Synthesizer {
 
         let master_volume = 0.5_f32;
 
-        let effects = if settings.enable_reverb_and_chorus {
-            Some(Effects::new(settings))
-        } else {
-            None
-        };
+        let reverb_parameters = settings
+            .enable_reverb_and_chorus
+            .then(|| settings.reverb.clamped());
+        let chorus_parameters = settings
+            .enable_reverb_and_chorus
+            .then(|| settings.chorus.clamped());
+        let effects = Effects::new(
+            settings.sample_rate,
+            settings.block_size,
+            reverb_parameters,
+            chorus_parameters,
+        );
 
         Ok(Self {
             sound_font,
@@ -125,12 +178,23 @@ impl Synthesizer {
             channels,
             settings: *settings,
             voices: Vec::with_capacity(settings.maximum_polyphony),
+            tunings: HashMap::new(),
             block_left,
             block_right,
             inverse_block_size,
             block_read,
+            position: 0,
+            scheduled: VecDeque::new(),
             master_volume,
+            device_volume: 1_f32,
             effects,
+            reverb_parameters,
+            chorus_parameters,
+            master_effects: Vec::new(),
+            buses: None,
+            voice_stealing: settings.voice_stealing,
+            stolen_voice_count: 0,
+            note_count: 0,
         })
     }
 
@@ -143,52 +207,124 @@ impl Synthesizer {
     /// * `data1` - The first data part of the message.
     /// * `data2` - The second data part of the message.
     pub fn process_midi_message(&mut self, status: u8, data1: u8, data2: u8) {
-        let channel = status & 0x0F;
-        let command = status & 0xF0;
+        if let Some((channel, message)) = VoiceMessage::decode(status, data1, data2) {
+            self.process_voice_message(channel, message);
+        }
+    }
+
+    /// Processes a decoded channel voice message.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel of the message, from 0 to 15.
+    /// * `message` - The message.
+    pub fn process_voice_message(&mut self, channel: u8, message: VoiceMessage) {
+        self.apply_voice_message(channel, message, 0);
+    }
 
+    /// Applies a voice message, starting notes `delay` samples into the next block.
+    fn apply_voice_message(&mut self, channel: u8, message: VoiceMessage, delay: usize) {
+        match message {
+            VoiceMessage::NoteOff { key } => self.note_off(channel, key),
+            VoiceMessage::NoteOn { key, velocity } => {
+                self.start_note(channel, key, velocity, delay)
+            }
+            VoiceMessage::KeyPressure { key, pressure } => {
+                self.key_pressure(channel, key, pressure)
+            }
+            VoiceMessage::ControlChange { controller, value } => {
+                self.control_change(channel, controller, value)
+            }
+            VoiceMessage::ProgramChange { program } => self.program_change(channel, program),
+            VoiceMessage::ChannelPressure { pressure } => self.channel_pressure(channel, pressure),
+            VoiceMessage::PitchBend { value } => self.pitch_bend(channel, value),
+        }
+    }
+
+    /// Changes the value of a controller. Controllers 120 to 127 are the channel mode messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel of the controller.
+    /// * `controller` - The number of the controller.
+    /// * `value` - The new value of the controller.
+    pub fn control_change(&mut self, channel: u8, controller: u8, value: u8) {
         if channel as usize >= self.channels.len() {
             return;
         }
 
         let channel_info = &mut self.channels[channel as usize];
-
-        match command {
-            0x80 => self.note_off(channel, data1),       // Note Off
-            0x90 => self.note_on(channel, data1, data2), // Note On
-            0xB0 => match data1 // Controller
-            {
-                0x00 => channel_info.set_bank(data2), // Bank Selection
-                0x01 => channel_info.set_modulation_coarse(data2), // Modulation Coarse
-                0x21 => channel_info.set_modulation_fine(data2), // Modulation Fine
-                0x06 => channel_info.data_entry_coarse(data2), // Data Entry Coarse
-                0x26 => channel_info.data_entry_fine(data2), // Data Entry Fine
-                0x07 => channel_info.set_volume_coarse(data2), // Channel Volume Coarse
-                0x27 => channel_info.set_volume_fine(data2), // Channel Volume Fine
-                0x0A => channel_info.set_pan_coarse(data2), // Pan Coarse
-                0x2A => channel_info.set_pan_fine(data2), // Pan Fine
-                0x0B => channel_info.set_expression_coarse(data2), // Expression Coarse
-                0x2B => channel_info.set_expression_fine(data2), // Expression Fine
-                0x40 => channel_info.set_hold_pedal(data2), // Hold Pedal
-                0x5B => channel_info.set_reverb_send(data2), // Reverb Send
-                0x5D => channel_info.set_chorus_send(data2), // Chorus Send
-                //Note, this used to not use data 2
-                0x63 => channel_info.set_nrpn_coarse(), // NRPN Coarse
-                //Note: this used to not use data 2
-                0x62 => channel_info.set_nrpn_fine(), // NRPN Fine
-                0x65 => channel_info.set_rpn_coarse(data2), // RPN Coarse
-                0x64 => channel_info.set_rpn_fine(data2), // RPN Fine
-
-                0x78 => self.note_off_all_channel(channel, true), // All Sound Off
-                0x79 => self.reset_all_controllers_channel(channel), // Reset All Controllers
-                0x7B => self.note_off_all_channel(channel, false), // All Note Off
-                _ => (),
-            },
-            0xC0 => channel_info.set_patch(data1), // Program Change
-            0xE0 => channel_info.set_pitch_bend(data1, data2), // Pitch Bend
+        channel_info.set_controller(controller, value);
+        match controller {
+            0x00 => channel_info.set_bank(value), // Bank Selection
+            0x01 => channel_info.set_modulation_coarse(value), // Modulation Coarse
+            0x21 => channel_info.set_modulation_fine(value), // Modulation Fine
+            0x06 => channel_info.data_entry_coarse(value), // Data Entry Coarse
+            0x26 => channel_info.data_entry_fine(value), // Data Entry Fine
+            0x07 => channel_info.set_volume_coarse(value), // Channel Volume Coarse
+            0x27 => channel_info.set_volume_fine(value), // Channel Volume Fine
+            0x0A => channel_info.set_pan_coarse(value), // Pan Coarse
+            0x2A => channel_info.set_pan_fine(value), // Pan Fine
+            0x0B => channel_info.set_expression_coarse(value), // Expression Coarse
+            0x2B => channel_info.set_expression_fine(value), // Expression Fine
+            0x40 => channel_info.set_hold_pedal(value), // Hold Pedal
+            0x05 => channel_info.set_portamento_time_coarse(value), // Portamento Time Coarse
+            0x25 => channel_info.set_portamento_time_fine(value), // Portamento Time Fine
+            0x41 => channel_info.set_portamento(value), // Portamento
+            // Sostenuto
+            0x42 => {
+                let pressed = channel_info.set_sostenuto(value);
+                if pressed {
+                    self.latch_sostenuto(channel);
+                }
+            }
+            0x43 => channel_info.set_soft_pedal(value), // Soft Pedal
+            0x44 => channel_info.set_legato(value),     // Legato Footswitch
+            0x54 => channel_info.set_portamento_source(value), // Portamento Control
+            0x5B => channel_info.set_reverb_send(value), // Reverb Send
+            0x5D => channel_info.set_chorus_send(value), // Chorus Send
+            //Note, this used to not use data 2
+            0x63 => channel_info.set_nrpn_coarse(), // NRPN Coarse
+            //Note: this used to not use data 2
+            0x62 => channel_info.set_nrpn_fine(), // NRPN Fine
+            0x65 => channel_info.set_rpn_coarse(value), // RPN Coarse
+            0x64 => channel_info.set_rpn_fine(value), // RPN Fine
+
+            0x78 => self.note_off_all_channel(channel, true), // All Sound Off
+            0x79 => self.reset_all_controllers_channel(channel), // Reset All Controllers
+            0x7B => self.note_off_all_channel(channel, false), // All Note Off
             _ => (),
         }
     }
 
+    /// Changes the program (instrument) of a channel. The bank is selected by controller 0.
+    pub fn program_change(&mut self, channel: u8, program: u8) {
+        if let Some(channel_info) = self.channels.get_mut(channel as usize) {
+            channel_info.set_patch(program);
+        }
+    }
+
+    /// Bends the pitch of a channel, where `value` is 14-bit and 8192 is centered.
+    pub fn pitch_bend(&mut self, channel: u8, value: u16) {
+        if let Some(channel_info) = self.channels.get_mut(channel as usize) {
+            channel_info.set_pitch_bend((value & 0x7F) as u8, ((value >> 7) & 0x7F) as u8);
+        }
+    }
+
+    /// Sets the pressure (aftertouch) of a whole channel.
+    pub fn channel_pressure(&mut self, channel: u8, value: u8) {
+        if let Some(channel_info) = self.channels.get_mut(channel as usize) {
+            channel_info.set_channel_pressure(value);
+        }
+    }
+
+    /// Sets the pressure (polyphonic aftertouch) of a key of a channel.
+    pub fn key_pressure(&mut self, channel: u8, key: u8, value: u8) {
+        if let Some(channel_info) = self.channels.get_mut(channel as usize) {
+            channel_info.set_key_pressure(key, value);
+        }
+    }
+
     /// Stops a note.
     ///
     /// # Arguments
@@ -215,6 +351,11 @@ impl Synthesizer {
     /// * `key` - The key of the note.
     /// * `velocity` - The velocity of the note.
     pub fn note_on(&mut self, channel: u8, key: u8, velocity: u8) {
+        self.start_note(channel, key, velocity, 0);
+    }
+
+    /// Starts a note whose voices stay silent for `delay` samples of their first block.
+    fn start_note(&mut self, channel: u8, key: u8, velocity: u8, delay: usize) {
         if velocity == 0 {
             self.note_off(channel, key);
             return;
@@ -224,7 +365,37 @@ impl Synthesizer {
             return;
         }
 
+        // Key pressure is sent after the note starts.
+        self.channels[channel as usize].set_key_pressure(key, 0);
+        let glide_from = self.channels[channel as usize].start_note(key);
+
+        let tuning = self.get_key_tuning(channel, key);
+        let glide_from = glide_from.map(|from| from as f32 + self.get_key_tuning(channel, from));
+
         let channel_info = &self.channels[channel as usize];
+        let glide_time = if glide_from.is_some() {
+            channel_info.get_portamento_time()
+        } else {
+            0_f32
+        };
+
+        // In legato mode, the held notes move to the new key without a new attack,
+        // as long as their regions cover it.
+        if channel_info.get_legato() {
+            let mut held = self
+                .voices
+                .iter_mut()
+                .filter(|voice| voice.channel == channel && voice.is_held())
+                .peekable();
+            if held.peek().is_some() && held.all(|voice| voice.covers(key)) {
+                for voice in self.voices.iter_mut() {
+                    if voice.channel == channel && voice.is_held() {
+                        voice.legato(key, tuning, glide_time);
+                    }
+                }
+                return;
+            }
+        }
 
         let preset_id = ((channel_info.get_bank_number() as i32) << 16)
             | channel_info.get_patch_number() as i32;
@@ -253,6 +424,31 @@ impl Synthesizer {
         };
 
         let preset = &self.sound_font.presets[preset];
+
+        // Make room for every voice of the note at once, so it never steals its own voices
+        let regions = preset
+            .regions
+            .iter()
+            .filter(|preset_region| preset_region.contains(key, velocity))
+            .map(|preset_region| {
+                self.sound_font.instruments[preset_region.instrument]
+                    .regions
+                    .iter()
+                    .filter(|instrument_region| instrument_region.contains(key, velocity))
+                    .count()
+            })
+            .sum();
+        self.stolen_voice_count += self.voice_stealing.make_room(
+            &mut self.voices,
+            &self.channels,
+            self.maximum_polyphony,
+            channel,
+            key,
+            regions,
+        );
+        self.note_count += 1;
+        let note = self.note_count;
+
         for preset_region in preset.regions.iter() {
             if preset_region.contains(key, velocity) {
                 let instrument = &self.sound_font.instruments[preset_region.instrument];
@@ -273,22 +469,40 @@ impl Synthesizer {
                                     *voice = Voice::new(
                                         &self.settings,
                                         &region_pair,
+                                        channel_info,
                                         channel,
                                         key,
                                         velocity,
                                     );
+                                    voice.tuning = tuning;
+                                    voice.delay = delay;
+                                    voice.note = note;
+                                    if let Some(from) = glide_from {
+                                        voice.glide(from, glide_time);
+                                    }
                                     return;
                                 }
                             }
                         }
 
-                        self.voices.push(Voice::new(
+                        let mut voice = Voice::new(
                             &self.settings,
                             &region_pair,
+                            channel_info,
                             channel,
                             key,
                             velocity,
-                        ));
+                        );
+                        voice.tuning = tuning;
+                        voice.delay = delay;
+                        voice.note = note;
+                        if let Some(from) = glide_from {
+                            voice.glide(from, glide_time);
+                        }
+                        // A note with more regions than the polyphony plays the first ones
+                        if self.voices.len() < self.maximum_polyphony {
+                            self.voices.push(voice);
+                        }
                     }
                 }
             }
@@ -348,17 +562,190 @@ impl Synthesizer {
         self.channels[channel as usize].reset_all_controllers();
     }
 
-    /// Resets the synthesizer.
+    /// Holds the notes of a channel whose keys are down, as the sostenuto pedal is pressed.
+    /// They are released once the pedal is.
+    fn latch_sostenuto(&mut self, channel: u8) {
+        for voice in self.voices.iter_mut() {
+            if voice.channel == channel {
+                voice.sostenuto = voice.is_held();
+            }
+        }
+    }
+
+    /// Processes a system exclusive message, with or without its leading `0xF0` and trailing `0xF7`.
+    ///
+    /// These messages are applied, whatever their device ID:
+    /// - GM and GM2 System On, GS Reset and XG System On, which reset every channel
+    /// - the universal, GS and XG master volume
+    /// - GS "use for rhythm part" and XG part mode, which switch a channel between
+    ///   instruments and drum kits
+    /// - MIDI Tuning Standard bulk dumps, single note tuning changes and scale/octave tunings
+    ///   (in both the 1-byte and 2-byte forms)
+    ///
+    /// Channels use a key-based tuning once they select its program with RPN 3 (and bank with RPN 4).
+    /// Real-time tuning changes also retune the notes that are sounding.
+    pub fn process_sysex(&mut self, data: &[u8]) {
+        let Some(message) = SysexMessage::parse(data) else {
+            return;
+        };
+
+        match message {
+            SysexMessage::SystemOn => self.system_on(),
+            SysexMessage::MasterVolume(volume) => self.device_volume = volume,
+            SysexMessage::RhythmPart { channel, drums } => {
+                if let Some(channel) = self.channels.get_mut(channel as usize) {
+                    channel.set_percussion(drums);
+                }
+            }
+            SysexMessage::Tuning(message) => self.process_tuning(message),
+        }
+    }
+
+    /// Processes a MIDI message once `delay` more samples have been rendered.
+    ///
+    /// # Remarks
+    ///
+    /// Note ons start on the exact sample. Any other message takes effect from the start
+    /// of the block that contains its sample, so it may come up to a block early.
+    ///
+    /// Rendering works a block ahead, so a message due within a block that [`Synthesizer::render`]
+    /// has already started is late, and takes effect from the start of the next block.
+    /// Schedule messages before rendering past them for sample-accurate timing.
+    pub fn schedule_midi_message(&mut self, delay: usize, status: u8, data1: u8, data2: u8) {
+        if let Some((channel, message)) = VoiceMessage::decode(status, data1, data2) {
+            self.schedule_voice_message(delay, channel, message);
+        }
+    }
+
+    /// Processes a decoded channel voice message once `delay` more samples have been rendered.
+    ///
+    /// See [`Synthesizer::schedule_midi_message`] for how the timing is kept.
+    pub fn schedule_voice_message(&mut self, delay: usize, channel: u8, message: VoiceMessage) {
+        self.schedule(delay, ScheduledMessage::Voice { channel, message });
+    }
+
+    /// Processes a system exclusive message once `delay` more samples have been rendered.
+    ///
+    /// See [`Synthesizer::schedule_midi_message`] for how the timing is kept.
+    pub fn schedule_sysex(&mut self, delay: usize, data: &[u8]) {
+        self.schedule(delay, ScheduledMessage::Sysex(data.to_vec()));
+    }
+
+    fn schedule(&mut self, delay: usize, message: ScheduledMessage) {
+        let due = self.position + delay as u64;
+        // messages due at the same sample keep the order they were scheduled in
+        let index = self.scheduled.partition_point(|(at, _)| *at <= due);
+        self.scheduled.insert(index, (due, message));
+    }
+
+    /// Processes the scheduled messages due within the block about to be rendered.
+    fn process_scheduled(&mut self) {
+        let block_end = self.position + self.block_size as u64;
+        while self
+            .scheduled
+            .front()
+            .is_some_and(|(due, _)| *due < block_end)
+        {
+            let Some((due, message)) = self.scheduled.pop_front() else {
+                break;
+            };
+            let delay = due.saturating_sub(self.position) as usize;
+            match message {
+                ScheduledMessage::Voice { channel, message } => {
+                    self.apply_voice_message(channel, message, delay)
+                }
+                ScheduledMessage::Sysex(data) => self.process_sysex(&data),
+            }
+        }
+    }
+
+    /// Stops every note and resets every channel, as a GM, GS or XG system on message asks.
+    fn system_on(&mut self) {
+        self.voices.clear();
+        for (i, channel) in self.channels.iter_mut().enumerate() {
+            channel.set_percussion(i == Synthesizer::PERCUSSION_CHANNEL);
+            channel.reset();
+        }
+        self.device_volume = 1_f32;
+    }
+
+    fn process_tuning(&mut self, message: TuningMessage) {
+        match message {
+            TuningMessage::KeyBased {
+                bank,
+                program,
+                realtime,
+                pitches,
+            } => {
+                let tuning = self.tunings.entry((bank, program)).or_default();
+                for (key, pitch) in pitches {
+                    tuning.set(key, pitch);
+                }
+                if realtime {
+                    self.retune(|_, channel| channel.get_tuning_program() == Some((bank, program)));
+                }
+            }
+            TuningMessage::ScaleOctave {
+                channels,
+                realtime,
+                offsets,
+            } => {
+                let applies = |channel: usize| channels & (1 << channel) != 0;
+                for (i, channel) in self.channels.iter_mut().enumerate() {
+                    if applies(i) {
+                        channel.set_scale_tuning(offsets);
+                    }
+                }
+                if realtime {
+                    self.retune(|i, _| applies(i));
+                }
+            }
+        }
+    }
+
+    /// How far a key is tuned from equal temperament on a channel, in semitones.
+    fn get_key_tuning(&self, channel: u8, key: u8) -> f32 {
+        let channel_info = &self.channels[channel as usize];
+        let key_based = channel_info
+            .get_tuning_program()
+            .and_then(|program| self.tunings.get(&program))
+            .map_or(0_f32, |tuning| tuning.get(key));
+        key_based + channel_info.get_scale_tuning(key)
+    }
+
+    /// Updates the tuning of the sounding voices on the channels that match.
+    fn retune(&mut self, matches: impl Fn(usize, &Channel) -> bool) {
+        let channels: Vec<bool> = self
+            .channels
+            .iter()
+            .enumerate()
+            .map(|(i, channel)| matches(i, channel))
+            .collect();
+        for i in 0..self.voices.len() {
+            let voice = &self.voices[i];
+            if channels[voice.channel as usize] {
+                self.voices[i].tuning = self.get_key_tuning(voice.channel, voice.key);
+            }
+        }
+    }
+
+    /// Resets the synthesizer, dropping any scheduled messages.
     pub fn reset(&mut self) {
         self.voices.clear();
+        self.scheduled.clear();
 
-        for channel in &mut self.channels {
+        for (i, channel) in self.channels.iter_mut().enumerate() {
+            channel.set_percussion(i == Synthesizer::PERCUSSION_CHANNEL);
             channel.reset();
         }
+        self.device_volume = 1_f32;
 
-        if let Some(effects) = self.effects.as_mut() {
-            effects.reverb.mute();
-            effects.chorus.mute();
+        self.effects.reset();
+        for effects in self.bus_effects() {
+            effects.reset();
+        }
+        for effect in self.master_effects.iter_mut() {
+            effect.reset();
         }
 
         self.block_read = self.block_size;
@@ -379,136 +766,188 @@ impl Synthesizer {
             panic!("The output buffers for the left and right must be the same length.");
         }
 
-        let left_length = left.len();
-
         let mut wrote = 0;
-        while wrote < left_length {
-            if self.block_read == self.block_size {
-                self.render_block();
-                self.block_read = 0;
-            }
+        while wrote < left.len() {
+            let read = self.read_block(left.len() - wrote);
+            let written = wrote..wrote + read.len();
+            left[written.clone()].copy_from_slice(&self.block_left[read.clone()]);
+            right[written].copy_from_slice(&self.block_right[read.clone()]);
+            wrote += read.len();
+        }
+    }
 
-            let src_rem = self.block_size - self.block_read;
-            let dst_rem = left_length - wrote;
-            let rem = cmp::min(src_rem, dst_rem);
+    /// Renders the waveform of each bus of the [bus layout](Synthesizer::set_bus_layout)
+    /// to its own stereo buffers. Together, the buses add up to what [`Synthesizer::render`]
+    /// would output. Without a layout, there is a single bus with the whole mix.
+    ///
+    /// Rendering carries on from where either method left off, so the two can be mixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The buffer of the left channel of each output.
+    /// * `right` - The buffer of the right channel of each output.
+    ///
+    /// # Panics
+    ///
+    /// If there isn't a buffer for each output ([`BusLayout::get_output_count`]),
+    /// or the buffers aren't all the same length.
+    pub fn render_buses(&mut self, left: &mut [&mut [f32]], right: &mut [&mut [f32]]) {
+        let output_count = self.get_bus_output_count();
+        if left.len() != output_count || right.len() != output_count {
+            panic!("There must be a pair of output buffers for each bus.");
+        }
+        let length = left.first().map_or(0, |left| left.len());
+        if left
+            .iter()
+            .chain(right.iter())
+            .any(|buffer| buffer.len() != length)
+        {
+            panic!("The output buffers for every bus must be the same length.");
+        }
 
-            for t in 0..rem {
-                left[wrote + t] = self.block_left[self.block_read + t];
-                right[wrote + t] = self.block_right[self.block_read + t];
+        let mut wrote = 0;
+        while wrote < length {
+            let read = self.read_block(length - wrote);
+            let written = wrote..wrote + read.len();
+            for (bus, (left, right)) in left.iter_mut().zip(right.iter_mut()).enumerate() {
+                let (block_left, block_right) = match self.buses.as_ref() {
+                    Some(buses) => (&buses.outputs[bus].left, &buses.outputs[bus].right),
+                    None => (&self.block_left, &self.block_right),
+                };
+                left[written.clone()].copy_from_slice(&block_left[read.clone()]);
+                right[written.clone()].copy_from_slice(&block_right[read.clone()]);
             }
+            wrote += read.len();
+        }
+    }
 
-            self.block_read += rem;
-            wrote += rem;
+    /// Renders a new block once the last one has been read,
+    /// and reads up to `length` more samples of it.
+    fn read_block(&mut self, length: usize) -> Range<usize> {
+        if self.block_read == self.block_size {
+            self.process_scheduled();
+            self.render_block();
+            self.block_read = 0;
         }
+
+        let start = self.block_read;
+        let read = cmp::min(self.block_size - start, length);
+        self.block_read += read;
+        self.position += read as u64;
+        start..start + read
     }
 
     fn render_block(&mut self) {
+        let master_volume = self.master_volume * self.device_volume;
+
         // the idea here is that if the voice cannot process, drop it.
         // A voice will not be able to process if it's been killed and is ready for release.
         self.voices
             .retain_mut(|voice| voice.process(&self.sound_font.wave_data, &self.channels));
 
-        self.block_left.fill(0_f32);
-        self.block_right.fill(0_f32);
-
-        for voice in self.voices.iter_mut() {
-            let previous_gain_left = self.master_volume * voice.previous_mix_gain_left;
-            let current_gain_left = self.master_volume * voice.current_mix_gain_left;
-            Synthesizer::write_block(
-                previous_gain_left,
-                current_gain_left,
-                &voice.block,
+        let Some(buses) = self.buses.as_mut() else {
+            self.block_left.fill(0_f32);
+            self.block_right.fill(0_f32);
+            Synthesizer::write_voices(
+                self.voices.iter(),
+                master_volume,
                 &mut self.block_left,
+                &mut self.block_right,
                 self.inverse_block_size,
             );
-            let previous_gain_right = self.master_volume * voice.previous_mix_gain_right;
-            let current_gain_right = self.master_volume * voice.current_mix_gain_right;
-            Synthesizer::write_block(
-                previous_gain_right,
-                current_gain_right,
-                &voice.block,
+            self.effects.process(
+                self.voices.iter(),
+                master_volume,
+                &mut self.block_left,
                 &mut self.block_right,
                 self.inverse_block_size,
             );
-        }
+            self.process_master_effects();
+            return;
+        };
 
-        if let Some(effects) = self.effects.as_mut() {
-            let chorus = &mut effects.chorus;
-            let chorus_input_left = &mut effects.chorus_input_left[..];
-            let chorus_input_right = &mut effects.chorus_input_right[..];
-            let chorus_output_left = &mut effects.chorus_output_left[..];
-            let chorus_output_right = &mut effects.chorus_output_right[..];
-            chorus_input_left.fill(0_f32);
-            chorus_input_right.fill(0_f32);
-            for voice in self.voices.iter_mut() {
-                let previous_gain_left = voice.previous_chorus_send * voice.previous_mix_gain_left;
-                let current_gain_left = voice.current_chorus_send * voice.current_mix_gain_left;
-                Synthesizer::write_block(
-                    previous_gain_left,
-                    current_gain_left,
-                    &voice.block[..],
-                    chorus_input_left,
-                    self.inverse_block_size,
-                );
-                let previous_gain_right =
-                    voice.previous_chorus_send * voice.previous_mix_gain_right;
-                let current_gain_right = voice.current_chorus_send * voice.current_mix_gain_right;
-                Synthesizer::write_block(
-                    previous_gain_right,
-                    current_gain_right,
-                    &voice.block[..],
-                    chorus_input_right,
+        let layout = buses.layout;
+        let bus_count = layout.get_bus_count();
+        for (bus, output) in buses.outputs.iter_mut().enumerate() {
+            output.left.fill(0_f32);
+            output.right.fill(0_f32);
+
+            if bus == bus_count {
+                // the bus of the shared effects
+                self.effects.process(
+                    self.voices.iter(),
+                    master_volume,
+                    &mut output.left,
+                    &mut output.right,
                     self.inverse_block_size,
                 );
+                continue;
             }
-            chorus.process(
-                chorus_input_left,
-                chorus_input_right,
-                chorus_output_left,
-                chorus_output_right,
-            );
-            ArrayMath::multiply_add(
-                self.master_volume,
-                chorus_output_left,
-                &mut self.block_left[..],
-            );
-            ArrayMath::multiply_add(
-                self.master_volume,
-                chorus_output_right,
-                &mut self.block_right[..],
-            );
 
-            let reverb = &mut effects.reverb;
-            let reverb_input = &mut effects.reverb_input[..];
-            let reverb_output_left = &mut effects.reverb_output_left[..];
-            let reverb_output_right = &mut effects.reverb_output_right[..];
-            reverb_input.fill(0_f32);
-            for voice in self.voices.iter_mut() {
-                let previous_gain = reverb.get_input_gain()
-                    * voice.previous_reverb_send
-                    * (voice.previous_mix_gain_left + voice.previous_mix_gain_right);
-                let current_gain = reverb.get_input_gain()
-                    * voice.current_reverb_send
-                    * (voice.current_mix_gain_left + voice.current_mix_gain_right);
-                Synthesizer::write_block(
-                    previous_gain,
-                    current_gain,
-                    &voice.block[..],
-                    &mut reverb_input[..],
+            let voices = self
+                .voices
+                .iter()
+                .filter(|voice| layout.get_bus(voice.channel) == bus);
+            Synthesizer::write_voices(
+                voices.clone(),
+                master_volume,
+                &mut output.left,
+                &mut output.right,
+                self.inverse_block_size,
+            );
+            if let Some(effects) = output.effects.as_mut() {
+                effects.process(
+                    voices,
+                    master_volume,
+                    &mut output.left,
+                    &mut output.right,
                     self.inverse_block_size,
                 );
             }
+        }
+
+        // the main mix is the sum of the buses
+        self.block_left.fill(0_f32);
+        self.block_right.fill(0_f32);
+        for output in buses.outputs.iter() {
+            ArrayMath::multiply_add(1_f32, &output.left, &mut self.block_left);
+            ArrayMath::multiply_add(1_f32, &output.right, &mut self.block_right);
+        }
+        self.process_master_effects();
+    }
 
-            reverb.process(reverb_input, reverb_output_left, reverb_output_right);
-            ArrayMath::multiply_add(
-                self.master_volume,
-                reverb_output_left,
-                &mut self.block_left[..],
+    fn process_master_effects(&mut self) {
+        for effect in self.master_effects.iter_mut() {
+            effect.process(&mut self.block_left, &mut self.block_right);
+        }
+    }
+
+    /// Adds the dry signal of the voices to a block.
+    fn write_voices<'a>(
+        voices: impl Iterator<Item = &'a Voice>,
+        master_volume: f32,
+        left: &mut [f32],
+        right: &mut [f32],
+        inverse_block_size: f32,
+    ) {
+        for voice in voices {
+            let previous_gain_left = master_volume * voice.previous_mix_gain_left;
+            let current_gain_left = master_volume * voice.current_mix_gain_left;
+            Synthesizer::write_block(
+                previous_gain_left,
+                current_gain_left,
+                &voice.block,
+                left,
+                inverse_block_size,
             );
-            ArrayMath::multiply_add(
-                self.master_volume,
-                reverb_output_right,
-                &mut self.block_right[..],
+            let previous_gain_right = master_volume * voice.previous_mix_gain_right;
+            let current_gain_right = master_volume * voice.current_mix_gain_right;
+            Synthesizer::write_block(
+                previous_gain_right,
+                current_gain_right,
+                &voice.block,
+                right,
+                inverse_block_size,
             );
         }
     }
@@ -552,9 +991,158 @@ impl Synthesizer {
         self.maximum_polyphony
     }
 
-    /// Gets the value indicating whether reverb and chorus are enabled.
+    /// Gets the number of voices that are sounding.
+    pub fn get_active_voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// Gets which voice stops when the polyphony is used up.
+    pub fn get_voice_stealing(&self) -> VoiceStealing {
+        self.voice_stealing
+    }
+
+    /// Sets which voice stops when the polyphony is used up.
+    pub fn set_voice_stealing(&mut self, value: VoiceStealing) {
+        self.voice_stealing = value;
+    }
+
+    /// Gets the number of voices stopped early to make room for new notes.
+    pub fn get_stolen_voice_count(&self) -> u64 {
+        self.stolen_voice_count
+    }
+
+    /// Gets the most notes a channel can sound at once, if it has a limit of its own.
+    pub fn get_channel_polyphony(&self, channel: u8) -> Option<usize> {
+        self.channels
+            .get(channel as usize)
+            .and_then(|channel| channel.get_polyphony())
+    }
+
+    /// Limits the notes a channel can sound at once, on top of the maximum polyphony.
+    /// Each note counts once, however many layered voices it plays.
+    /// A limit of 0 is treated as 1. The limit is kept when the synthesizer is reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel, from 0 to 15.
+    /// * `polyphony` - The limit, or `None` to only use the maximum polyphony.
+    pub fn set_channel_polyphony(&mut self, channel: u8, polyphony: Option<usize>) {
+        if let Some(channel) = self.channels.get_mut(channel as usize) {
+            channel.set_polyphony(polyphony);
+        }
+    }
+
+    /// Gets the priority of a channel.
+    pub fn get_channel_priority(&self, channel: u8) -> u8 {
+        self.channels
+            .get(channel as usize)
+            .map_or(0, |channel| channel.get_priority())
+    }
+
+    /// Sets the priority of a channel. With [`VoiceStealing::LowestPriorityChannel`],
+    /// voices are stolen from the channels with the lowest priority first.
+    /// Every channel starts at 0, and the priority is kept when the synthesizer is reset.
+    pub fn set_channel_priority(&mut self, channel: u8, priority: u8) {
+        if let Some(channel) = self.channels.get_mut(channel as usize) {
+            channel.set_priority(priority);
+        }
+    }
+
+    /// Gets the bus layout used by [`Synthesizer::render_buses`].
+    pub fn get_bus_layout(&self) -> Option<BusLayout> {
+        self.buses.as_ref().map(|buses| buses.layout)
+    }
+
+    /// Sets the bus layout used by [`Synthesizer::render_buses`], or `None` to render a
+    /// single bus. Per-bus effects start out silent, while the shared effects carry on.
+    pub fn set_bus_layout(&mut self, layout: Option<BusLayout>) {
+        let (sample_rate, block_size) = (self.sample_rate, self.block_size);
+        let (reverb, chorus) = (self.reverb_parameters, self.chorus_parameters);
+        self.buses = layout.map(|layout| {
+            Buses::new(layout, block_size, || {
+                Effects::new(sample_rate, block_size, reverb, chorus)
+            })
+        });
+    }
+
+    /// Gets the number of stereo outputs of [`Synthesizer::render_buses`].
+    pub fn get_bus_output_count(&self) -> usize {
+        self.get_bus_layout()
+            .map_or(1, |layout| layout.get_output_count())
+    }
+
+    /// The effects of each bus, with [`BusEffects::PerBus`].
+    fn bus_effects(&mut self) -> impl Iterator<Item = &mut Effects> {
+        self.buses
+            .iter_mut()
+            .flat_map(|buses| buses.outputs.iter_mut())
+            .filter_map(|output| output.effects.as_mut())
+    }
+
+    /// Gets the value indicating whether the built-in reverb or chorus is enabled.
     pub fn get_enable_reverb_and_chorus(&self) -> bool {
-        self.effects.is_some()
+        self.reverb_parameters.is_some() || self.chorus_parameters.is_some()
+    }
+
+    /// Gets the parameters of the built-in reverb, or `None` if it is turned off.
+    pub fn get_reverb_parameters(&self) -> Option<ReverbParameters> {
+        self.reverb_parameters
+    }
+
+    /// Sets the parameters of the built-in reverb, or turns it off with `None`.
+    /// Changing the parameters keeps the tail of the reverb.
+    pub fn set_reverb_parameters(&mut self, parameters: Option<ReverbParameters>) {
+        let parameters = parameters.map(|parameters| parameters.clamped());
+        self.reverb_parameters = parameters;
+        self.effects.set_reverb(parameters);
+        for effects in self.bus_effects() {
+            effects.set_reverb(parameters);
+        }
+    }
+
+    /// Gets the parameters of the built-in chorus, or `None` if it is turned off.
+    pub fn get_chorus_parameters(&self) -> Option<ChorusParameters> {
+        self.chorus_parameters
+    }
+
+    /// Sets the parameters of the built-in chorus, or turns it off with `None`.
+    /// The chorus restarts, dropping what it holds.
+    pub fn set_chorus_parameters(&mut self, parameters: Option<ChorusParameters>) {
+        let parameters = parameters.map(|parameters| parameters.clamped());
+        self.chorus_parameters = parameters;
+        self.effects.set_chorus(parameters);
+        for effects in self.bus_effects() {
+            effects.set_chorus(parameters);
+        }
+    }
+
+    /// Adds an effect to the end of a bus.
+    ///
+    /// Effects on the reverb and chorus sends run even when the built-in effect is turned off,
+    /// so they can replace it. With [`BusEffects::PerBus`], the buses only have the built-in
+    /// effects. Master effects run on what [`Synthesizer::render`] outputs, and not on the
+    /// buses of [`Synthesizer::render_buses`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bus` - Where the effect is inserted.
+    /// * `effect` - The effect.
+    pub fn add_effect(&mut self, bus: EffectBus, effect: impl AudioEffect + 'static) {
+        let effect: Box<dyn AudioEffect> = Box::new(effect);
+        match bus {
+            EffectBus::Master => self.master_effects.push(effect),
+            EffectBus::Reverb => self.effects.reverb_effects.push(effect),
+            EffectBus::Chorus => self.effects.chorus_effects.push(effect),
+        }
+    }
+
+    /// Removes the effects added to a bus. The built-in reverb and chorus are kept.
+    pub fn clear_effects(&mut self, bus: EffectBus) {
+        match bus {
+            EffectBus::Master => self.master_effects.clear(),
+            EffectBus::Reverb => self.effects.reverb_effects.clear(),
+            EffectBus::Chorus => self.effects.chorus_effects.clear(),
+        }
     }
 
     /// Gets the master volume.
@@ -571,32 +1159,3 @@ impl Synthesizer {
         self.master_volume = value;
     }
 }
-
-struct Effects {
-    reverb: Reverb,
-    reverb_input: Vec<f32>,
-    reverb_output_left: Vec<f32>,
-    reverb_output_right: Vec<f32>,
-
-    chorus: Chorus,
-    chorus_input_left: Vec<f32>,
-    chorus_input_right: Vec<f32>,
-    chorus_output_left: Vec<f32>,
-    chorus_output_right: Vec<f32>,
-}
-
-impl Effects {
-    fn new(settings: &SynthesizerSettings) -> Effects {
-        Self {
-            reverb: Reverb::new(settings.sample_rate),
-            reverb_input: vec![0_f32; settings.block_size],
-            reverb_output_left: vec![0_f32; settings.block_size],
-            reverb_output_right: vec![0_f32; settings.block_size],
-            chorus: Chorus::new(settings.sample_rate, 0.002, 0.0019, 0.4),
-            chorus_input_left: vec![0_f32; settings.block_size],
-            chorus_input_right: vec![0_f32; settings.block_size],
-            chorus_output_left: vec![0_f32; settings.block_size],
-            chorus_output_right: vec![0_f32; settings.block_size],
-        }
-    }
-}
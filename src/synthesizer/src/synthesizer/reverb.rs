@@ -1,4 +1,7 @@
-use bevy_platform::prelude::*;
+use std::cmp;
+
+use super::ReverbParameters;
+
 pub(crate) struct Reverb {
     left_combfilters: Vec<CombFilter>,
     left_allpassfilters: Vec<AllPassFilter>,
@@ -23,10 +26,10 @@ impl Reverb {
     const SCALE_DAMP: f32 = 0.4;
     const SCALE_ROOM: f32 = 0.28;
     const OFFSET_ROOM: f32 = 0.7;
-    const INITIAL_ROOM: f32 = 0.5;
-    const INITIAL_DAMP: f32 = 0.5;
-    const INITIAL_WET: f32 = 1.0 / Reverb::SCALE_WET;
-    const INITIAL_WIDTH: f32 = 1.0;
+    pub(super) const INITIAL_ROOM: f32 = 0.5;
+    pub(super) const INITIAL_DAMP: f32 = 0.5;
+    pub(super) const INITIAL_WET: f32 = 1.0 / Reverb::SCALE_WET;
+    pub(super) const INITIAL_WIDTH: f32 = 1.0;
     const STEREO_SPREAD: usize = 23;
 
     const CF_TUNING_L1: usize = 1116;
@@ -179,7 +182,7 @@ impl Reverb {
         }
 
         // With the default settings, we can skip this part.
-        if 1_f32 - self.wet1 > 1.0E-3_f32 || self.wet2 > 1.0E-3_f32 {
+        if (1_f32 - self.wet1).abs() > 1.0E-3_f32 || self.wet2 > 1.0E-3_f32 {
             for t in 0..input_length {
                 let left = output_left[t];
                 let right = output_right[t];
@@ -208,6 +211,13 @@ impl Reverb {
         }
     }
 
+    pub(crate) fn set_parameters(&mut self, parameters: &ReverbParameters) {
+        self.set_wet(parameters.wet);
+        self.set_room_size(parameters.room_size);
+        self.set_damp(parameters.damping);
+        self.set_width(parameters.width);
+    }
+
     pub fn get_input_gain(&self) -> f32 {
         self.gain
     }
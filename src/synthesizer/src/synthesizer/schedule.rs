@@ -0,0 +1,7 @@
+use super::VoiceMessage;
+
+/// A message waiting in the synthesizer's schedule for the sample it's due at.
+pub(crate) enum ScheduledMessage {
+    Voice { channel: u8, message: VoiceMessage },
+    Sysex(Vec<u8>),
+}
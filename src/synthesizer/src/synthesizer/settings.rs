@@ -11,6 +11,12 @@ pub struct SynthesizerSettings {
     pub maximum_polyphony: usize,
     /// The value indicating whether reverb and chorus are enabled.
     pub enable_reverb_and_chorus: bool,
+    /// The parameters of the reverb, when it is enabled.
+    pub reverb: ReverbParameters,
+    /// The parameters of the chorus, when it is enabled.
+    pub chorus: ChorusParameters,
+    /// Which voice stops when the polyphony is used up.
+    pub voice_stealing: VoiceStealing,
 }
 
 impl SynthesizerSettings {
@@ -29,6 +35,9 @@ impl SynthesizerSettings {
             block_size: SynthesizerSettings::DEFAULT_BLOCK_SIZE,
             maximum_polyphony: SynthesizerSettings::DEFAULT_MAXIMUM_POLYPHONY,
             enable_reverb_and_chorus: SynthesizerSettings::DEFAULT_ENABLE_REVERB_AND_CHORUS,
+            reverb: ReverbParameters::default(),
+            chorus: ChorusParameters::default(),
+            voice_stealing: VoiceStealing::default(),
         }
     }
 
@@ -0,0 +1,77 @@
+use super::TuningMessage;
+
+/// A system exclusive message the synthesizer applies.
+pub(crate) enum SysexMessage {
+    /// GM System On, GM2 System On, GS Reset or XG System On.
+    /// Every channel goes back to its defaults.
+    SystemOn,
+    /// The master volume, as a gain from 0 to 1.
+    MasterVolume(f32),
+    /// GS "use for rhythm part" or XG part mode, which switches a channel between
+    /// melodic instruments and drum kits.
+    RhythmPart {
+        channel: u8,
+        drums: bool,
+    },
+    Tuning(TuningMessage),
+}
+
+impl SysexMessage {
+    /// Reads a sysex message, with or without its `0xF0` and `0xF7` bytes.
+    /// Returns `None` for messages the synthesizer ignores.
+    /// Device IDs and Roland checksums are not checked.
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        let data = data.strip_prefix(&[0xF0]).unwrap_or(data);
+        let data = data.strip_suffix(&[0xF7]).unwrap_or(data);
+
+        match data {
+            // Universal non-real-time: GM and GM2 System On
+            [0x7E, _, 0x09, 0x01 | 0x03, ..] => Some(Self::SystemOn),
+            // Universal real-time: master volume
+            [0x7F, _, 0x04, 0x01, lsb, msb, ..] => {
+                let volume = ((*msb as u16 & 0x7F) << 7) | (*lsb as u16 & 0x7F);
+                Some(Self::MasterVolume(volume as f32 / 16383_f32))
+            }
+            [0x7E | 0x7F, _, 0x08, ..] => TuningMessage::parse(data).map(Self::Tuning),
+            // Roland GS data set: address, data, checksum
+            [0x41, _, 0x42, 0x12, address @ .., value, _checksum] => {
+                match address {
+                    // GS Reset, and the SC-88 system mode set
+                    [0x40, 0x00, 0x7F] | [0x00, 0x00, 0x7F] => Some(Self::SystemOn),
+                    [0x40, 0x00, 0x04] => Some(Self::MasterVolume(volume(*value))),
+                    [0x40, part @ 0x10..=0x1F, 0x15] => Some(Self::RhythmPart {
+                        channel: gs_part_channel(part & 0x0F),
+                        drums: *value != 0,
+                    }),
+                    _ => None,
+                }
+            }
+            // Yamaha XG parameter change: address, data
+            [0x43, device, 0x4C, address @ .., value] if device & 0xF0 == 0x10 => match address {
+                // XG System On, and XG all parameter reset
+                [0x00, 0x00, 0x7E | 0x7F] => Some(Self::SystemOn),
+                [0x00, 0x00, 0x04] => Some(Self::MasterVolume(volume(*value))),
+                [0x08, part @ 0x00..=0x0F, 0x07] => Some(Self::RhythmPart {
+                    channel: *part,
+                    drums: *value != 0,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A 7-bit master volume as a gain.
+fn volume(value: u8) -> f32 {
+    (value & 0x7F) as f32 / 127_f32
+}
+
+/// GS numbers its parts from the drum part: block 0 is part 10, then parts 1 to 9 and 11 to 16.
+fn gs_part_channel(block: u8) -> u8 {
+    match block {
+        0 => 9,
+        1..=9 => block - 1,
+        _ => block,
+    }
+}
@@ -0,0 +1,125 @@
+/// A key-based tuning program: how far each key is from equal temperament, in semitones.
+#[derive(Clone, Copy)]
+pub(crate) struct Tuning([f32; 128]);
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self([0_f32; 128])
+    }
+}
+
+impl Tuning {
+    pub(crate) fn get(&self, key: u8) -> f32 {
+        self.0[key as usize & 0x7F]
+    }
+
+    pub(crate) fn set(&mut self, key: u8, pitch: f32) {
+        self.0[key as usize & 0x7F] = pitch - key as f32;
+    }
+}
+
+/// A MIDI Tuning Standard message the synthesizer applies.
+pub(crate) enum TuningMessage {
+    /// Retunes keys of a tuning program to new pitches, in semitones.
+    /// Bulk dumps skip the keys they leave unchanged.
+    KeyBased {
+        bank: u8,
+        program: u8,
+        realtime: bool,
+        pitches: Vec<(u8, f32)>,
+    },
+    /// Offsets the semitones of the octave on the channels in the mask, in semitones.
+    ScaleOctave {
+        channels: u16,
+        realtime: bool,
+        offsets: [f32; 12],
+    },
+}
+
+impl TuningMessage {
+    /// Reads a sysex message without its `0xF0` and `0xF7` bytes.
+    /// Returns `None` for anything but MTS bulk dumps, single note tuning changes and
+    /// scale/octave tunings. Device IDs and checksums are ignored.
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        let [universal @ (0x7E | 0x7F), _device, 0x08, format, rest @ ..] = data else {
+            return None;
+        };
+        let realtime = *universal == 0x7F;
+
+        match (realtime, format) {
+            (false, 0x01) => Self::bulk_dump(0, rest),
+            (false, 0x04) => Self::bulk_dump(*rest.first()?, rest.get(1..)?),
+            (true, 0x02) => Self::single_note_change(0, true, rest),
+            (_, 0x07) => Self::single_note_change(*rest.first()?, realtime, rest.get(1..)?),
+            (_, 0x08 | 0x09) => {
+                let channels = ((rest.first()? & 0x03) as u16) << 14
+                    | ((rest.get(1)? & 0x7F) as u16) << 7
+                    | (rest.get(2)? & 0x7F) as u16;
+                let offsets = rest.get(3..)?;
+                let offsets: [f32; 12] = if *format == 0x08 {
+                    let offsets = offsets.get(..12)?;
+                    std::array::from_fn(|i| 0.01_f32 * (offsets[i] as f32 - 64_f32))
+                } else {
+                    let offsets = offsets.get(..24)?;
+                    std::array::from_fn(|i| {
+                        let value = ((offsets[2 * i] as i32) << 7) | offsets[2 * i + 1] as i32;
+                        (value - 8192) as f32 / 8192_f32
+                    })
+                };
+                Some(Self::ScaleOctave {
+                    channels,
+                    realtime,
+                    offsets,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn bulk_dump(bank: u8, data: &[u8]) -> Option<Self> {
+        let (program, data) = data.split_first()?;
+        // skip the name
+        let keys = data.get(16..16 + 3 * 128)?;
+        let pitches = keys
+            .chunks_exact(3)
+            .enumerate()
+            .filter_map(|(key, bytes)| Some((key as u8, pitch(bytes)?)))
+            .collect();
+        Some(Self::KeyBased {
+            bank,
+            program: *program,
+            realtime: false,
+            pitches,
+        })
+    }
+
+    fn single_note_change(bank: u8, realtime: bool, data: &[u8]) -> Option<Self> {
+        let [program, count, changes @ ..] = data else {
+            return None;
+        };
+        let pitches = changes
+            .chunks_exact(4)
+            .take(*count as usize)
+            .filter_map(|change| Some((change[0] & 0x7F, pitch(&change[1..])?)))
+            .collect();
+        Some(Self::KeyBased {
+            bank,
+            program: *program,
+            realtime,
+            pitches,
+        })
+    }
+}
+
+/// Reads the MTS frequency format: a semitone and a 14-bit fraction of one.
+/// `7F 7F 7F` leaves the key unchanged.
+fn pitch(bytes: &[u8]) -> Option<f32> {
+    match bytes {
+        [0x7F, 0x7F, 0x7F] => None,
+        [semitone, msb, lsb] => {
+            let fraction = ((*msb as i32 & 0x7F) << 7) | (*lsb as i32 & 0x7F);
+            Some((*semitone & 0x7F) as f32 + fraction as f32 / 16384_f32)
+        }
+        _ => None,
+    }
+}
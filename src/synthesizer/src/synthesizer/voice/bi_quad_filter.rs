@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use bevy_platform::prelude::*;
+use std::f32::consts;
 
 pub(crate) struct BiQuadFilter {
     sample_rate: i32,
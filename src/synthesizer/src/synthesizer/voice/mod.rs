@@ -1,4 +1,4 @@
-use bevy_platform::prelude::*;
+use std::f32::consts;
 mod envelope;
 use envelope::*;
 mod region;
@@ -10,11 +10,15 @@ use oscillator::*;
 mod bi_quad_filter;
 use bi_quad_filter::*;
 
+mod modulators;
+use modulators::*;
+
 use crate::{prelude::*, utils};
 
 use super::Channel;
 
 pub(crate) struct Voice {
+    sample_rate: i32,
     block_size: usize,
 
     vol_env: VolumeEnvelope,
@@ -45,6 +49,21 @@ pub(crate) struct Voice {
     pub(crate) exclusive_class: i32,
     pub(crate) channel: u8,
     pub(crate) key: u8,
+    velocity: u8,
+    // The keys covered by the regions of the voice
+    key_range: (u8, u8),
+    // Latched by the sostenuto pedal
+    pub(crate) sostenuto: bool,
+    // The MTS tuning of the key, in semitones from equal temperament
+    pub(crate) tuning: f32,
+    // The samples of silence before the voice starts, within its first block
+    pub(crate) delay: usize,
+    // The note that started the voice, shared by the voices of each region it plays
+    pub(crate) note: u64,
+
+    // The portamento offset from the key in semitones, and how much it shrinks each block.
+    glide: f32,
+    glide_rate: f32,
 
     note_gain: f32,
 
@@ -70,24 +89,57 @@ pub(crate) struct Voice {
     // This is used to smooth out the cutoff frequency.
     smoothed_cutoff: f32,
 
+    modulators: VoiceModulators,
+    // The current output of the modulators for each generator.
+    // Only the realtime destinations are applied here, the others are fixed at the start of the voice.
+    modulation: [f32; GeneratorType::COUNT],
+    // The cutoff offset in cents that the filter was last set with.
+    cutoff_cents: f32,
+
     voice_state: VoiceState,
     pub(crate) voice_length: usize,
     min_voice_length: usize,
 }
 
 impl Voice {
+    const SOFT_PEDAL_ATTENUATION: f32 = -3_f32;
+    const SOFT_PEDAL_CUTOFF: f32 = -1200_f32;
+
     pub(crate) fn new(
         settings: &SynthesizerSettings,
         region: &RegionPair,
+        channel_info: &Channel,
         channel: u8,
         key: u8,
         velocity: u8,
     ) -> Self {
+        let modulators = VoiceModulators::new(region);
+        let modulation = modulators.evaluate(channel_info, key, velocity);
+
+        let mut offsets = [0; GeneratorType::COUNT];
+        for (destination, (offset, value)) in offsets.iter_mut().zip(modulation).enumerate() {
+            if !VoiceModulators::is_realtime(destination as u16) {
+                *offset = value.round() as i32;
+            }
+        }
+        let region = &region.modulated(offsets);
+
         // this is used elsewhere...really thinking we should
         // just use the region
         let exclusive_class = region.get_exclusive_class();
 
-        let note_gain = if velocity > 0 {
+        let key_range = (
+            region
+                .preset
+                .get_key_range_start()
+                .max(region.instrument.get_key_range_start()),
+            region
+                .preset
+                .get_key_range_end()
+                .min(region.instrument.get_key_range_end()),
+        );
+
+        let mut note_gain = if velocity > 0 {
             // According to the Polyphone's implementation, the initial attenuation should be reduced to 40%.
             // I'm not sure why, but this indeed improves the loudness variability.
             let sample_attenuation = 0.4_f32 * region.get_initial_attenuation();
@@ -100,8 +152,16 @@ impl Voice {
             0_f32
         };
 
-        let cutoff = region.get_initial_filter_cutoff_frequency();
+        let mut cutoff = region.get_initial_filter_cutoff_frequency();
+
+        // The soft pedal attenuates and darkens the notes played while it's down.
+        if channel_info.get_soft_pedal() {
+            note_gain *= utils::decibels_to_linear(Voice::SOFT_PEDAL_ATTENUATION);
+            cutoff *= utils::cents_to_multiplying_factor(Voice::SOFT_PEDAL_CUTOFF);
+        }
+
         let resonance = utils::decibels_to_linear(region.get_initial_filter_q());
+        let cutoff_cents = modulation[GeneratorType::INITIAL_FILTER_CUTOFF_FREQUENCY as usize];
 
         let vib_lfo_to_pitch = 0.01_f32 * region.get_vibrato_lfo_to_pitch() as f32;
         let mod_lfo_to_pitch = 0.01_f32 * region.get_modulation_lfo_to_pitch() as f32;
@@ -138,9 +198,8 @@ impl Voice {
 
         let mut filter = BiQuadFilter::new(settings);
         filter.clear_buffer();
-        filter.set_low_pass_filter(cutoff, resonance);
-
-        let smoothed_cutoff = cutoff;
+        let smoothed_cutoff = cutoff * utils::cents_to_multiplying_factor(cutoff_cents);
+        filter.set_low_pass_filter(smoothed_cutoff, resonance);
 
         let voice_state = VoiceState::Playing;
         //???
@@ -149,6 +208,7 @@ impl Voice {
         //???
         let min_voice_length = (settings.sample_rate / 500) as usize;
         Self {
+            sample_rate: settings.sample_rate,
             block_size: settings.block_size,
             vol_env,
             mod_env,
@@ -168,6 +228,14 @@ impl Voice {
             exclusive_class,
             channel,
             key,
+            velocity,
+            key_range,
+            sostenuto: false,
+            tuning: 0_f32,
+            delay: 0,
+            note: 0,
+            glide: 0_f32,
+            glide_rate: 0_f32,
             note_gain,
             cutoff,
             resonance,
@@ -183,12 +251,49 @@ impl Voice {
             instrument_reverb,
             instrument_chorus,
             smoothed_cutoff,
+            modulators,
+            modulation,
+            cutoff_cents,
             voice_state,
             voice_length,
             min_voice_length,
         }
     }
 
+    /// Returns `true` until the note is ended.
+    pub(crate) fn is_held(&self) -> bool {
+        self.voice_state == VoiceState::Playing
+    }
+
+    /// Returns `true` if the regions of the voice cover a key.
+    pub(crate) fn covers(&self, key: u8) -> bool {
+        self.key_range.0 <= key && key <= self.key_range.1
+    }
+
+    /// Starts the note at another pitch in semitones, gliding to its own over `seconds`.
+    pub(crate) fn glide(&mut self, from: f32, seconds: f32) {
+        self.glide_to(from - self.key as f32 - self.tuning, seconds);
+    }
+
+    /// Moves a held note to another key and its tuning without a new attack, gliding over `seconds`.
+    pub(crate) fn legato(&mut self, key: u8, tuning: f32, seconds: f32) {
+        let offset = self.key as f32 + self.tuning + self.glide - key as f32 - tuning;
+        self.key = key;
+        self.tuning = tuning;
+        self.glide_to(offset, seconds);
+    }
+
+    fn glide_to(&mut self, offset: f32, seconds: f32) {
+        let blocks = seconds * self.sample_rate as f32 / self.block_size as f32;
+        if blocks < 1_f32 {
+            self.glide = 0_f32;
+            self.glide_rate = 0_f32;
+        } else {
+            self.glide = offset;
+            self.glide_rate = offset.abs() / blocks;
+        }
+    }
+
     pub(crate) fn end(&mut self) {
         if self.voice_state == VoiceState::Playing {
             self.voice_state = VoiceState::ReleaseRequested;
@@ -215,7 +320,7 @@ impl Voice {
     /// 3. mod env is just hanging around, so it's definitely not supposed to
     ///    return a bool
     ///
-    pub(crate) fn process(&mut self, data: &[i16], channels: &[Channel]) -> bool {
+    pub(crate) fn process(&mut self, data: &WaveData, channels: &[Channel]) -> bool {
         if self.note_gain < utils::NON_AUDIBLE {
             return false;
         }
@@ -234,18 +339,59 @@ impl Voice {
         let vib_lfo = self.vib_lfo.process();
         let mod_lfo = self.mod_lfo.process();
 
-        let vib_pitch_change =
-            (0.01_f32 * channel_info.get_modulation() + self.vib_lfo_to_pitch) * vib_lfo;
-        let mod_pitch_change = self.mod_lfo_to_pitch * mod_lfo + self.mod_env_to_pitch * mod_env;
+        if !self.modulators.is_empty() {
+            self.modulation = self
+                .modulators
+                .evaluate(channel_info, self.key, self.velocity);
+        }
+        let modulation = |destination: u16| self.modulation[destination as usize];
+
+        let vib_pitch_change = (0.01_f32 * channel_info.get_modulation()
+            + self.vib_lfo_to_pitch
+            + 0.01_f32 * modulation(GeneratorType::VIBRATO_LFO_TO_PITCH))
+            * vib_lfo;
+        let mod_pitch_change = (self.mod_lfo_to_pitch
+            + 0.01_f32 * modulation(GeneratorType::MODULATION_LFO_TO_PITCH))
+            * mod_lfo
+            + (self.mod_env_to_pitch
+                + 0.01_f32 * modulation(GeneratorType::MODULATION_ENVELOPE_TO_PITCH))
+                * mod_env;
+        let modulated_pitch_change = modulation(GeneratorType::COARSE_TUNE)
+            + 0.01_f32
+                * (modulation(GeneratorType::FINE_TUNE) + modulation(Modulator::INITIAL_PITCH));
         let channel_pitch_change = channel_info.get_tune() + channel_info.get_pitch_bend();
-        let pitch = self.key as f32 + vib_pitch_change + mod_pitch_change + channel_pitch_change;
-        if !self.oscillator.process(data, &mut self.block[..], pitch) {
+        let glide = self.glide;
+        self.glide = if glide > 0_f32 {
+            (glide - self.glide_rate).max(0_f32)
+        } else {
+            (glide + self.glide_rate).min(0_f32)
+        };
+
+        let pitch = self.key as f32
+            + self.tuning
+            + glide
+            + vib_pitch_change
+            + mod_pitch_change
+            + modulated_pitch_change
+            + channel_pitch_change;
+        let delay = std::mem::take(&mut self.delay).min(self.block_size);
+        self.block[..delay].fill(0_f32);
+        if !self
+            .oscillator
+            .process(data, &mut self.block[delay..], pitch)
+        {
             return false;
         }
 
-        if self.dynamic_cutoff {
-            let cents =
-                self.mod_lfo_to_cutoff as f32 * mod_lfo + self.mod_env_to_cutoff as f32 * mod_env;
+        let cents = (self.mod_lfo_to_cutoff as f32
+            + modulation(GeneratorType::MODULATION_LFO_TO_FILTER_CUTOFF_FREQUENCY))
+            * mod_lfo
+            + (self.mod_env_to_cutoff as f32
+                + modulation(GeneratorType::MODULATION_ENVELOPE_TO_FILTER_CUTOFF_FREQUENCY))
+                * mod_env
+            + modulation(GeneratorType::INITIAL_FILTER_CUTOFF_FREQUENCY);
+        if self.dynamic_cutoff || cents != self.cutoff_cents {
+            self.cutoff_cents = cents;
             let factor = utils::cents_to_multiplying_factor(cents);
             let new_cutoff = factor * self.cutoff;
 
@@ -270,13 +416,20 @@ impl Voice {
         let channel_gain = ve * ve;
 
         let mut mix_gain = self.note_gain * channel_gain * vol_env;
-        if self.dynamic_volume {
-            let decibels = self.mod_lfo_to_volume * mod_lfo;
+        let mod_lfo_to_volume =
+            self.mod_lfo_to_volume + 0.1_f32 * modulation(GeneratorType::MODULATION_LFO_TO_VOLUME);
+        if self.dynamic_volume || mod_lfo_to_volume != self.mod_lfo_to_volume {
+            let decibels = mod_lfo_to_volume * mod_lfo;
             mix_gain *= utils::decibels_to_linear(decibels);
         }
+        let attenuation = modulation(GeneratorType::INITIAL_ATTENUATION);
+        if attenuation != 0_f32 {
+            mix_gain *= utils::decibels_to_linear(-0.1_f32 * attenuation);
+        }
 
-        let angle =
-            (consts::PI / 200_f32) * (channel_info.get_pan() + self.instrument_pan + 50_f32);
+        let instrument_pan =
+            (self.instrument_pan + 0.1_f32 * modulation(GeneratorType::PAN)).clamp(-50_f32, 50_f32);
+        let angle = (consts::PI / 200_f32) * (channel_info.get_pan() + instrument_pan + 50_f32);
         if angle <= 0_f32 {
             self.current_mix_gain_left = mix_gain;
             self.current_mix_gain_right = 0_f32;
@@ -288,11 +441,15 @@ impl Voice {
             self.current_mix_gain_right = mix_gain * angle.sin();
         }
 
-        self.current_reverb_send =
-            (channel_info.get_reverb_send() + self.instrument_reverb).clamp(0., 1.);
+        self.current_reverb_send = (channel_info.get_reverb_send()
+            + self.instrument_reverb
+            + 0.001_f32 * modulation(GeneratorType::REVERB_EFFECTS_SEND))
+        .clamp(0., 1.);
 
-        self.current_chorus_send =
-            (channel_info.get_chorus_send() + self.instrument_chorus).clamp(0., 1.);
+        self.current_chorus_send = (channel_info.get_chorus_send()
+            + self.instrument_chorus
+            + 0.001_f32 * modulation(GeneratorType::CHORUS_EFFECTS_SEND))
+        .clamp(0., 1.);
 
         if self.voice_length == 0 {
             self.previous_mix_gain_left = self.current_mix_gain_left;
@@ -311,7 +468,10 @@ impl Voice {
             return;
         }
 
-        if self.voice_state == VoiceState::ReleaseRequested && !channel_info.get_hold_pedal() {
+        if self.voice_state == VoiceState::ReleaseRequested
+            && !channel_info.get_hold_pedal()
+            && !(self.sostenuto && channel_info.get_sostenuto())
+        {
             self.vol_env.release();
             self.mod_env.release();
             self.oscillator.release();
@@ -0,0 +1,124 @@
+use crate::prelude::*;
+
+use super::{Channel, RegionPair};
+
+/// The modulators that apply to a voice, merged from the defaults, the instrument and the preset.
+pub(crate) struct VoiceModulators {
+    /// Modulators and the sign of their contribution.
+    ///
+    /// The channel already applies the builtin default modulators, so they are left out.
+    /// When a SoundFont overrides one of them, the default is subtracted instead.
+    modulators: Vec<(Modulator, f32)>,
+}
+
+impl VoiceModulators {
+    pub(crate) fn new(region: &RegionPair) -> Self {
+        // Instrument modulators replace identical defaults.
+        let mut instrument: Vec<Modulator> = Modulator::DEFAULTS
+            .iter()
+            .map(|(m, _)| *m)
+            .chain(Modulator::PRESSURE_DEFAULTS)
+            .collect();
+        Modulator::merge(&mut instrument, &region.instrument.modulators);
+
+        let mut modulators = Vec::new();
+        for (default, builtin) in Modulator::DEFAULTS {
+            if builtin && !instrument.contains(&default) {
+                modulators.push((Self::applied_by_channel(default), -1_f32));
+            }
+        }
+        for modulator in instrument {
+            if !Modulator::DEFAULTS.contains(&(modulator, true)) {
+                modulators.push((modulator, 1_f32));
+            }
+        }
+
+        // Preset modulators add to the instrument ones.
+        for modulator in region.preset.modulators.iter() {
+            modulators.push((*modulator, 1_f32));
+        }
+
+        modulators.retain(|(m, _)| m.amount != 0 && m.is_supported());
+
+        Self { modulators }
+    }
+
+    /// A builtin default as the channel applies it.
+    /// The channel pans within half of the default's range, like FluidSynth does.
+    fn applied_by_channel(default: Modulator) -> Modulator {
+        if default.destination == GeneratorType::PAN {
+            Modulator {
+                amount: default.amount / 2,
+                ..default
+            }
+        } else {
+            default
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.modulators.is_empty()
+    }
+
+    /// Sums the modulator outputs for each destination generator.
+    pub(crate) fn evaluate(
+        &self,
+        channel: &Channel,
+        key: u8,
+        velocity: u8,
+    ) -> [f32; GeneratorType::COUNT] {
+        let mut values = [0_f32; GeneratorType::COUNT];
+
+        for (modulator, sign) in self.modulators.iter() {
+            let source = source_value(modulator.source, channel, key, velocity);
+            let amount_source = source_value(modulator.amount_source, channel, key, velocity);
+            values[modulator.destination as usize] +=
+                sign * modulator.evaluate(source, amount_source);
+        }
+
+        values
+    }
+
+    /// Returns `true` for destinations that are updated while the voice plays.
+    /// Other destinations are fixed when the voice starts.
+    pub(crate) fn is_realtime(destination: u16) -> bool {
+        matches!(
+            destination,
+            GeneratorType::MODULATION_LFO_TO_PITCH
+                | GeneratorType::VIBRATO_LFO_TO_PITCH
+                | GeneratorType::MODULATION_ENVELOPE_TO_PITCH
+                | GeneratorType::INITIAL_FILTER_CUTOFF_FREQUENCY
+                | GeneratorType::MODULATION_LFO_TO_FILTER_CUTOFF_FREQUENCY
+                | GeneratorType::MODULATION_ENVELOPE_TO_FILTER_CUTOFF_FREQUENCY
+                | GeneratorType::MODULATION_LFO_TO_VOLUME
+                | GeneratorType::CHORUS_EFFECTS_SEND
+                | GeneratorType::REVERB_EFFECTS_SEND
+                | GeneratorType::PAN
+                | GeneratorType::INITIAL_ATTENUATION
+                | GeneratorType::COARSE_TUNE
+                | GeneratorType::FINE_TUNE
+                | Modulator::INITIAL_PITCH
+        )
+    }
+}
+
+/// The value of a modulator source, normalized to `[0, 1]`.
+fn source_value(source: ModulatorSource, channel: &Channel, key: u8, velocity: u8) -> f32 {
+    if source.is_midi_controller() {
+        return (1_f32 / 127_f32) * channel.get_controller(source.index()) as f32;
+    }
+
+    match source.index() {
+        ModulatorSource::NOTE_ON_VELOCITY => (1_f32 / 127_f32) * velocity as f32,
+        ModulatorSource::NOTE_ON_KEY_NUMBER => (1_f32 / 127_f32) * key as f32,
+        ModulatorSource::POLY_PRESSURE => (1_f32 / 127_f32) * channel.get_key_pressure(key) as f32,
+        ModulatorSource::CHANNEL_PRESSURE => {
+            (1_f32 / 127_f32) * channel.get_channel_pressure() as f32
+        }
+        ModulatorSource::PITCH_WHEEL => channel.get_pitch_wheel(),
+        ModulatorSource::PITCH_WHEEL_SENSITIVITY => {
+            ((1_f32 / 127_f32) * channel.get_pitch_bend_range()).min(1_f32)
+        }
+        _ => 0_f32,
+    }
+}
@@ -7,6 +7,31 @@ use crate::prelude::*;
 // and the rest represent the integer part.
 // For clarity, fixed-point number variables have a suffix "_fp".
 
+/// A sample format the oscillator can render from.
+pub(crate) trait WaveSample: Copy {
+    /// Multiplies a fixed-point sample to get a value between -1 and 1.
+    const FP_TO_SAMPLE: f32;
+
+    fn value(self) -> i64;
+}
+
+impl WaveSample for i16 {
+    const FP_TO_SAMPLE: f32 = 1_f32 / (32768 * Oscillator::FRAC_UNIT) as f32;
+
+    fn value(self) -> i64 {
+        self as i64
+    }
+}
+
+// 24-bit samples, stored in the lower bits of an i32.
+impl WaveSample for i32 {
+    const FP_TO_SAMPLE: f32 = 1_f32 / (8388608 * Oscillator::FRAC_UNIT) as f32;
+
+    fn value(self) -> i64 {
+        self as i64
+    }
+}
+
 #[non_exhaustive]
 pub(crate) struct Oscillator {
     loop_mode: LoopMode,
@@ -27,7 +52,6 @@ pub(crate) struct Oscillator {
 impl Oscillator {
     const FRAC_BITS: i32 = 24;
     const FRAC_UNIT: i64 = 1_i64 << Oscillator::FRAC_BITS;
-    const FP_TO_SAMPLE: f32 = 1_f32 / (32768 * Oscillator::FRAC_UNIT) as f32;
 
     pub(crate) fn new(settings: &SynthesizerSettings, region: &RegionPair) -> Self {
         let sample_rate = region.instrument.sample_sample_rate;
@@ -67,13 +91,21 @@ impl Oscillator {
         }
     }
 
-    pub(crate) fn process(&mut self, data: &[i16], block: &mut [f32], pitch: f32) -> bool {
+    pub(crate) fn process(&mut self, data: &WaveData, block: &mut [f32], pitch: f32) -> bool {
         let pitch_change = self.pitch_change_scale * (pitch - self.root_key as f32) + self.tune;
         let pitch_ratio = self.sample_rate_ratio * 2_f32.powf(pitch_change / 12_f32);
-        self.fill_block(data, block, pitch_ratio as f64)
+        match data {
+            WaveData::Pcm16(data) => self.fill_block(data, block, pitch_ratio as f64),
+            WaveData::Pcm24(data) => self.fill_block(data, block, pitch_ratio as f64),
+        }
     }
 
-    fn fill_block(&mut self, data: &[i16], block: &mut [f32], pitch_ratio: f64) -> bool {
+    fn fill_block<S: WaveSample>(
+        &mut self,
+        data: &[S],
+        block: &mut [f32],
+        pitch_ratio: f64,
+    ) -> bool {
         let pitch_ratio_fp = (Oscillator::FRAC_UNIT as f64 * pitch_ratio) as i64;
 
         if self.looping {
@@ -83,7 +115,12 @@ impl Oscillator {
         }
     }
 
-    fn fill_block_no_loop(&mut self, data: &[i16], block: &mut [f32], pitch_ratio_fp: i64) -> bool {
+    fn fill_block_no_loop<S: WaveSample>(
+        &mut self,
+        data: &[S],
+        block: &mut [f32],
+        pitch_ratio_fp: i64,
+    ) -> bool {
         for t in 0..block.len() {
             let index = (self.position_fp >> Oscillator::FRAC_BITS) as usize;
             if index >= self.end as usize {
@@ -96,11 +133,10 @@ impl Oscillator {
                 }
             }
 
-            let x1 = data[index] as i64;
-            let x2 = data[index + 1] as i64;
+            let x1 = data[index].value();
+            let x2 = data[index + 1].value();
             let a_fp = self.position_fp & (Oscillator::FRAC_UNIT - 1);
-            block[t] = Oscillator::FP_TO_SAMPLE
-                * ((x1 << Oscillator::FRAC_BITS) + a_fp * (x2 - x1)) as f32;
+            block[t] = S::FP_TO_SAMPLE * ((x1 << Oscillator::FRAC_BITS) + a_fp * (x2 - x1)) as f32;
 
             self.position_fp += pitch_ratio_fp;
         }
@@ -108,9 +144,9 @@ impl Oscillator {
         true
     }
 
-    fn fill_block_continuous(
+    fn fill_block_continuous<S: WaveSample>(
         &mut self,
-        data: &[i16],
+        data: &[S],
         block: &mut [f32],
         pitch_ratio_fp: i64,
     ) -> bool {
@@ -129,11 +165,10 @@ impl Oscillator {
                 index2 -= loop_length as usize;
             }
 
-            let x1 = data[index1] as i64;
-            let x2 = data[index2] as i64;
+            let x1 = data[index1].value();
+            let x2 = data[index2].value();
             let a_fp = self.position_fp & (Oscillator::FRAC_UNIT - 1);
-            *sample = Oscillator::FP_TO_SAMPLE
-                * ((x1 << Oscillator::FRAC_BITS) + a_fp * (x2 - x1)) as f32;
+            *sample = S::FP_TO_SAMPLE * ((x1 << Oscillator::FRAC_BITS) + a_fp * (x2 - x1)) as f32;
 
             self.position_fp += pitch_ratio_fp;
         }
@@ -3,15 +3,29 @@ use crate::{prelude::*, utils};
 pub struct RegionPair<'a> {
     pub preset: &'a PresetRegion,
     pub instrument: &'a InstrumentRegion,
+    /// Generator offsets from the modulators, fixed when a voice starts.
+    modulation: [i32; GeneratorType::COUNT],
 }
 
 impl<'a> RegionPair<'a> {
     pub fn new(preset: &'a PresetRegion, instrument: &'a InstrumentRegion) -> Self {
-        Self { preset, instrument }
+        Self {
+            preset,
+            instrument,
+            modulation: [0; GeneratorType::COUNT],
+        }
+    }
+
+    pub(crate) fn modulated(&self, modulation: [i32; GeneratorType::COUNT]) -> Self {
+        Self {
+            preset: self.preset,
+            instrument: self.instrument,
+            modulation,
+        }
     }
 
     fn gs(&self, i: usize) -> i32 {
-        self.preset.gs[i] as i32 + self.instrument.gs[i] as i32
+        self.preset.gs[i] as i32 + self.instrument.gs[i] as i32 + self.modulation[i]
     }
 
     pub fn get_sample_start(&self) -> i32 {
@@ -0,0 +1,50 @@
+/// A decoded channel voice message, which the synthesizer processes without reading bytes.
+///
+/// See [`Synthesizer::process_voice_message`](super::Synthesizer::process_voice_message)
+/// and [`Synthesizer::schedule_voice_message`](super::Synthesizer::schedule_voice_message).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VoiceMessage {
+    /// Stops a note.
+    NoteOff { key: u8 },
+    /// Starts a note. A velocity of 0 stops it.
+    NoteOn { key: u8, velocity: u8 },
+    /// Polyphonic key pressure.
+    KeyPressure { key: u8, pressure: u8 },
+    /// A controller change. Controllers 120 to 127 are the channel mode messages.
+    ControlChange { controller: u8, value: u8 },
+    /// Selects the program of the channel.
+    ProgramChange { program: u8 },
+    /// Channel pressure.
+    ChannelPressure { pressure: u8 },
+    /// The pitch bend, from 0 to 16383, centered at 8192.
+    PitchBend { value: u16 },
+}
+
+impl VoiceMessage {
+    /// Reads a message and its channel from its bytes.
+    /// Returns `None` for status bytes that aren't channel voice messages.
+    pub(crate) fn decode(status: u8, data1: u8, data2: u8) -> Option<(u8, Self)> {
+        let message = match status & 0xF0 {
+            0x80 => Self::NoteOff { key: data1 },
+            0x90 => Self::NoteOn {
+                key: data1,
+                velocity: data2,
+            },
+            0xA0 => Self::KeyPressure {
+                key: data1,
+                pressure: data2,
+            },
+            0xB0 => Self::ControlChange {
+                controller: data1,
+                value: data2,
+            },
+            0xC0 => Self::ProgramChange { program: data1 },
+            0xD0 => Self::ChannelPressure { pressure: data1 },
+            0xE0 => Self::PitchBend {
+                value: ((data2 as u16) << 7) | data1 as u16,
+            },
+            _ => return None,
+        };
+        Some((status & 0x0F, message))
+    }
+}
@@ -0,0 +1,123 @@
+use super::{Channel, voice::Voice};
+
+/// Specifies which voice stops when a new note needs one and the polyphony is used up.
+///
+/// A stolen voice stops immediately, along with the other voices of its note. Each policy falls back to the oldest voice
+/// when it has no better candidate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VoiceStealing {
+    /// The voice that has sounded the longest.
+    Oldest,
+    /// The quietest voice.
+    Quietest,
+    /// A voice of the same key on the same channel, which the new note retriggers.
+    SameKey,
+    /// A voice of the channel with the lowest priority.
+    /// See [`Synthesizer::set_channel_priority`](super::Synthesizer::set_channel_priority).
+    LowestPriorityChannel,
+    /// A voice whose key has been released, even if a pedal still holds it.
+    /// This keeps the notes that are played over the sustain pedal.
+    #[default]
+    ReleasedFirst,
+}
+
+impl VoiceStealing {
+    /// Stops notes until a new note with `voices` voices fits in the channel's polyphony,
+    /// which counts notes, and the synthesizer's, which counts voices.
+    /// Every voice of a stolen note stops, so layers aren't left playing on their own.
+    /// Returns the number of voices stolen.
+    pub(crate) fn make_room(
+        &self,
+        voices: &mut Vec<Voice>,
+        channels: &[Channel],
+        maximum_polyphony: usize,
+        channel: u8,
+        key: u8,
+        needed: usize,
+    ) -> u64 {
+        let mut stolen = 0;
+
+        if let Some(polyphony) = channels[channel as usize].get_polyphony() {
+            let polyphony = polyphony.max(1);
+            // every voice of a note is on the same channel, so a stolen note is one less
+            let mut notes = note_count(voices, channel);
+            while notes >= polyphony {
+                let Some(i) = self.choose(voices, channels, channel, key, |voice| {
+                    voice.channel == channel
+                }) else {
+                    break;
+                };
+                stolen += steal_note(voices, voices[i].note);
+                notes -= 1;
+            }
+        }
+
+        let needed = needed.min(maximum_polyphony);
+        while voices.len() + needed > maximum_polyphony {
+            let Some(i) = self.choose(voices, channels, channel, key, |_| true) else {
+                break;
+            };
+            stolen += steal_note(voices, voices[i].note);
+        }
+
+        stolen
+    }
+
+    /// Picks the voice to steal for a new note, among the voices `can_steal` allows.
+    pub(crate) fn choose(
+        &self,
+        voices: &[Voice],
+        channels: &[Channel],
+        channel: u8,
+        key: u8,
+        can_steal: impl Fn(&Voice) -> bool,
+    ) -> Option<usize> {
+        // the voice with the lowest (group, score) goes first
+        let rank = |voice: &Voice| -> (u8, f32) {
+            let age = -(voice.voice_length as f32);
+            match self {
+                Self::Oldest => (0, age),
+                Self::Quietest => {
+                    // voices that haven't rendered yet have no level to compare
+                    let level = if voice.voice_length == 0 {
+                        f32::INFINITY
+                    } else {
+                        voice.current_mix_gain_left + voice.current_mix_gain_right
+                    };
+                    (0, level)
+                }
+                Self::SameKey => ((voice.channel != channel || voice.key != key) as u8, age),
+                Self::LowestPriorityChannel => {
+                    (channels[voice.channel as usize].get_priority(), age)
+                }
+                Self::ReleasedFirst => (voice.is_held() as u8, age),
+            }
+        };
+
+        voices
+            .iter()
+            .enumerate()
+            .filter(|(_, voice)| can_steal(voice))
+            .map(|(i, voice)| (i, rank(voice)))
+            .min_by(|(_, a), (_, b)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+            .map(|(i, _)| i)
+    }
+}
+
+/// The number of notes sounding on a channel.
+fn note_count(voices: &[Voice], channel: u8) -> usize {
+    voices
+        .iter()
+        .enumerate()
+        .filter(|(i, voice)| {
+            voice.channel == channel && !voices[..*i].iter().any(|other| other.note == voice.note)
+        })
+        .count()
+}
+
+/// Stops every voice of a note, returning how many there were.
+fn steal_note(voices: &mut Vec<Voice>, note: u64) -> u64 {
+    let count = voices.len();
+    voices.retain(|voice| voice.note != note);
+    (count - voices.len()) as u64
+}
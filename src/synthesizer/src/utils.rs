@@ -1,4 +1,4 @@
-use bevy_platform::prelude::*;
+use std::f32::consts;
 pub(crate) const HALF_PI: f32 = consts::PI / 2_f32;
 pub(crate) const NON_AUDIBLE: f32 = 1.0e-4_f32;
 pub(crate) const LOG_NON_AUDIBLE: f32 = -6.907_755_4_f32;
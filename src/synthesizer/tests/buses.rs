@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use midix_synth::prelude::*;
+
+fn synthesizer_with_effects() -> Synthesizer {
+    Synthesizer::new(sine_sound_font(&[], &[]), &SynthesizerSettings::new(44_100)).unwrap()
+}
+
+/// Renders each output of the synthesizer, returning their left channels
+fn render_buses(synthesizer: &mut Synthesizer, length: usize) -> Vec<Vec<f32>> {
+    let count = synthesizer.get_bus_output_count();
+    let mut left = vec![vec![0.; length]; count];
+    let mut right = vec![vec![0.; length]; count];
+    let mut left_buffers = left.iter_mut().map(Vec::as_mut_slice).collect::<Vec<_>>();
+    let mut right_buffers = right.iter_mut().map(Vec::as_mut_slice).collect::<Vec<_>>();
+    synthesizer.render_buses(&mut left_buffers, &mut right_buffers);
+    left
+}
+
+fn is_silent(samples: &[f32]) -> bool {
+    samples.iter().all(|sample| sample.abs() < 1e-6)
+}
+
+#[test]
+fn buses_add_up_to_the_mix() {
+    let mut mixed = synthesizer_with_effects();
+    let mut split = synthesizer_with_effects();
+    assert_eq!(split.get_bus_output_count(), 1);
+    split.set_bus_layout(Some(BusLayout::per_channel()));
+    assert_eq!(split.get_bus_output_count(), 17);
+
+    for synthesizer in [&mut mixed, &mut split] {
+        synthesizer.note_on(0, 60, 127);
+        synthesizer.note_on(3, 64, 127);
+    }
+
+    let mut left = vec![0.; 4_410];
+    let mut right = vec![0.; 4_410];
+    mixed.render(&mut left, &mut right);
+    let buses = render_buses(&mut split, 4_410);
+
+    assert!(!is_silent(&buses[0]));
+    assert!(is_silent(&buses[1]));
+    assert!(!is_silent(&buses[3]));
+    // the shared reverb and chorus
+    assert!(!is_silent(&buses[16]));
+
+    for (i, sample) in left.iter().enumerate() {
+        let sum = buses.iter().map(|bus| bus[i]).sum::<f32>();
+        assert!((sum - sample).abs() < 1e-5, "{i}: {sum} != {sample}");
+    }
+}
+
+#[test]
+fn groups_with_their_own_effects() {
+    let mut synthesizer = synthesizer_with_effects();
+    let layout = BusLayout::groups(&[&[0, 1], &[9]])
+        .unwrap()
+        .with_effects(BusEffects::PerBus);
+    assert_eq!(layout.get_bus(1), 0);
+    assert_eq!(layout.get_bus(9), 1);
+    assert_eq!(layout.get_bus(15), 2);
+    synthesizer.set_bus_layout(Some(layout));
+    assert_eq!(synthesizer.get_bus_output_count(), 3);
+
+    synthesizer.note_on(1, 60, 127);
+    let buses = render_buses(&mut synthesizer, 4_410);
+    assert!(!is_silent(&buses[0]));
+    assert!(is_silent(&buses[1]));
+    assert!(is_silent(&buses[2]));
+
+    // the reverb tail stays on the bus
+    synthesizer.note_off_all_channel(1, true);
+    let buses = render_buses(&mut synthesizer, 64);
+    assert!(!is_silent(&buses[0]));
+    assert!(is_silent(&buses[2]));
+}
+
+#[test]
+fn buses_out_of_range() {
+    assert!(matches!(
+        BusLayout::new([usize::MAX; 16]),
+        Err(SynthesizerError::BusOutOfRange(usize::MAX))
+    ));
+    let mut channel_buses = [0; 16];
+    channel_buses[15] = 15;
+    assert_eq!(BusLayout::new(channel_buses).unwrap().get_bus_count(), 16);
+
+    // sixteen groups only fit when no channel is left over
+    let channels = (0..16).collect::<Vec<u8>>();
+    let groups = channels.chunks(1).collect::<Vec<_>>();
+    assert!(BusLayout::groups(&groups).is_ok());
+    assert!(BusLayout::groups(&groups[1..]).is_ok());
+    assert!(BusLayout::groups(&[&[0][..]; 16]).is_err());
+}
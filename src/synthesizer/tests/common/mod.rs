@@ -0,0 +1,276 @@
+#![allow(dead_code)]
+
+use midix_synth::prelude::*;
+
+/// A modulator record: source, destination, amount, amount source and transform.
+pub type ModulatorRecord = (u16, u16, i16, u16, u16);
+
+fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut bytes = id.to_vec();
+    bytes.extend((data.len() as u32).to_le_bytes());
+    bytes.extend(data);
+    // chunks are padded to an even size
+    if data.len() % 2 == 1 {
+        bytes.push(0);
+    }
+    bytes
+}
+
+fn list(list_type: &[u8; 4], chunks: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = list_type.to_vec();
+    for c in chunks {
+        data.extend(c);
+    }
+    chunk(b"LIST", &data)
+}
+
+fn name(name: &str) -> [u8; 20] {
+    let mut bytes = [0; 20];
+    bytes[..name.len()].copy_from_slice(name.as_bytes());
+    bytes
+}
+
+fn modulators(records: &[ModulatorRecord]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (source, destination, amount, amount_source, transform) in
+        records.iter().chain([&(0, 0, 0, 0, 0)])
+    {
+        bytes.extend(source.to_le_bytes());
+        bytes.extend(destination.to_le_bytes());
+        bytes.extend(amount.to_le_bytes());
+        bytes.extend(amount_source.to_le_bytes());
+        bytes.extend(transform.to_le_bytes());
+    }
+    bytes
+}
+
+fn generators(records: &[(u16, u16)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (generator, value) in records.iter().chain([&(0, 0)]) {
+        bytes.extend(generator.to_le_bytes());
+        bytes.extend(value.to_le_bytes());
+    }
+    bytes
+}
+
+/// Bag records for a single zone, followed by the terminator
+fn bag(generator_count: u16, modulator_count: u16) -> Vec<u8> {
+    let mut bytes = vec![0, 0, 0, 0];
+    bytes.extend(generator_count.to_le_bytes());
+    bytes.extend(modulator_count.to_le_bytes());
+    bytes
+}
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// A SoundFont whose preset (bank 0, patch 0) plays a looped 440 Hz sine wave.
+/// Its drum kit (bank 128, patch 0) plays it an octave up
+pub fn sine_sound_font(
+    instrument_modulators: &[ModulatorRecord],
+    preset_modulators: &[ModulatorRecord],
+) -> SoundFont {
+    sound_font(&sine(), None, instrument_modulators, preset_modulators)
+}
+
+/// Like [`sine_sound_font`], with the sine preset layering its instrument `layers` times,
+/// so each note plays that many voices
+#[allow(dead_code)]
+pub fn layered_sine_sound_font(layers: u16) -> SoundFont {
+    layered_sound_font(&sine(), None, layers, &[], &[])
+}
+
+fn sine() -> Vec<i16> {
+    (0..4_410)
+        .map(|i| {
+            let phase = 2. * std::f64::consts::PI * 440. * i as f64 / SAMPLE_RATE as f64;
+            (phase.sin() * 16_000.) as i16
+        })
+        .collect()
+}
+
+/// A SoundFont whose preset (bank 0, patch 0) loops a single sample at A4,
+/// with optional `sm24` low bytes
+pub fn sound_font(
+    samples: &[i16],
+    low_bytes: Option<&[u8]>,
+    instrument_modulators: &[ModulatorRecord],
+    preset_modulators: &[ModulatorRecord],
+) -> SoundFont {
+    layered_sound_font(
+        samples,
+        low_bytes,
+        1,
+        instrument_modulators,
+        preset_modulators,
+    )
+}
+
+fn layered_sound_font(
+    samples: &[i16],
+    low_bytes: Option<&[u8]>,
+    layers: u16,
+    instrument_modulators: &[ModulatorRecord],
+    preset_modulators: &[ModulatorRecord],
+) -> SoundFont {
+    let length = samples.len() as u32;
+    let mut smpl = Vec::new();
+    for sample in samples {
+        smpl.extend(sample.to_le_bytes());
+    }
+    // the spec asks for 46 zero samples after each sample
+    smpl.extend([0; 92]);
+
+    let mut sdta = vec![chunk(b"smpl", &smpl)];
+    if let Some(low_bytes) = low_bytes {
+        let mut sm24 = low_bytes.to_vec();
+        sm24.resize(samples.len() + 46, 0);
+        sdta.push(chunk(b"sm24", &sm24));
+    }
+
+    let bytes = sound_font_bytes(
+        &sdta,
+        length,
+        1,
+        layers,
+        instrument_modulators,
+        preset_modulators,
+    );
+    SoundFont::new(&mut bytes.as_slice()).unwrap()
+}
+
+/// A SoundFont 3 file holding a single compressed sample
+pub fn compressed_sound_font_bytes(stream: &[u8]) -> Vec<u8> {
+    // mono, compressed with Ogg Vorbis
+    sound_font_bytes(
+        &[chunk(b"smpl", stream)],
+        stream.len() as u32,
+        0x11,
+        1,
+        &[],
+        &[],
+    )
+}
+
+/// The bytes of a SoundFont with one preset (bank 0, patch 0) looping a single sample at A4,
+/// and a drum kit (bank 128, patch 0) playing it an octave up.
+/// The preset has a zone for each layer, and the preset modulators are in the first one
+fn sound_font_bytes(
+    sdta: &[Vec<u8>],
+    length: u32,
+    sample_type: u16,
+    layers: u16,
+    instrument_modulators: &[ModulatorRecord],
+    preset_modulators: &[ModulatorRecord],
+) -> Vec<u8> {
+    let mut phdr = Vec::new();
+    for (preset, bank, bag_index) in [
+        ("sine", 0_u16, 0_u16),
+        ("drums", 128, layers),
+        ("EOP", 0, layers + 1),
+    ] {
+        phdr.extend(name(preset));
+        phdr.extend(0_u16.to_le_bytes());
+        phdr.extend(bank.to_le_bytes());
+        phdr.extend(bag_index.to_le_bytes());
+        phdr.extend([0; 12]);
+    }
+    // the drum kit plays the same instrument an octave up, without modulators
+    let mut preset_generators = vec![(41, 0); layers as usize];
+    preset_generators.extend([(51, 12), (41, 0)]);
+    let mut bags = (0..layers)
+        .map(|layer| {
+            (
+                layer,
+                if layer == 0 {
+                    0
+                } else {
+                    preset_modulators.len()
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    bags.extend([
+        (layers, preset_modulators.len()),
+        (layers + 2, preset_modulators.len()),
+    ]);
+    let mut pbag = Vec::new();
+    for (generator_index, modulator_index) in bags {
+        pbag.extend(generator_index.to_le_bytes());
+        pbag.extend((modulator_index as u16).to_le_bytes());
+    }
+
+    let mut inst = Vec::new();
+    for (instrument, bag_index) in [("sine", 0_u16), ("EOI", 1)] {
+        inst.extend(name(instrument));
+        inst.extend(bag_index.to_le_bytes());
+    }
+
+    let mut shdr = Vec::new();
+    for (sample, start, end) in [("sample", 0, length), ("EOS", 0, 0)] {
+        shdr.extend(name(sample));
+        for value in [start, end, start, end, SAMPLE_RATE] {
+            shdr.extend(value.to_le_bytes());
+        }
+        // A4, no correction, no link
+        shdr.extend([69, 0, 0, 0]);
+        shdr.extend(sample_type.to_le_bytes());
+    }
+
+    // looped continuously, then the sample ID
+    let instrument_generators = [(54, 1), (53, 0)];
+
+    let bytes = [
+        b"sfbk".to_vec(),
+        list(b"INFO", &[chunk(b"ifil", &[2, 0, 4, 0])]),
+        list(b"sdta", sdta),
+        list(
+            b"pdta",
+            &[
+                chunk(b"phdr", &phdr),
+                chunk(b"pbag", &pbag),
+                chunk(b"pmod", &modulators(preset_modulators)),
+                chunk(b"pgen", &generators(&preset_generators)),
+                chunk(b"inst", &inst),
+                chunk(
+                    b"ibag",
+                    &bag(
+                        instrument_generators.len() as u16,
+                        instrument_modulators.len() as u16,
+                    ),
+                ),
+                chunk(b"imod", &modulators(instrument_modulators)),
+                chunk(b"igen", &generators(&instrument_generators)),
+                chunk(b"shdr", &shdr),
+            ],
+        ),
+    ]
+    .concat();
+
+    chunk(b"RIFF", &bytes)
+}
+
+/// Renders a second of audio, returning the RMS level of the left channel
+pub fn render_level(synthesizer: &mut Synthesizer) -> f32 {
+    let mut left = vec![0.; 44_100];
+    let mut right = vec![0.; 44_100];
+    synthesizer.render(&mut left, &mut right);
+    (left.iter().map(|s| s * s).sum::<f32>() / left.len() as f32).sqrt()
+}
+
+pub fn synthesizer(sound_font: SoundFont) -> Synthesizer {
+    let mut settings = SynthesizerSettings::new(44_100);
+    settings.enable_reverb_and_chorus = false;
+    Synthesizer::new(sound_font, &settings).unwrap()
+}
+
+/// Estimates the frequency of the left channel over the next 50 ms, from its zero crossings
+pub fn render_frequency(synthesizer: &mut Synthesizer) -> f32 {
+    let mut left = vec![0.; 2_205];
+    let mut right = vec![0.; 2_205];
+    synthesizer.render(&mut left, &mut right);
+    let crossings = left
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.) != (pair[1] < 0.))
+        .count();
+    crossings as f32 / 2. / 0.05
+}
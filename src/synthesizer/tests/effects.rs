@@ -0,0 +1,122 @@
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use common::*;
+use midix_synth::prelude::*;
+
+/// Scales the signal, counting its resets
+struct Gain {
+    gain: f32,
+    resets: Arc<AtomicUsize>,
+}
+
+impl Gain {
+    fn new(gain: f32) -> Self {
+        Self {
+            gain,
+            resets: Arc::default(),
+        }
+    }
+}
+
+impl AudioEffect for Gain {
+    fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        for sample in left.iter_mut().chain(right.iter_mut()) {
+            *sample *= self.gain;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.resets.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn synthesizer_with_effects() -> Synthesizer {
+    Synthesizer::new(sine_sound_font(&[], &[]), &SynthesizerSettings::new(44_100)).unwrap()
+}
+
+/// Plays A4 for a second, returning its RMS level
+fn level(synthesizer: &mut Synthesizer) -> f32 {
+    synthesizer.note_on(0, 69, 127);
+    render_level(synthesizer)
+}
+
+#[test]
+fn master_effects() {
+    let full = level(&mut synthesizer_with_effects());
+
+    let mut synthesizer = synthesizer_with_effects();
+    let gain = Gain::new(0.5);
+    let resets = gain.resets.clone();
+    synthesizer.add_effect(EffectBus::Master, gain);
+    let half = level(&mut synthesizer);
+    assert!((half / full - 0.5).abs() < 1e-3, "{half} {full}");
+
+    synthesizer.reset();
+    assert_eq!(resets.load(Ordering::Relaxed), 1);
+
+    synthesizer.clear_effects(EffectBus::Master);
+    assert!((level(&mut synthesizer) - full).abs() < 1e-3);
+}
+
+#[test]
+fn send_effects_replace_the_built_in_ones() {
+    let mut synthesizer = synthesizer_with_effects();
+    synthesizer.set_reverb_parameters(None);
+    synthesizer.set_chorus_parameters(None);
+    assert!(!synthesizer.get_enable_reverb_and_chorus());
+    let dry = level(&mut synthesizer);
+
+    // the reverb send (40 by default) passes straight through
+    let mut synthesizer = synthesizer_with_effects();
+    synthesizer.set_reverb_parameters(None);
+    synthesizer.set_chorus_parameters(None);
+    synthesizer.add_effect(EffectBus::Reverb, Gain::new(1.));
+    let sent = level(&mut synthesizer);
+    assert!(sent > 1.2 * dry, "{sent} {dry}");
+
+    // the chorus send is 0
+    let mut synthesizer = synthesizer_with_effects();
+    synthesizer.set_reverb_parameters(None);
+    synthesizer.set_chorus_parameters(None);
+    synthesizer.add_effect(EffectBus::Chorus, Gain::new(1.));
+    assert!((level(&mut synthesizer) - dry).abs() < 1e-6);
+}
+
+#[test]
+fn reverb_parameters() {
+    let mut synthesizer = synthesizer_with_effects();
+    assert_eq!(
+        synthesizer.get_reverb_parameters(),
+        Some(ReverbParameters::default())
+    );
+    assert_eq!(
+        synthesizer.get_chorus_parameters(),
+        Some(ChorusParameters::default())
+    );
+
+    // the tail after the note is cut off, past the block rendered ahead
+    let tail = |wet: f32| {
+        let mut synthesizer = synthesizer_with_effects();
+        synthesizer.set_reverb_parameters(Some(ReverbParameters {
+            wet,
+            ..Default::default()
+        }));
+        level(&mut synthesizer);
+        synthesizer.note_off_all(true);
+        synthesizer.render(&mut [0.; 64], &mut [0.; 64]);
+        render_level(&mut synthesizer)
+    };
+    assert!(tail(0.) < 1e-6);
+    assert!(tail(1.) > 2. * tail(ReverbParameters::default().wet));
+
+    synthesizer.set_reverb_parameters(Some(ReverbParameters {
+        wet: 2.,
+        ..Default::default()
+    }));
+    assert_eq!(synthesizer.get_reverb_parameters().unwrap().wet, 1.);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::*;
+
+fn level_with(sound_font: midix_synth::prelude::SoundFont, controllers: &[(u8, u8)]) -> f32 {
+    let mut synthesizer = synthesizer(sound_font);
+    for (number, value) in controllers {
+        synthesizer.process_midi_message(0xB0, *number, *value);
+    }
+    synthesizer.note_on(0, 69, 127);
+    render_level(&mut synthesizer)
+}
+
+#[test]
+fn controller_to_attenuation() {
+    // CC2 (breath), linear, positive, unipolar, to initial attenuation
+    let breath = (0x0082, 48, 480, 0, 0);
+
+    let open = level_with(sine_sound_font(&[breath], &[]), &[]);
+    let closed = level_with(sine_sound_font(&[breath], &[]), &[(2, 127)]);
+    assert!(open > 0.01);
+    // 48 dB quieter
+    assert!(closed < open / 200.);
+
+    // preset modulators add to the instrument ones
+    let both = level_with(sine_sound_font(&[breath], &[breath]), &[(2, 127)]);
+    assert!(both < closed / 200.);
+}
+
+#[test]
+fn overriding_a_default_modulator() {
+    // CC7 to initial attenuation, with no effect
+    let volume = (0x0587, 48, 0, 0, 0);
+
+    let full = level_with(sine_sound_font(&[volume], &[]), &[]);
+    let half = level_with(sine_sound_font(&[volume], &[]), &[(7, 64)]);
+    assert!((half / full - 1.).abs() < 0.05);
+
+    // without the override, the volume applies
+    let half = level_with(sine_sound_font(&[], &[]), &[(7, 64)]);
+    assert!(half < full / 2.);
+}
+
+#[test]
+fn overriding_the_pan_default() {
+    // the left and right RMS levels of a note, with CC10 part way to the right
+    let levels = |sound_font| {
+        let mut synthesizer = synthesizer(sound_font);
+        synthesizer.process_midi_message(0xB0, 10, 96);
+        synthesizer.note_on(0, 69, 127);
+        let mut left = vec![0.; 44_100];
+        let mut right = vec![0.; 44_100];
+        synthesizer.render(&mut left, &mut right);
+        let rms = |buffer: &[f32]| {
+            (buffer.iter().map(|s| s * s).sum::<f32>() / buffer.len() as f32).sqrt()
+        };
+        (rms(&left), rms(&right))
+    };
+
+    // CC10 to pan, with no effect, leaves the note in the center
+    let pan = (0x028A, 17, 0, 0, 0);
+    let (left, right) = levels(sine_sound_font(&[pan], &[]));
+    assert!((left / right - 1.).abs() < 0.05);
+
+    // without the override, the pan applies
+    let (left, right) = levels(sine_sound_font(&[], &[]));
+    assert!(left < right / 2.);
+}
+
+#[test]
+fn pressure_swells_notes() {
+    let level_with_pressure = |status: u8, data1: u8, data2: u8| {
+        let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+        synthesizer.note_on(0, 69, 127);
+        synthesizer.process_midi_message(status, data1, data2);
+        render_level(&mut synthesizer)
+    };
+
+    let none = level_with_pressure(0xD0, 0, 0);
+    let channel = level_with_pressure(0xD0, 127, 0);
+    let key = level_with_pressure(0xA0, 69, 127);
+    let other_key = level_with_pressure(0xA0, 70, 127);
+
+    // 6 dB louder
+    assert!((channel / none - 2.).abs() < 0.05);
+    assert!((key / none - 2.).abs() < 0.05);
+    assert_eq!(other_key, none);
+}
+
+#[test]
+fn overriding_a_pressure_modulator() {
+    // channel pressure to initial attenuation, with no effect
+    let pressure = (0x000D, 48, 0, 0, 0);
+    let mut synthesizer = synthesizer(sine_sound_font(&[pressure], &[]));
+    synthesizer.note_on(0, 69, 127);
+    let none = render_level(&mut synthesizer);
+    synthesizer.process_midi_message(0xD0, 127, 0);
+    let pressed = render_level(&mut synthesizer);
+    assert!((pressed / none - 1.).abs() < 0.05);
+}
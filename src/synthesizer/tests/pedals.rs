@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+
+fn cc(synthesizer: &mut midix_synth::prelude::Synthesizer, number: u8, value: u8) {
+    synthesizer.process_midi_message(0xB0, number, value);
+}
+
+#[test]
+fn sostenuto_latches_held_notes() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    synthesizer.note_on(0, 69, 127);
+    cc(&mut synthesizer, 0x42, 127);
+    synthesizer.note_off(0, 69);
+
+    // played after the pedal, so not latched
+    synthesizer.note_on(0, 72, 127);
+    synthesizer.note_off(0, 72);
+    render_level(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 1);
+
+    cc(&mut synthesizer, 0x42, 0);
+    render_level(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 0);
+}
+
+#[test]
+fn soft_pedal() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    synthesizer.note_on(0, 69, 127);
+    let loud = render_level(&mut synthesizer);
+
+    cc(&mut synthesizer, 0x43, 127);
+    synthesizer.note_off(0, 69);
+    synthesizer.note_on(0, 69, 127);
+    let soft = render_level(&mut synthesizer);
+
+    // 3 dB quieter
+    assert!((soft / loud - 0.708).abs() < 0.02, "{}", soft / loud);
+}
+
+#[test]
+fn portamento_glides_between_notes() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    // two seconds
+    cc(&mut synthesizer, 0x05, 64);
+    cc(&mut synthesizer, 0x41, 127);
+
+    synthesizer.note_on(0, 57, 127);
+    assert!((render_frequency(&mut synthesizer) - 220.).abs() < 30.);
+    synthesizer.note_off(0, 57);
+
+    synthesizer.note_on(0, 69, 127);
+    let start = render_frequency(&mut synthesizer);
+    assert!(start < 300., "{start}");
+    render_level(&mut synthesizer);
+    render_level(&mut synthesizer);
+    assert!((render_frequency(&mut synthesizer) - 440.).abs() < 30.);
+
+    // portamento control glides from a given key, once
+    synthesizer.note_off(0, 69);
+    render_level(&mut synthesizer);
+    cc(&mut synthesizer, 0x41, 0);
+    cc(&mut synthesizer, 0x54, 57);
+    synthesizer.note_on(0, 81, 127);
+    assert!(render_frequency(&mut synthesizer) < 300.);
+    synthesizer.note_on(0, 69, 127);
+    synthesizer.note_off(0, 81);
+    render_level(&mut synthesizer);
+    assert!((render_frequency(&mut synthesizer) - 440.).abs() < 30.);
+}
+
+#[test]
+fn legato_retunes_held_notes() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    cc(&mut synthesizer, 0x44, 127);
+
+    synthesizer.note_on(0, 57, 127);
+    synthesizer.note_on(0, 69, 127);
+    assert_eq!(synthesizer.get_active_voice_count(), 1);
+    assert!((render_frequency(&mut synthesizer) - 440.).abs() < 30.);
+
+    // the first key no longer owns the voice
+    synthesizer.note_off(0, 57);
+    render_level(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 1);
+    synthesizer.note_off(0, 69);
+    render_level(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 0);
+}
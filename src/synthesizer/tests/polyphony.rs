@@ -0,0 +1,176 @@
+mod common;
+
+use common::*;
+use midix_synth::prelude::*;
+
+fn render_block(synthesizer: &mut Synthesizer) {
+    let mut left = [0.; 64];
+    let mut right = [0.; 64];
+    synthesizer.render(&mut left, &mut right);
+}
+
+fn synthesizer_with(voice_stealing: VoiceStealing) -> Synthesizer {
+    let mut settings = SynthesizerSettings::new(44_100);
+    settings.enable_reverb_and_chorus = false;
+    settings.maximum_polyphony = 8;
+    settings.voice_stealing = voice_stealing;
+    Synthesizer::new(sine_sound_font(&[], &[]), &settings).unwrap()
+}
+
+/// Plays the oldest note on channel 0, then fills the polyphony with `fill` on channel 1,
+/// and plays one more note on channel 2. Returns the voices left on channel 0.
+fn channel_0_voices(voice_stealing: VoiceStealing, fill: impl FnOnce(&mut Synthesizer)) -> usize {
+    let mut synthesizer = synthesizer_with(voice_stealing);
+    synthesizer.note_on(0, 60, 127);
+    render_block(&mut synthesizer);
+    fill(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 8);
+
+    synthesizer.note_on(2, 72, 127);
+    assert_eq!(synthesizer.get_active_voice_count(), 8);
+    assert_eq!(synthesizer.get_stolen_voice_count(), 1);
+
+    synthesizer.note_off_all_channel(1, true);
+    synthesizer.note_off_all_channel(2, true);
+    synthesizer.get_active_voice_count()
+}
+
+/// Seven notes on channel 1, a block apart
+fn seven_notes(synthesizer: &mut Synthesizer) {
+    for key in 61..68 {
+        synthesizer.note_on(1, key, 127);
+        render_block(synthesizer);
+    }
+}
+
+#[test]
+fn maximum_polyphony_is_kept() {
+    let mut synthesizer = synthesizer_with(VoiceStealing::Oldest);
+    for key in 60..70 {
+        synthesizer.note_on(0, key, 127);
+        render_block(&mut synthesizer);
+    }
+    assert_eq!(synthesizer.get_active_voice_count(), 8);
+    assert_eq!(synthesizer.get_stolen_voice_count(), 2);
+}
+
+#[test]
+fn channel_polyphony() {
+    let mut synthesizer = synthesizer_with(VoiceStealing::default());
+    assert_eq!(synthesizer.get_channel_polyphony(0), None);
+    synthesizer.set_channel_polyphony(0, Some(2));
+
+    for key in 60..63 {
+        synthesizer.note_on(0, key, 127);
+        synthesizer.note_on(1, key, 127);
+    }
+    assert_eq!(synthesizer.get_active_voice_count(), 5);
+    assert_eq!(synthesizer.get_stolen_voice_count(), 1);
+
+    // the limit outlasts a reset, and a limit of 0 still lets one voice play
+    synthesizer.reset();
+    assert_eq!(synthesizer.get_channel_polyphony(0), Some(2));
+    synthesizer.set_channel_polyphony(0, Some(0));
+    synthesizer.note_on(0, 60, 127);
+    synthesizer.note_on(0, 61, 127);
+    assert_eq!(synthesizer.get_active_voice_count(), 1);
+}
+
+#[test]
+fn oldest() {
+    assert_eq!(channel_0_voices(VoiceStealing::Oldest, seven_notes), 0);
+}
+
+#[test]
+fn quietest() {
+    let quiet = |synthesizer: &mut Synthesizer| {
+        synthesizer.control_change(1, 7, 10);
+        seven_notes(synthesizer);
+    };
+    assert_eq!(channel_0_voices(VoiceStealing::Quietest, quiet), 1);
+    assert_eq!(channel_0_voices(VoiceStealing::Oldest, quiet), 0);
+}
+
+#[test]
+fn same_key() {
+    // the new note's key is already sounding on channel 2
+    let retrigger = |synthesizer: &mut Synthesizer| {
+        for key in 61..67 {
+            synthesizer.note_on(1, key, 127);
+            render_block(synthesizer);
+        }
+        synthesizer.note_on(2, 72, 127);
+    };
+    assert_eq!(channel_0_voices(VoiceStealing::SameKey, retrigger), 1);
+    assert_eq!(channel_0_voices(VoiceStealing::Oldest, retrigger), 0);
+
+    // without a voice of the same key, the oldest goes
+    assert_eq!(channel_0_voices(VoiceStealing::SameKey, seven_notes), 0);
+}
+
+#[test]
+fn lowest_priority_channel() {
+    let prioritized = |synthesizer: &mut Synthesizer| {
+        synthesizer.set_channel_priority(0, 1);
+        seven_notes(synthesizer);
+    };
+    assert_eq!(
+        channel_0_voices(VoiceStealing::LowestPriorityChannel, prioritized),
+        1
+    );
+    assert_eq!(channel_0_voices(VoiceStealing::Oldest, prioritized), 0);
+}
+
+#[test]
+fn released_first() {
+    let released = |synthesizer: &mut Synthesizer| {
+        seven_notes(synthesizer);
+        synthesizer.note_off(1, 67);
+    };
+    assert_eq!(channel_0_voices(VoiceStealing::ReleasedFirst, released), 1);
+    assert_eq!(channel_0_voices(VoiceStealing::Oldest, released), 0);
+}
+
+#[test]
+fn notes_keep_their_layers() {
+    let layered = |voice_stealing: VoiceStealing| {
+        let mut settings = SynthesizerSettings::new(44_100);
+        settings.enable_reverb_and_chorus = false;
+        settings.maximum_polyphony = 8;
+        settings.voice_stealing = voice_stealing;
+        Synthesizer::new(layered_sine_sound_font(2), &settings).unwrap()
+    };
+
+    // a full synthesizer steals a whole note for the new one, even one of the same key
+    for voice_stealing in [VoiceStealing::SameKey, VoiceStealing::Oldest] {
+        let mut synthesizer = layered(voice_stealing);
+        for key in 60..63 {
+            synthesizer.note_on(0, key, 127);
+            render_block(&mut synthesizer);
+        }
+        synthesizer.note_on(1, 63, 127);
+        render_block(&mut synthesizer);
+        assert_eq!(synthesizer.get_active_voice_count(), 8);
+
+        synthesizer.note_on(1, 63, 127);
+        assert_eq!(synthesizer.get_active_voice_count(), 8);
+        assert_eq!(synthesizer.get_stolen_voice_count(), 2);
+
+        // the new note has both of its voices
+        synthesizer.note_off_all_channel(0, true);
+        let expected = match voice_stealing {
+            VoiceStealing::SameKey => 2,
+            _ => 4,
+        };
+        assert_eq!(synthesizer.get_active_voice_count(), expected);
+    }
+
+    // the channel polyphony counts notes
+    let mut synthesizer = layered(VoiceStealing::default());
+    synthesizer.set_channel_polyphony(0, Some(1));
+    synthesizer.note_on(0, 60, 127);
+    assert_eq!(synthesizer.get_active_voice_count(), 2);
+    synthesizer.note_on(0, 62, 127);
+    assert_eq!(synthesizer.get_active_voice_count(), 2);
+    assert_eq!(synthesizer.get_stolen_voice_count(), 2);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+use midix_synth::prelude::*;
+
+/// A square wave that is too quiet for 16 bits
+fn quiet_square() -> (Vec<i16>, Vec<u8>) {
+    let high = (0..100).map(|i| if i < 50 { 0 } else { -1 }).collect();
+    let low = (0..100).map(|i| if i < 50 { 0x40 } else { 0xC0 }).collect();
+    (high, low)
+}
+
+#[test]
+fn sixteen_bit_samples() {
+    let (high, _) = quiet_square();
+    let sound_font = sound_font(&high, None, &[], &[]);
+    assert_eq!(sound_font.get_bits_per_sample(), 16);
+    assert!(matches!(sound_font.get_wave_data(), WaveData::Pcm16(_)));
+}
+
+#[test]
+fn twenty_four_bit_samples() {
+    let (high, low) = quiet_square();
+    let sound_font = sound_font(&high, Some(&low), &[], &[]);
+    assert_eq!(sound_font.get_bits_per_sample(), 24);
+    let WaveData::Pcm24(samples) = sound_font.get_wave_data() else {
+        panic!("expected 24-bit samples");
+    };
+    assert_eq!(samples[..2], [0x40, 0x40]);
+    assert_eq!(samples[50], -0x40);
+
+    // the low bytes are audible
+    let mut synthesizer = synthesizer(sound_font);
+    synthesizer.note_on(0, 69, 127);
+    let level = render_level(&mut synthesizer);
+    assert!(level > 0. && level < 1e-4);
+}
+
+#[test]
+fn compressed_samples() {
+    // bytes which aren't a valid Vorbis stream, with an even and an odd length
+    for stream in [
+        &b"OggS\0\x02 not really vorbis"[..],
+        b"OggS\0\x02 not really vorbi",
+    ] {
+        compressed_samples_fail(stream);
+    }
+}
+
+fn compressed_samples_fail(stream: &[u8]) {
+    let bytes = compressed_sound_font_bytes(stream);
+    let error = SoundFont::new(&mut bytes.as_slice()).unwrap_err();
+
+    if cfg!(feature = "sf3") {
+        assert!(
+            matches!(&error, SoundFontError::SampleDecodeFailed { sample_name, .. } if sample_name == "sample"),
+            "{error}"
+        );
+    } else {
+        assert!(matches!(error, SoundFontError::UnsupportedSampleFormat));
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+use midix_synth::prelude::*;
+
+/// The index of the first sample that isn't silent
+fn onset(samples: &[f32]) -> Option<usize> {
+    samples.iter().position(|sample| sample.abs() > 1e-6)
+}
+
+#[test]
+fn note_on_is_sample_accurate() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    assert_eq!(synthesizer.get_block_size(), 64);
+
+    // both land part-way through a block
+    synthesizer.schedule_midi_message(100, 0x90, 69, 127);
+    synthesizer.schedule_midi_message(1_000, 0x91, 69, 127);
+
+    let mut left = vec![0.; 2_000];
+    let mut right = vec![0.; 2_000];
+    synthesizer.render(&mut left, &mut right);
+
+    // the sine starts at zero, so the first sound is one sample in
+    let first = onset(&left).unwrap();
+    assert!((100..=101).contains(&first), "{first}");
+    assert_eq!(synthesizer.get_active_voice_count(), 2);
+
+    // the second note doubles the level from its sample on
+    let level = |samples: &[f32]| samples.iter().map(|s| s.abs()).fold(0., f32::max);
+    assert!(level(&left[1_010..1_500]) > 1.5 * level(&left[500..990]));
+}
+
+#[test]
+fn scheduled_messages_wait_for_their_block() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let mut left = vec![0.; 32];
+    let mut right = vec![0.; 32];
+
+    synthesizer.schedule_midi_message(200, 0x90, 69, 127);
+    synthesizer.schedule_sysex(0, &[0xF0, 0x7E, 0x7F, 0x09, 0x01, 0xF7]);
+    synthesizer.render(&mut left, &mut right);
+    assert_eq!(synthesizer.get_active_voice_count(), 0);
+
+    // a message due within a block that has started rendering comes at the next one
+    synthesizer.schedule_midi_message(0, 0x90, 60, 127);
+    synthesizer.render(&mut left, &mut right);
+    assert_eq!(onset(&left), None);
+    synthesizer.render(&mut left, &mut right);
+    assert!(onset(&left).is_some());
+
+    // resetting drops whatever is left
+    synthesizer.reset();
+    render_level(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 0);
+}
+
+#[test]
+fn decoded_messages() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let note_on = VoiceMessage::NoteOn {
+        key: 69,
+        velocity: 127,
+    };
+    synthesizer.schedule_voice_message(100, 0, note_on);
+    synthesizer.schedule_voice_message(300, 0, VoiceMessage::NoteOff { key: 69 });
+
+    let mut left = vec![0.; 200];
+    let mut right = vec![0.; 200];
+    synthesizer.render(&mut left, &mut right);
+    let first = onset(&left).unwrap();
+    assert!((100..=101).contains(&first), "{first}");
+
+    synthesizer.process_voice_message(1, note_on);
+    assert_eq!(synthesizer.get_active_voice_count(), 2);
+}
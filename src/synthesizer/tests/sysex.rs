@@ -0,0 +1,103 @@
+mod common;
+
+use common::*;
+use midix_synth::prelude::Synthesizer;
+
+const GM_SYSTEM_ON: [u8; 6] = [0xF0, 0x7E, 0x7F, 0x09, 0x01, 0xF7];
+const GS_RESET: [u8; 11] = [
+    0xF0, 0x41, 0x10, 0x42, 0x12, 0x40, 0x00, 0x7F, 0x00, 0x41, 0xF7,
+];
+const XG_SYSTEM_ON: [u8; 9] = [0xF0, 0x43, 0x10, 0x4C, 0x00, 0x00, 0x7E, 0x00, 0xF7];
+
+/// A GS data set message, with its checksum
+fn gs(address: [u8; 3], value: u8) -> Vec<u8> {
+    let sum = address.iter().sum::<u8>().wrapping_add(value);
+    let checksum = (128 - sum % 128) % 128;
+    [
+        &[0xF0, 0x41, 0x10, 0x42, 0x12][..],
+        &address,
+        &[value, checksum, 0xF7],
+    ]
+    .concat()
+}
+
+fn level(synthesizer: &mut Synthesizer) -> f32 {
+    synthesizer.note_on(0, 69, 127);
+    let level = render_level(synthesizer);
+    synthesizer.note_off(0, 69);
+    render_level(synthesizer);
+    level
+}
+
+fn frequency(synthesizer: &mut Synthesizer, channel: u8) -> f32 {
+    synthesizer.note_on(channel, 69, 127);
+    // skip the attack, which starts part-way through a block
+    render_frequency(synthesizer);
+    let frequency = render_frequency(synthesizer);
+    synthesizer.note_off(channel, 69);
+    render_level(synthesizer);
+    frequency
+}
+
+#[test]
+fn master_volume() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let full = level(&mut synthesizer);
+
+    // universal real-time master volume, at half
+    synthesizer.process_sysex(&[0xF0, 0x7F, 0x7F, 0x04, 0x01, 0x00, 0x40, 0xF7]);
+    let half = level(&mut synthesizer);
+    assert!((half / full - 0.5).abs() < 0.01, "{}", half / full);
+
+    // GS and XG master volume, without the framing bytes
+    synthesizer.process_sysex(&gs([0x40, 0x00, 0x04], 32)[1..10]);
+    let quarter = level(&mut synthesizer);
+    assert!((quarter / full - 0.25).abs() < 0.01, "{}", quarter / full);
+    synthesizer.process_sysex(&[0xF0, 0x43, 0x10, 0x4C, 0x00, 0x00, 0x04, 0x7F, 0xF7]);
+    assert!((level(&mut synthesizer) / full - 1.).abs() < 0.01);
+
+    synthesizer.process_sysex(&[0xF0, 0x7F, 0x7F, 0x04, 0x01, 0x00, 0x00, 0xF7]);
+    assert_eq!(level(&mut synthesizer), 0.);
+
+    // system on restores it
+    synthesizer.process_sysex(&GM_SYSTEM_ON);
+    assert!((level(&mut synthesizer) / full - 1.).abs() < 0.01);
+}
+
+#[test]
+fn system_on_resets_channels() {
+    for reset in [&GM_SYSTEM_ON[..], &GS_RESET, &XG_SYSTEM_ON] {
+        let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+        let full = level(&mut synthesizer);
+
+        synthesizer.process_midi_message(0xB0, 0x07, 50);
+        synthesizer.note_on(0, 69, 127);
+        synthesizer.process_sysex(reset);
+        assert_eq!(synthesizer.get_active_voice_count(), 0);
+        assert!((level(&mut synthesizer) / full - 1.).abs() < 0.01);
+    }
+}
+
+#[test]
+fn rhythm_part() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let melodic = frequency(&mut synthesizer, 0);
+    // the drum kit plays an octave up
+    let drums = frequency(&mut synthesizer, 9);
+    assert!((drums / melodic - 2.).abs() < 0.05, "{drums} {melodic}");
+
+    // GS part 1 is block 1, and part 10 is block 0
+    synthesizer.process_sysex(&gs([0x40, 0x11, 0x15], 1));
+    synthesizer.process_sysex(&gs([0x40, 0x10, 0x15], 0));
+    assert!((frequency(&mut synthesizer, 0) - drums).abs() < 10.);
+    assert!((frequency(&mut synthesizer, 9) - melodic).abs() < 10.);
+
+    // XG part mode on part 2
+    synthesizer.process_sysex(&[0xF0, 0x43, 0x10, 0x4C, 0x08, 0x01, 0x07, 0x02, 0xF7]);
+    assert!((frequency(&mut synthesizer, 1) - drums).abs() < 10.);
+
+    synthesizer.process_sysex(&GS_RESET);
+    assert!((frequency(&mut synthesizer, 0) - melodic).abs() < 10.);
+    assert!((frequency(&mut synthesizer, 1) - melodic).abs() < 10.);
+    assert!((frequency(&mut synthesizer, 9) - drums).abs() < 10.);
+}
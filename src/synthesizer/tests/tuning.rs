@@ -0,0 +1,95 @@
+mod common;
+
+use common::*;
+use midix_synth::prelude::Synthesizer;
+
+fn rpn(synthesizer: &mut Synthesizer, number: u8, msb: u8, lsb: u8) {
+    synthesizer.process_midi_message(0xB0, 0x65, 0);
+    synthesizer.process_midi_message(0xB0, 0x64, number);
+    synthesizer.process_midi_message(0xB0, 0x06, msb);
+    synthesizer.process_midi_message(0xB0, 0x26, lsb);
+}
+
+fn play(synthesizer: &mut Synthesizer) -> f32 {
+    synthesizer.note_on(0, 69, 127);
+    // skip the attack, which starts part-way through a block
+    render_frequency(synthesizer);
+    let frequency = render_frequency(synthesizer);
+    synthesizer.note_off(0, 69);
+    render_level(synthesizer);
+    frequency
+}
+
+#[test]
+fn tuning_down() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let untuned = play(&mut synthesizer);
+
+    // an octave down
+    rpn(&mut synthesizer, 2, 64 - 12, 0);
+    let frequency = play(&mut synthesizer);
+    assert!((frequency - untuned / 2.).abs() < 10., "{frequency}");
+
+    // a semitone down
+    rpn(&mut synthesizer, 2, 64, 0);
+    rpn(&mut synthesizer, 1, 0, 0);
+    let frequency = play(&mut synthesizer);
+    assert!((frequency - untuned * 0.944).abs() < 10., "{frequency}");
+}
+
+/// An MTS bulk dump to bank 0, retuning every key a number of semitones
+fn bulk_dump(program: u8, semitones: i8) -> Vec<u8> {
+    let mut data = vec![0xF0, 0x7E, 0x7F, 0x08, 0x01, program];
+    data.extend(b"shifted         ");
+    for key in 0..128_i16 {
+        data.extend([(key + semitones as i16).clamp(0, 127) as u8, 0, 0]);
+    }
+    let checksum = data[1..].iter().fold(0, |checksum, byte| checksum ^ byte);
+    data.extend([checksum & 0x7F, 0xF7]);
+    data
+}
+
+#[test]
+fn key_based_tuning() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let untuned = play(&mut synthesizer);
+
+    synthesizer.process_sysex(&bulk_dump(1, -12));
+    // not selected yet
+    let frequency = play(&mut synthesizer);
+    assert!((frequency - untuned).abs() < 10., "{frequency}");
+
+    rpn(&mut synthesizer, 3, 1, 0);
+    let frequency = play(&mut synthesizer);
+    assert!((frequency - untuned / 2.).abs() < 10., "{frequency}");
+
+    // a real-time single note change retunes the sounding note up a fifth
+    synthesizer.note_on(0, 69, 127);
+    render_frequency(&mut synthesizer);
+    synthesizer.process_sysex(&[0x7F, 0x7F, 0x08, 0x02, 1, 1, 69, 76, 0, 0]);
+    let frequency = render_frequency(&mut synthesizer);
+    assert!((frequency - untuned * 1.498).abs() < 15., "{frequency}");
+}
+
+#[test]
+fn scale_octave_tuning() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+    let untuned = play(&mut synthesizer);
+
+    // 2-byte form, A a semitone flat on channels 1 and 16
+    let mut data = vec![0xF0, 0x7E, 0x7F, 0x08, 0x09, 0x02, 0x00, 0x01];
+    for semitone in 0..12 {
+        data.extend(if semitone == 9 { [0, 0] } else { [0x40, 0] });
+    }
+    data.push(0xF7);
+    synthesizer.process_sysex(&data);
+
+    let frequency = play(&mut synthesizer);
+    assert!((frequency - untuned * 0.944).abs() < 10., "{frequency}");
+
+    // other channels are untouched
+    synthesizer.note_on(1, 69, 127);
+    render_frequency(&mut synthesizer);
+    let frequency = render_frequency(&mut synthesizer);
+    assert!((frequency - untuned).abs() < 10., "{frequency}");
+}
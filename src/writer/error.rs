@@ -0,0 +1,52 @@
+use thiserror::Error;
+
+#[doc = r#"
+A set of errors that can occur while writing a midi representation into bytes
+"#]
+#[derive(Debug, Error)]
+#[error("Writing at Position {position}, {kind}")]
+pub struct WriterError {
+    position: usize,
+    pub(crate) kind: WriterErrorKind,
+}
+
+/// A kind of error that a writer can produce
+#[derive(Debug, Error)]
+pub enum WriterErrorKind {
+    /// A track event was written before any track chunk was started
+    #[error("A track event was written outside of a track chunk")]
+    NotInTrack,
+    /// A value is too large to be represented as a variable length quantity
+    #[error("{0} is too large for a variable length quantity (max 0x0FFFFFFF)")]
+    VarlenOverflow(u32),
+    /// A chunk is too large to have its length represented as a u32
+    #[error("Chunk of length {0} is too large")]
+    ChunkTooLarge(usize),
+    /// A file has more tracks than its header can count
+    #[error("{0} tracks are too many for a file (max 65535)")]
+    TooManyTracks(usize),
+    /// Errors from the underlying [`Write`](std::io::Write) type
+    #[cfg(feature = "std")]
+    #[error("IO: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl WriterError {
+    /// Create a writer error from a position and kind
+    pub const fn new(position: usize, kind: WriterErrorKind) -> Self {
+        Self { position, kind }
+    }
+
+    /// The number of bytes written before the error occurred
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The kind of error produced
+    pub const fn kind(&self) -> &WriterErrorKind {
+        &self.kind
+    }
+}
+
+/// A result type that is either `T` or a [`WriterError`].
+pub type WriteResult<T> = Result<T, WriterError>;
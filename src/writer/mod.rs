@@ -0,0 +1,375 @@
+#![doc = r"
+Contains a push-based MIDI file writer
+
+See the [`Writer`] docs for more information
+"]
+
+mod error;
+mod sink;
+mod state;
+
+pub use error::*;
+pub use sink::*;
+use state::WriterState;
+
+use crate::prelude::*;
+
+#[doc = r#"
+A MIDI file writer.
+
+Consumes [`FileEvent`]s and emits the bytes of a Standard MIDI File.
+This is the counterpart of the [`Reader`].
+
+# Overview
+Events are written in the same order they would be yielded by
+[`Reader::read_event`]:
+
+1. A [`FileEvent::Header`] writes an `MThd` chunk.
+2. A [`FileEvent::Track`] begins an `MTrk` chunk.
+3. [`FileEvent::TrackEvent`]s are written into the most recently started track.
+4. A [`FileEvent::Unknown`] writes an arbitrary chunk.
+5. [`FileEvent::EOF`] (or [`Writer::finish`]) closes any open track.
+
+Track chunks are prefixed by their length, so the body of a track
+is buffered until the track is closed. The length held by the
+[`TrackChunkHeader`] passed in is ignored in favor of the actual length.
+
+If a track does not end with [`MetaMessage::EndOfTrack`], one is appended
+when the track is closed.
+
+# Running Status
+Running status is disabled by default. When enabled via
+[`Writer::set_running_status`], a channel voice message will omit its
+status byte if it is identical to the status of the previous channel
+voice message in the same track. Meta and system exclusive events
+cancel running status, per the SMF spec.
+
+# Example
+```rust
+use midix::prelude::*;
+
+let mut writer = Writer::new(Vec::new());
+
+writer
+    .write_event(&FileEvent::Header(RawHeaderChunk::new(
+        RawFormat::single_multichannel(),
+        Timing::new_ticks_per_quarter_note(96),
+    )))
+    .unwrap();
+writer.write_event(&FileEvent::Track(TrackChunkHeader::new(0))).unwrap();
+writer
+    .write_event(&FileEvent::TrackEvent(TrackEvent::new(
+        0,
+        ChannelVoiceMessage::new(
+            Channel::One,
+            VoiceEvent::note_on(key!(C, 4), Velocity::MAX),
+        )
+        .into(),
+    )))
+    .unwrap();
+
+let bytes = writer.finish().unwrap();
+
+let mut reader = Reader::from_byte_slice(&bytes);
+let Ok(FileEvent::Header(header)) = reader.read_event() else {
+    panic!("Expected a header event");
+};
+assert_eq!(header.timing().ticks_per_quarter_note(), Some(96));
+
+let Ok(FileEvent::Track(track)) = reader.read_event() else {
+    panic!("Expected a track event");
+};
+// note on (4 bytes) + end of track (4 bytes)
+assert_eq!(track.len(), 8);
+```
+"#]
+#[derive(Clone)]
+pub struct Writer<W> {
+    writer: W,
+    pub(crate) state: WriterState,
+}
+
+impl<W> Writer<W> {
+    /// Create a new writer.
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            state: WriterState::default(),
+        }
+    }
+
+    /// Consume self to grab the inner writer.
+    ///
+    /// Note that a track that has not been closed will not have been written.
+    /// See [`Writer::finish`].
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Gets a reference to the underlying writer
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Gets a mutable reference to the underlying writer
+    pub const fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// The number of bytes written so far, including the
+    /// buffered body of an open track.
+    pub fn buffer_position(&self) -> usize {
+        self.state.offset() + self.state.track().map(|t| t.body.len() + 8).unwrap_or(0)
+    }
+
+    /// Returns true if channel voice messages will be compressed using running status
+    pub const fn running_status(&self) -> bool {
+        self.state.running_status()
+    }
+
+    /// Enable or disable running status compression for channel voice messages.
+    pub const fn set_running_status(&mut self, running_status: bool) {
+        self.state.set_running_status(running_status);
+    }
+
+    /// Builder-style variant of [`Writer::set_running_status`]
+    #[must_use]
+    pub const fn with_running_status(mut self, running_status: bool) -> Self {
+        self.state.set_running_status(running_status);
+        self
+    }
+
+    /// Returns true if a track chunk has been started and not yet closed
+    pub const fn in_track(&self) -> bool {
+        self.state.track().is_some()
+    }
+
+    pub(crate) fn error(&self, kind: WriterErrorKind) -> WriterError {
+        WriterError::new(self.buffer_position(), kind)
+    }
+}
+
+//internal implementations
+impl<W: MidiSink> Writer<W> {
+    /// Writes bytes into the open track, or the sink if no track is open.
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) -> WriteResult<()> {
+        if let Some(track) = self.state.track_mut() {
+            track.body.extend_from_slice(bytes);
+            return Ok(());
+        }
+        let position = self.buffer_position();
+        self.writer
+            .write_bytes(bytes)
+            .map_err(|e| WriterError::new(position, e.kind))?;
+        self.state.increment_offset(bytes.len());
+        Ok(())
+    }
+
+    pub(crate) fn write_varlen(&mut self, value: u32) -> WriteResult<()> {
+        let mut buf = [0; 4];
+        let len = encode_varlen(value, &mut buf)
+            .ok_or_else(|| self.error(WriterErrorKind::VarlenOverflow(value)))?;
+        self.write_bytes(&buf[..len])
+    }
+
+    /// Writes the status of a channel voice message, respecting running status.
+    pub(crate) fn write_status(&mut self, status: u8) -> WriteResult<()> {
+        let running_status = self.state.running_status();
+        if let Some(track) = self.state.track_mut() {
+            let skip = running_status && track.prev_status == Some(status);
+            track.prev_status = Some(status);
+            if skip {
+                return Ok(());
+            }
+        }
+        self.write_bytes(&[status])
+    }
+
+    /// Meta and sysex events cancel running status
+    pub(crate) fn clear_running_status(&mut self) {
+        if let Some(track) = self.state.track_mut() {
+            track.prev_status = None;
+        }
+    }
+
+    pub(crate) fn set_track_ended(&mut self, ended: bool) {
+        if let Some(track) = self.state.track_mut() {
+            track.ended = ended;
+        }
+    }
+
+    /// Begin a new track chunk. Closes the currently open track, if any.
+    ///
+    /// # Errors
+    /// If the previous track could not be written
+    pub fn start_track(&mut self) -> WriteResult<()> {
+        self.close_track()?;
+        self.state.start_track();
+        Ok(())
+    }
+
+    /// Closes the currently open track chunk, writing it to the
+    /// underlying sink. Appends an `EndOfTrack` if the track doesn't have one.
+    ///
+    /// Does nothing if no track is open.
+    ///
+    /// # Errors
+    /// If the track could not be written
+    pub fn close_track(&mut self) -> WriteResult<()> {
+        let Some(mut track) = self.state.take_track() else {
+            return Ok(());
+        };
+        if !track.ended {
+            track.body.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+        }
+        let Ok(length) = u32::try_from(track.body.len()) else {
+            return Err(self.error(WriterErrorKind::ChunkTooLarge(track.body.len())));
+        };
+        self.write_bytes(b"MTrk")?;
+        self.write_bytes(&length.to_be_bytes())?;
+        self.write_bytes(&track.body)
+    }
+}
+
+/// Encodes a value as a variable length quantity, returning the
+/// number of bytes used. Returns `None` if the value is greater than `0x0FFFFFFF`.
+pub(crate) fn encode_varlen(value: u32, buf: &mut [u8; 4]) -> Option<usize> {
+    if value > 0x0FFF_FFFF {
+        return None;
+    }
+    let mut len = 1;
+    while len < 4 && value >> (7 * len) != 0 {
+        len += 1;
+    }
+    for (i, byte) in buf[..len].iter_mut().enumerate() {
+        let shift = 7 * (len - 1 - i);
+        let continuation = if i == len - 1 { 0 } else { 0x80 };
+        *byte = ((value >> shift) & 0x7F) as u8 | continuation;
+    }
+    Some(len)
+}
+
+impl<W: MidiSink> Writer<W> {
+    /// Write an event into the buffer
+    ///
+    /// # Errors
+    ///
+    /// If a [`FileEvent::TrackEvent`] is written before a track has been started,
+    /// or if the underlying sink fails.
+    pub fn write_event(&mut self, event: &FileEvent<'_>) -> WriteResult<()> {
+        match event {
+            FileEvent::Header(header) => {
+                self.close_track()?;
+                header.write(self)
+            }
+            FileEvent::Track(_) => self.start_track(),
+            FileEvent::Unknown(chunk) => {
+                self.close_track()?;
+                chunk.write(self)
+            }
+            FileEvent::TrackEvent(event) => event.write(self),
+            FileEvent::EOF => self.close_track(),
+        }
+    }
+
+    /// Write a full [`ParsedMidiFile`] into the buffer.
+    ///
+    /// # Errors
+    /// If the underlying sink fails, or the file has more than 65535 tracks
+    pub fn write_file(&mut self, file: &ParsedMidiFile<'_>) -> WriteResult<()> {
+        let tracks = file.tracks();
+        let Ok(num_tracks) = u16::try_from(tracks.len()) else {
+            return Err(self.error(WriterErrorKind::TooManyTracks(tracks.len())));
+        };
+        let format = match file.format_type() {
+            FormatType::SingleMultiChannel => RawFormat::single_multichannel(),
+            FormatType::Simultaneous => RawFormat::simultaneous(num_tracks),
+            FormatType::SequentiallyIndependent => RawFormat::sequentially_independent(num_tracks),
+        };
+        self.write_event(&FileEvent::Header(RawHeaderChunk::new(
+            format,
+            *file.header().timing(),
+        )))?;
+
+        for track in tracks {
+            self.start_track()?;
+            for event in track.track_events() {
+                event.write(self)?;
+            }
+        }
+        self.close_track()
+    }
+
+    /// Closes any open track and returns the inner writer.
+    ///
+    /// # Errors
+    /// If the open track could not be written
+    pub fn finish(mut self) -> WriteResult<W> {
+        self.close_track()?;
+        Ok(self.writer)
+    }
+}
+
+#[test]
+fn encode_known_varlens() {
+    use pretty_assertions::assert_eq;
+    let known: [(u32, &[u8]); 8] = [
+        (0x00, &[0x00]),
+        (0x40, &[0x40]),
+        (0x7F, &[0x7F]),
+        (0x80, &[0x81, 0x00]),
+        (0x2000, &[0xC0, 0x00]),
+        (0x3FFF, &[0xFF, 0x7F]),
+        (0x10_0000, &[0xC0, 0x80, 0x00]),
+        (0x0FFF_FFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+    ];
+    for (value, expected) in known {
+        let mut buf = [0; 4];
+        let len = encode_varlen(value, &mut buf).unwrap();
+        assert_eq!(&buf[..len], expected);
+
+        let mut reader = Reader::from_byte_slice(expected);
+        assert_eq!(crate::reader::decode_varlen(&mut reader).unwrap(), value);
+    }
+    assert!(encode_varlen(0x1000_0000, &mut [0; 4]).is_none());
+}
+
+#[test]
+fn running_status_is_cancelled_by_meta() {
+    let note = |key| {
+        TrackEvent::new(
+            0,
+            ChannelVoiceMessage::new(
+                Channel::One,
+                VoiceEvent::note_on(Key::from_databyte(key).unwrap(), Velocity::MAX),
+            )
+            .into(),
+        )
+    };
+    let mut writer = Writer::new(alloc::vec::Vec::new()).with_running_status(true);
+    writer.start_track().unwrap();
+    writer.write_event(&note(60).into()).unwrap();
+    writer.write_event(&note(61).into()).unwrap();
+    writer
+        .write_event(
+            &TrackEvent::new(
+                0,
+                MetaMessage::Marker(BytesText::new_from_bytes(b"a".as_slice())).into(),
+            )
+            .into(),
+        )
+        .unwrap();
+    writer.write_event(&note(62).into()).unwrap();
+    let bytes = writer.finish().unwrap();
+
+    assert_eq!(
+        &bytes[8..],
+        &[
+            0x00, 0x90, 60, 0x7F, //
+            0x00, 61, 0x7F, // running status
+            0x00, 0xFF, 0x06, 0x01, b'a', //
+            0x00, 0x90, 62, 0x7F, // status is restated after the marker
+            0x00, 0xFF, 0x2F, 0x00
+        ]
+    );
+}
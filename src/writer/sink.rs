@@ -0,0 +1,44 @@
+use alloc::vec::Vec;
+
+use super::WriteResult;
+
+#[doc = r#"
+Any type that bytes of a midi file can be written into.
+
+This is the counterpart of [`MidiSource`](crate::reader::MidiSource).
+"#]
+pub trait MidiSink {
+    /// Write the entirety of `bytes` into the sink.
+    ///
+    /// # Errors
+    /// If the sink could not accept the bytes
+    fn write_bytes(&mut self, bytes: &[u8]) -> WriteResult<()>;
+}
+
+impl MidiSink for Vec<u8> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> WriteResult<()> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl<S: MidiSink + ?Sized> MidiSink for &mut S {
+    fn write_bytes(&mut self, bytes: &[u8]) -> WriteResult<()> {
+        (**self).write_bytes(bytes)
+    }
+}
+
+#[doc = r#"
+Wraps any [`Write`](std::io::Write) type so that it may be used as a [`MidiSink`].
+"#]
+#[cfg(feature = "std")]
+pub struct IoSink<W>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> MidiSink for IoSink<W> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> WriteResult<()> {
+        self.0
+            .write_all(bytes)
+            .map_err(|e| super::WriterError::new(0, e.into()))
+    }
+}
@@ -0,0 +1,60 @@
+use alloc::vec::Vec;
+
+/// The body of a track chunk that has been started, but not yet flushed.
+///
+/// Track chunks are prefixed by their length, so their events
+/// are buffered until the track is closed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TrackState {
+    pub body: Vec<u8>,
+    pub prev_status: Option<u8>,
+    /// True if the last event written was an `EndOfTrack` meta message
+    pub ended: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct WriterState {
+    offset: usize,
+    running_status: bool,
+    track: Option<TrackState>,
+}
+
+impl WriterState {
+    #[must_use]
+    pub const fn default() -> Self {
+        Self {
+            offset: 0,
+            running_status: false,
+            track: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+    pub const fn increment_offset(&mut self, amt: usize) {
+        self.offset += amt;
+    }
+
+    #[must_use]
+    pub const fn running_status(&self) -> bool {
+        self.running_status
+    }
+    pub const fn set_running_status(&mut self, running_status: bool) {
+        self.running_status = running_status;
+    }
+
+    pub const fn track(&self) -> Option<&TrackState> {
+        self.track.as_ref()
+    }
+    pub const fn track_mut(&mut self) -> Option<&mut TrackState> {
+        self.track.as_mut()
+    }
+    pub fn start_track(&mut self) {
+        self.track = Some(TrackState::default());
+    }
+    pub fn take_track(&mut self) -> Option<TrackState> {
+        self.track.take()
+    }
+}
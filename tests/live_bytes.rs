@@ -0,0 +1,150 @@
+use midix::prelude::*;
+use proptest::prelude::*;
+
+fn channel() -> impl Strategy<Value = Channel> {
+    (0..16usize).prop_map(|i| Channel::all()[i])
+}
+
+/// Any valid encoding of a channel voice message
+fn voice_bytes() -> impl Strategy<Value = Vec<u8>> {
+    (0x8u8..=0xE, 0..16u8, 0..0x80u8, 0..0x80u8).prop_map(|(nibble, channel, d1, d2)| {
+        let status = nibble << 4 | channel;
+        match nibble {
+            0xC | 0xD => vec![status, d1],
+            _ => vec![status, d1, d2],
+        }
+    })
+}
+
+/// Any valid encoding of a system common message
+fn common_bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        prop::collection::vec(0..0x80u8, 0..64).prop_map(|data| {
+            let mut bytes = vec![0xF0];
+            bytes.extend(data);
+            bytes.push(0xF7);
+            bytes
+        }),
+        (0..0x80u8, 0..0x80u8).prop_map(|(lsb, msb)| vec![0xF2, lsb, msb]),
+        (0..0x80u8).prop_map(|song| vec![0xF3, song]),
+        prop::sample::select(vec![0xF1u8, 0xF4, 0xF5, 0xF6]).prop_map(|s| vec![s]),
+    ]
+}
+
+/// Any valid encoding of a system realtime message
+fn realtime_bytes() -> impl Strategy<Value = Vec<u8>> {
+    (0xF8..=0xFFu8).prop_map(|s| vec![s])
+}
+
+fn live_event_bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![voice_bytes(), common_bytes(), realtime_bytes()]
+}
+
+fn channel_mode() -> impl Strategy<Value = ChannelModeMessage> {
+    let mode = prop_oneof![
+        Just(ChannelMode::AllSoundOff),
+        Just(ChannelMode::ResetAllControllers),
+        any::<bool>().prop_map(ChannelMode::LocalControl),
+        Just(ChannelMode::AllNotesOff),
+        Just(ChannelMode::OmniModeOff),
+        Just(ChannelMode::OmniModeOn),
+        (0..0x80u8).prop_map(|m| ChannelMode::MonoModeOn(DataByte::new(m).unwrap())),
+        Just(ChannelMode::PolyModeOn),
+    ];
+    (channel(), mode).prop_map(|(channel, mode)| ChannelModeMessage::new(channel, mode))
+}
+
+fn midi_message() -> impl Strategy<Value = MidiMessage<'static>> {
+    prop_oneof![
+        live_event_bytes().prop_map(|b| MidiMessage::from_bytes(&b).unwrap()),
+        channel_mode().prop_map(MidiMessage::from),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn live_event_bytes_round_trip(bytes in live_event_bytes()) {
+        let event = LiveEvent::from_bytes(&bytes).unwrap();
+        prop_assert_eq!(event.byte_len(), bytes.len());
+        prop_assert_eq!(event.to_bytes(), bytes.clone());
+
+        let mut buf = [0; 80];
+        let len = event.write_into(&mut buf).unwrap();
+        prop_assert_eq!(&buf[..len], bytes.as_slice());
+        prop_assert_eq!(LiveEvent::from_bytes(&buf[..len]).unwrap(), event);
+    }
+
+    #[test]
+    fn voice_message_bytes_round_trip(bytes in voice_bytes()) {
+        let message = ChannelVoiceMessage::from_bytes(&bytes).unwrap();
+        prop_assert_eq!(message.to_bytes(), bytes);
+        prop_assert_eq!(ChannelVoiceMessage::from_bytes(&message.to_bytes()).unwrap(), message);
+    }
+
+    #[test]
+    fn common_message_bytes_round_trip(bytes in common_bytes()) {
+        let message = SystemCommonMessage::from_bytes(&bytes).unwrap();
+        prop_assert_eq!(message.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn realtime_message_bytes_round_trip(bytes in realtime_bytes()) {
+        let message = SystemRealTimeMessage::from_bytes(&bytes).unwrap();
+        prop_assert_eq!(message.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn channel_mode_round_trip(message in channel_mode()) {
+        let bytes = message.to_bytes();
+        prop_assert_eq!(ChannelModeMessage::from_bytes(&bytes).unwrap(), message);
+    }
+
+    #[test]
+    fn midi_message_round_trip(message in midi_message()) {
+        let bytes = message.to_bytes();
+        prop_assert_eq!(bytes.len(), message.byte_len());
+        prop_assert_eq!(MidiMessage::from_bytes(&bytes).unwrap(), message);
+    }
+
+    #[test]
+    fn extend_appends(first in live_event_bytes(), second in live_event_bytes()) {
+        let first = LiveEvent::from_bytes(&first).unwrap();
+        let second = LiveEvent::from_bytes(&second).unwrap();
+
+        let mut buf = Vec::new();
+        first.extend_into(&mut buf);
+        second.extend_into(&mut buf);
+
+        let mut expected = first.to_bytes();
+        expected.extend(second.to_bytes());
+        prop_assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn small_buffers_are_untouched(message in midi_message(), shortfall in 1..4usize) {
+        let required = message.byte_len();
+        let mut buf = vec![0xAA; required.saturating_sub(shortfall)];
+        prop_assert_eq!(
+            message.write_into(&mut buf),
+            Err(EncodeError::BufferTooSmall { required, available: buf.len() })
+        );
+        prop_assert!(buf.iter().all(|b| *b == 0xAA));
+    }
+}
+
+#[test]
+fn voice_message_into_midi_message_bytes() {
+    let message = ChannelVoiceMessage::new(
+        Channel::Ten,
+        VoiceEvent::program_change(Program::new(5).unwrap()),
+    );
+    let bytes = MidiMessageBytes::from(message);
+    assert_eq!(bytes.to_bytes(), [0xC9, 5]);
+
+    let message = ChannelVoiceMessage::new(
+        Channel::One,
+        VoiceEvent::note_on(key!(C, 4), Velocity::new(100).unwrap()),
+    );
+    let bytes = MidiMessageBytes::from(message);
+    assert_eq!(bytes.to_bytes(), [0x90, 60, 100]);
+}
@@ -0,0 +1,138 @@
+use midix::{prelude::*, reader::ReaderErrorKind};
+use pretty_assertions::assert_eq;
+
+const NOTE_ON: [u8; 4] = [0x00, 0x90, 0x3C, 0x40];
+const END_OF_TRACK: [u8; 4] = [0x00, 0xFF, 0x2F, 0x00];
+
+/// A format 1 file with the given track bodies and declared lengths
+fn file(tracks: &[(&[u8], u32)]) -> Vec<u8> {
+    let mut bytes = vec![
+        0x4D, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x02, 0x00, 0x60,
+    ];
+    for (body, length) in tracks {
+        bytes.extend(b"MTrk");
+        bytes.extend(length.to_be_bytes());
+        bytes.extend(*body);
+    }
+    bytes
+}
+
+fn track_error(error: &midix::reader::ReaderError) -> Option<&TrackError> {
+    match error.kind() {
+        ReaderErrorKind::ParseError(ParseError::TrackEvent(e)) => Some(e),
+        _ => None,
+    }
+}
+
+fn lenient(bytes: &[u8]) -> (ParsedMidiFile<'_>, Vec<midix::reader::ReaderError>) {
+    ParsedMidiFile::parse_with_options(bytes, ReaderOptions::lenient()).unwrap()
+}
+
+#[test]
+fn recover_pi_damaged() {
+    let bytes = include_bytes!("../test-asset/PiDamaged.mid");
+    assert!(ParsedMidiFile::parse(bytes.as_slice()).is_err());
+
+    let (file, diagnostics) = lenient(bytes);
+    assert_eq!(file.tracks().len(), 3);
+
+    // the third track claims to be longer than the file
+    let ReaderErrorKind::ParseError(ParseError::Chunk(ChunkError::LengthPastEndOfFile(length))) =
+        diagnostics[0].kind()
+    else {
+        panic!("Expected the track length to be clamped, got {diagnostics:?}");
+    };
+    assert_eq!(*length, 0x0012_3853);
+    assert_eq!(diagnostics[0].position(), 0x47);
+
+    let mut reader = Reader::from_byte_slice(bytes).with_options(ReaderOptions::lenient());
+    while reader.read_event().unwrap() != FileEvent::EOF {}
+    assert_eq!(reader.diagnostics().len(), diagnostics.len());
+}
+
+#[test]
+fn track_length_too_long() {
+    let mut first = NOTE_ON.to_vec();
+    first.extend(END_OF_TRACK);
+    let bytes = file(&[(&first, 12), (&END_OF_TRACK, 4)]);
+    assert!(ParsedMidiFile::parse(bytes.as_slice()).is_err());
+
+    let (file, diagnostics) = lenient(&bytes);
+    assert_eq!(file.tracks().len(), 2);
+    assert_eq!(file.tracks()[0].events().len(), 1);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        track_error(&diagnostics[0]),
+        Some(TrackError::LengthMismatch)
+    ));
+    // the second track's header
+    assert_eq!(diagnostics[0].position(), 30);
+}
+
+#[test]
+fn track_length_too_short() {
+    let mut first = NOTE_ON.to_vec();
+    first.extend([0x00, 0x80, 0x3C, 0x40]);
+    first.extend(END_OF_TRACK);
+    let bytes = file(&[(&first, 4), (&END_OF_TRACK, 4)]);
+
+    let (file, diagnostics) = lenient(&bytes);
+    assert_eq!(file.tracks().len(), 2);
+    assert_eq!(file.tracks()[0].events().len(), 2);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        track_error(&diagnostics[0]),
+        Some(TrackError::LengthMismatch)
+    ));
+    assert_eq!(diagnostics[0].position(), 26);
+}
+
+#[test]
+fn invalid_events_are_skipped() {
+    // a data byte without a running status
+    let mut body = vec![0x00, 0x40];
+    body.extend(NOTE_ON);
+    body.extend(END_OF_TRACK);
+    let bytes = file(&[(&body, body.len() as u32)]);
+    assert!(ParsedMidiFile::parse(bytes.as_slice()).is_err());
+
+    let (file, diagnostics) = lenient(&bytes);
+    assert_eq!(file.tracks()[0].events().len(), 1);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        track_error(&diagnostics[0]),
+        Some(TrackError::InvalidEvent(0x40))
+    ));
+}
+
+#[test]
+fn missing_end_of_track() {
+    let bytes = file(&[(&NOTE_ON, 4), (&NOTE_ON, 4)]);
+
+    let (file, diagnostics) = lenient(&bytes);
+    assert_eq!(file.tracks().len(), 2);
+    assert_eq!(diagnostics.len(), 2);
+    assert!(
+        diagnostics
+            .iter()
+            .all(|d| matches!(track_error(d), Some(TrackError::MissingEndOfTrack)))
+    );
+    assert_eq!(diagnostics[1].position(), bytes.len());
+}
+
+#[test]
+fn strict_by_default() {
+    let bytes = include_bytes!("../test-asset/PiDamaged.mid");
+    let mut reader = Reader::from_byte_slice(bytes);
+    assert_eq!(reader.options(), ReaderOptions::strict());
+    let error = loop {
+        if let Err(e) = reader.read_event() {
+            break e;
+        }
+    };
+    assert!(error.is_out_of_bounds());
+    assert!(reader.diagnostics().is_empty());
+}
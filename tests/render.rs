@@ -0,0 +1,100 @@
+#![cfg(feature = "render")]
+
+#[path = "../src/synthesizer/tests/common/mod.rs"]
+mod common;
+
+use core::time::Duration;
+
+use midix::prelude::*;
+use midix::render::{render, render_stems};
+use midix::synth::BusLayout;
+
+/// A4 from tick 1 (10 ms in) to tick 50, ending at tick 100 (one second).
+/// Each tick is 10 ms. The sysex events are sent at tick 0.
+fn file_bytes(sysex: &[&[u8]]) -> Vec<u8> {
+    let mut track = vec![0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40];
+    for data in sysex {
+        track.extend([0x00, 0xF0, data.len() as u8 + 1]);
+        track.extend(*data);
+        track.push(0xF7);
+    }
+    track.extend([0x01, 0x90, 0x45, 0x7F]);
+    track.extend([0x31, 0x80, 0x45, 0x40]);
+    track.extend([0x32, 0xFF, 0x2F, 0x00]);
+
+    let mut bytes = vec![
+        0x4D, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x64,
+    ];
+    bytes.extend(b"MTrk");
+    bytes.extend((track.len() as u32).to_be_bytes());
+    bytes.extend(track);
+    bytes
+}
+
+fn settings() -> RenderSettings {
+    let mut settings = RenderSettings::new(44_100);
+    settings.release_tail = Duration::from_millis(500);
+    settings.enable_reverb_and_chorus = false;
+    settings
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().map(|s| s.abs()).fold(0., f32::max)
+}
+
+#[test]
+fn notes_start_on_their_sample() {
+    let bytes = file_bytes(&[]);
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let sound_font = common::sine_sound_font(&[], &[]);
+
+    let audio = render(&file, &sound_font, &settings()).unwrap();
+    assert_eq!(audio.sample_rate(), 44_100);
+    assert_eq!(audio.frames(), 44_100 + 22_050);
+    assert_eq!(audio.duration(), Duration::from_millis(1_500));
+
+    // 10 ms is 441 samples, which isn't on a block boundary. The sine starts at zero.
+    let onset = audio
+        .samples()
+        .chunks(2)
+        .position(|frame| frame[0].abs() > 1e-6)
+        .unwrap();
+    assert!((441..=442).contains(&onset), "{onset}");
+
+    // released well before the end of the tail
+    assert!(peak(&audio.samples()[2 * 66_000..]) < 1e-4);
+    assert_eq!(audio.to_pcm16().len(), audio.samples().len());
+}
+
+#[test]
+fn sysex_is_sent() {
+    let sound_font = common::sine_sound_font(&[], &[]);
+    let level = |sysex: &[&[u8]]| {
+        let bytes = file_bytes(sysex);
+        let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+        let audio = render(&file, &sound_font, &settings()).unwrap();
+        peak(&audio.samples()[..2 * 22_050])
+    };
+
+    let full = level(&[]);
+    // universal real-time master volume, at half
+    let half = level(&[&[0x7F, 0x7F, 0x04, 0x01, 0x00, 0x40]]);
+    assert!((half / full - 0.5).abs() < 0.01, "{}", half / full);
+}
+
+#[test]
+fn stems() {
+    let bytes = file_bytes(&[]);
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let sound_font = common::sine_sound_font(&[], &[]);
+
+    let audio = render(&file, &sound_font, &settings()).unwrap();
+    let layout = BusLayout::groups(&[&[0]]).unwrap();
+    let stems = render_stems(&file, &sound_font, &settings(), layout).unwrap();
+
+    // channel 0, the other channels, and the shared effects
+    assert_eq!(stems.len(), 3);
+    assert_eq!(stems[0], audio);
+    assert_eq!(peak(stems[1].samples()), 0.);
+    assert_eq!(peak(stems[2].samples()), 0.);
+}
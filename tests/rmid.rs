@@ -0,0 +1,111 @@
+use midix::prelude::*;
+use pretty_assertions::assert_eq;
+
+/// Appends a RIFF chunk, padded to an even length
+fn chunk(bytes: &mut Vec<u8>, id: &[u8; 4], body: &[u8]) {
+    bytes.extend(id);
+    bytes.extend((body.len() as u32).to_le_bytes());
+    bytes.extend(body);
+    if body.len() % 2 == 1 {
+        bytes.push(0);
+    }
+}
+
+fn rmid(smf: &[u8], extra: &[u8]) -> Vec<u8> {
+    let mut body = b"RMID".to_vec();
+    chunk(&mut body, b"data", smf);
+    body.extend(extra);
+
+    let mut bytes = Vec::new();
+    chunk(&mut bytes, b"RIFF", &body);
+    bytes
+}
+
+#[test]
+fn parse_beethoven() {
+    let bytes = include_bytes!("../test-asset/Beethoven.rmi");
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    assert_eq!(file.tracks().len(), 15);
+
+    let rmid = file.rmid().unwrap();
+    assert_eq!(
+        rmid.artist().unwrap().as_str().unwrap(),
+        "Ludwig von Beethoven"
+    );
+    assert!(rmid.info_text(b"ISBJ").is_some());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stream_beethoven() {
+    let bytes = include_bytes!("../test-asset/Beethoven.rmi");
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+
+    let streamed = ParsedMidiFile::parse_reader(bytes.as_slice()).unwrap();
+    assert_eq!(streamed.tracks(), file.tracks());
+    assert_eq!(streamed.rmid(), file.rmid());
+}
+
+#[test]
+fn plain_files_have_no_container() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    assert!(file.rmid().is_none());
+}
+
+#[test]
+fn embedded_sound_banks() {
+    let smf = include_bytes!("simple_midi/simple.mid");
+
+    let mut sf2 = Vec::new();
+    chunk(&mut sf2, b"RIFF", b"sfbkLIST\x00\x00\x00\x00");
+    let mut dls = Vec::new();
+    chunk(
+        &mut dls,
+        b"RIFF",
+        b"DLS colh\x04\x00\x00\x00\x00\x00\x00\x00",
+    );
+    let mut info = b"INFO".to_vec();
+    chunk(&mut info, b"INAM", b"Simple\0");
+
+    let mut extra = sf2.clone();
+    extra.extend(&dls);
+    chunk(&mut extra, b"LIST", &info);
+    let bytes = rmid(smf, &extra);
+
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let rmid = file.rmid().unwrap();
+    assert_eq!(rmid.name().unwrap().as_str().unwrap(), "Simple");
+
+    let banks = rmid.sound_banks();
+    assert_eq!(banks.len(), 2);
+    assert_eq!(banks[0].format(), SoundBankFormat::Sf2);
+    assert_eq!(banks[0].data(), sf2.as_slice());
+    assert_eq!(banks[1].format(), SoundBankFormat::Dls);
+    assert_eq!(banks[1].data(), dls.as_slice());
+
+    let plain = ParsedMidiFile::parse(smf.as_slice()).unwrap();
+    assert_eq!(file.tracks(), plain.tracks());
+}
+
+#[test]
+fn error_offsets_include_the_container() {
+    let smf = include_bytes!("../test-asset/Clementi.mid");
+    let truncated = &smf[..smf.len() / 2];
+
+    let expected = ParsedMidiFile::parse(truncated).err().unwrap();
+    let error = ParsedMidiFile::parse(rmid(truncated, &[])).err().unwrap();
+    // "RIFF" + length + "RMID" + "data" + length
+    assert_eq!(error.position(), expected.position() + 20);
+}
+
+#[test]
+fn other_riff_forms_are_rejected() {
+    let mut bytes = Vec::new();
+    chunk(&mut bytes, b"RIFF", b"WAVEfmt \x00\x00\x00\x00");
+    let error = ParsedMidiFile::parse(bytes).err().unwrap();
+    assert!(matches!(
+        error.kind(),
+        midix::reader::ReaderErrorKind::ParseError(ParseError::Riff(RiffError::NotRmid(_)))
+    ));
+}
@@ -0,0 +1,235 @@
+use midix::prelude::*;
+use pretty_assertions::assert_eq;
+
+/// One note every second (100 ticks), ending at four seconds
+fn file_bytes() -> Vec<u8> {
+    let mut track = vec![0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40];
+    track.extend([0x00, 0x90, 0x3C, 0x40]);
+    track.extend([0x64, 0x90, 0x3E, 0x40]);
+    track.extend([0x64, 0x90, 0x40, 0x40]);
+    track.extend([0x64, 0x90, 0x41, 0x40]);
+    track.extend([0x64, 0xFF, 0x2F, 0x00]);
+
+    let mut bytes = vec![
+        0x4D, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x64,
+    ];
+    bytes.extend(b"MTrk");
+    bytes.extend((track.len() as u32).to_be_bytes());
+    bytes.extend(track);
+    bytes
+}
+
+fn timestamps(sequencer: &mut Sequencer<'_>, start: u64, end: u64) -> Vec<u64> {
+    sequencer
+        .events_between(start, end)
+        .map(|e| e.timestamp)
+        .collect()
+}
+
+#[test]
+fn plays_every_event_once() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+    sequencer.play();
+
+    let mut played = Vec::new();
+    let mut time = 0;
+    while sequencer.is_playing() {
+        for event in sequencer.events_between(time, time + 10_000) {
+            assert!(event.timestamp >= time && event.timestamp <= time + 10_000);
+            played.push(event.event.clone());
+        }
+        time += 10_000;
+    }
+
+    assert_eq!(played.as_slice(), sequencer.events());
+    assert!(sequencer.is_finished());
+    assert_eq!(sequencer.position_micros(), sequencer.length_micros());
+}
+
+#[test]
+fn timestamps_follow_the_window() {
+    let bytes = file_bytes();
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+    assert_eq!(sequencer.length_micros(), 4_000_000);
+
+    // paused by default
+    assert!(timestamps(&mut sequencer, 0, 10_000_000).is_empty());
+
+    sequencer.play();
+    assert_eq!(
+        timestamps(&mut sequencer, 500_000, 2_500_000),
+        [500_000, 1_500_000]
+    );
+    assert_eq!(sequencer.position_micros(), 2_000_000);
+
+    sequencer.pause();
+    assert!(timestamps(&mut sequencer, 2_500_000, 3_500_000).is_empty());
+    assert_eq!(sequencer.position_micros(), 2_000_000);
+
+    sequencer.play();
+    assert_eq!(timestamps(&mut sequencer, 0, 10_000_000), [0, 1_000_000]);
+    assert!(!sequencer.is_playing());
+}
+
+#[test]
+fn tempo_scale() {
+    let bytes = file_bytes();
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+    sequencer.set_tempo_scale(2.);
+    sequencer.play();
+
+    assert_eq!(timestamps(&mut sequencer, 0, 1_000_000), [0, 500_000]);
+    assert_eq!(sequencer.position_micros(), 2_000_000);
+}
+
+#[test]
+fn seek() {
+    let bytes = file_bytes();
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+
+    sequencer.seek(PlaybackPosition::Ticks(150));
+    assert_eq!(sequencer.position_micros(), 1_500_000);
+
+    // 4/4, so each beat is 100 ticks
+    sequencer.seek(BarBeat { bar: 0, beat: 2. });
+    assert_eq!(sequencer.position_ticks(), 200);
+
+    sequencer.seek(PlaybackPosition::Micros(u64::MAX));
+    assert!(sequencer.is_finished());
+
+    // restarts from the beginning once finished
+    sequencer.play();
+    assert_eq!(timestamps(&mut sequencer, 0, 1), [0]);
+}
+
+#[test]
+fn loop_region() {
+    let bytes = file_bytes();
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+    sequencer.set_loop(PlaybackPosition::Ticks(100), PlaybackPosition::Ticks(200));
+    assert_eq!(sequencer.loop_region(), Some(1_000_000..2_000_000));
+    sequencer.play();
+
+    // the event at the loop end plays before jumping back
+    assert_eq!(
+        timestamps(&mut sequencer, 0, 3_500_000),
+        [0, 1_000_000, 2_000_000, 2_000_000, 3_000_000, 3_000_000]
+    );
+    assert_eq!(sequencer.position_micros(), 1_500_000);
+
+    sequencer.clear_loop();
+    assert_eq!(
+        timestamps(&mut sequencer, 0, 10_000_000),
+        [500_000, 1_500_000]
+    );
+
+    // empty regions are ignored
+    sequencer.set_loop(PlaybackPosition::Ticks(200), PlaybackPosition::Ticks(200));
+    assert_eq!(sequencer.loop_region(), None);
+}
+
+/// Changes the program, then the volume, then the pitch bend of the first channel, a second apart
+fn state_file_bytes() -> Vec<u8> {
+    let mut track = vec![0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40];
+    track.extend([0x00, 0xC0, 0x05]);
+    track.extend([0x64, 0xB0, 0x07, 0x50]);
+    track.extend([0x64, 0xE0, 0x00, 0x50]);
+    track.extend([0x64, 0x90, 0x3C, 0x40]);
+    track.extend([0x64, 0xFF, 0x2F, 0x00]);
+
+    let mut bytes = vec![
+        0x4D, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x64,
+    ];
+    bytes.extend(b"MTrk");
+    bytes.extend((track.len() as u32).to_be_bytes());
+    bytes.extend(track);
+    bytes
+}
+
+fn voice_events(sequencer: &mut Sequencer<'_>, start: u64, end: u64) -> Vec<(u64, VoiceEvent)> {
+    sequencer
+        .events_between(start, end)
+        .filter_map(|e| match e.event.event() {
+            LiveEvent::ChannelVoice(message) => Some((e.timestamp, *message.event())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn volume(value: u8) -> VoiceEvent {
+    VoiceEvent::ControlChange(Controller::VolumeCoarse(DataByte::new(value).unwrap()))
+}
+
+#[test]
+fn seeking_chases_channel_state() {
+    let bytes = state_file_bytes();
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+
+    sequencer.seek(PlaybackPosition::Ticks(250));
+    sequencer.play();
+    assert_eq!(
+        voice_events(&mut sequencer, 0, 1_000_000),
+        [
+            (0, VoiceEvent::program_change(Program::new(5).unwrap())),
+            (0, volume(0x50)),
+            (
+                0,
+                VoiceEvent::pitch_bend(PitchBend::new(0x00, 0x50).unwrap())
+            ),
+            (
+                500_000,
+                VoiceEvent::note_on(key!(C, 4), Velocity::new(0x40).unwrap())
+            ),
+        ]
+    );
+
+    // seeking back only restores what changed
+    sequencer.seek(PlaybackPosition::Ticks(150));
+    assert_eq!(
+        voice_events(&mut sequencer, 0, 1),
+        [(
+            0,
+            VoiceEvent::pitch_bend(PitchBend::new(0x00, 0x40).unwrap())
+        )]
+    );
+    assert_eq!(
+        ChannelState::at_tick(&file, 150)
+            .channel(Channel::One)
+            .program(),
+        Program::new(5).unwrap()
+    );
+}
+
+#[test]
+fn looping_chases_channel_state() {
+    let bytes = state_file_bytes();
+    let file = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let mut sequencer = Sequencer::new(&file);
+    sequencer.set_loop(PlaybackPosition::Ticks(50), PlaybackPosition::Ticks(250));
+    sequencer.play();
+
+    assert_eq!(
+        voice_events(&mut sequencer, 0, 2_600_000),
+        [
+            (0, VoiceEvent::program_change(Program::new(5).unwrap())),
+            (1_000_000, volume(0x50)),
+            (
+                2_000_000,
+                VoiceEvent::pitch_bend(PitchBend::new(0x00, 0x50).unwrap())
+            ),
+            // back to the state at the start of the loop
+            (2_500_000, volume(100)),
+            (
+                2_500_000,
+                VoiceEvent::pitch_bend(PitchBend::new(0x00, 0x40).unwrap())
+            ),
+        ]
+    );
+}
@@ -20,6 +20,27 @@ fn test_parse() {
     note_off(events.next().unwrap(), 384, Two, Note::G, 4);
     note_off(events.next().unwrap(), 384, One, Note::E, 5);
 }
+#[test]
+fn test_parse_meta_events() {
+    let parsed = ParsedMidiFile::parse(include_bytes!("./simple.mid")).unwrap();
+    let track = parsed.tracks()[0];
+
+    let metas = track.meta_events();
+    assert_eq!(metas.len(), 3);
+    assert_eq!(
+        metas[0].event(),
+        &MetaMessage::TimeSignature(TimeSignature::new_from_bytes([4, 2, 24, 8]))
+    );
+    assert_eq!(
+        metas[1].event(),
+        &MetaMessage::Tempo(Tempo::new_from_bytes(&[0x07, 0xA1, 0x20]))
+    );
+    assert_eq!(metas[2].accumulated_ticks(), 384);
+    assert_eq!(metas[2].event(), &MetaMessage::EndOfTrack);
+
+    assert_eq!(track.info().time_signature.num(), 4);
+}
+
 fn note_on(
     e: &Ticked<LiveEvent<'_>>,
     accumulated_ticks: u32,
@@ -0,0 +1,167 @@
+#![cfg(feature = "std")]
+
+use std::{
+    io::{self, Read},
+    pin::{Pin, pin},
+    task::{Context, Poll, Waker},
+};
+
+use midix::{
+    prelude::*,
+    reader::{ReadError, ReaderErrorKind},
+};
+use pretty_assertions::assert_eq;
+
+/// Yields at most `step` bytes per read, then fails with `error_at`, if any
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    step: usize,
+    error_at: Option<usize>,
+    position: usize,
+}
+
+impl<'a> Trickle<'a> {
+    fn new(bytes: &'a [u8], step: usize) -> Self {
+        Self {
+            bytes,
+            step,
+            error_at: None,
+            position: 0,
+        }
+    }
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.error_at.is_some_and(|at| self.position >= at) {
+            return Err(io::Error::other("stream failed"));
+        }
+        let remaining = &self.bytes[self.position..];
+        let len = remaining.len().min(buf.len()).min(self.step);
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+impl futures_io::AsyncRead for Trickle<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().read(buf))
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn slice_events(bytes: &[u8]) -> Vec<FileEvent<'_>> {
+    let mut reader = Reader::from_byte_slice(bytes);
+    let mut events = Vec::new();
+    loop {
+        let event = reader.read_event().unwrap();
+        if event == FileEvent::EOF {
+            break events;
+        }
+        events.push(event);
+    }
+}
+
+#[test]
+fn stream_events_match_slice_events() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    let expected = slice_events(bytes);
+
+    for step in [1, 7, 4096, usize::MAX] {
+        let mut reader = StreamReader::new(Trickle::new(bytes, step));
+        let mut events = Vec::new();
+        loop {
+            let event = reader.read_event().unwrap();
+            if event == FileEvent::EOF {
+                break;
+            }
+            events.push(event);
+        }
+        assert_eq!(events, expected);
+        assert_eq!(reader.buffer_position(), bytes.len());
+    }
+}
+
+#[test]
+fn async_stream_events_match_slice_events() {
+    let bytes = include_bytes!("../test-asset/CrabRave.mid");
+    let expected = slice_events(bytes);
+
+    let mut reader = AsyncStreamReader::new(Trickle::new(bytes, 13));
+    let mut events = Vec::new();
+    loop {
+        let event = block_on(reader.read_event()).unwrap();
+        if event == FileEvent::EOF {
+            break;
+        }
+        events.push(event);
+    }
+    assert_eq!(events, expected);
+}
+
+#[test]
+fn parse_from_streams() {
+    let bytes = include_bytes!("../test-asset/RiverFlowsInYou.mid");
+    let parsed = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+
+    let streamed = ParsedMidiFile::parse_reader(Trickle::new(bytes, 100)).unwrap();
+    assert_eq!(streamed.tracks(), parsed.tracks());
+
+    let streamed = block_on(ParsedMidiFile::parse_async_reader(Trickle::new(bytes, 100))).unwrap();
+    assert_eq!(streamed.tracks(), parsed.tracks());
+}
+
+#[test]
+fn truncated_stream_reports_same_offset() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    let truncated = &bytes[..bytes.len() / 2];
+
+    let mut reader = Reader::from_byte_slice(truncated);
+    let expected = loop {
+        if let Err(e) = reader.read_event() {
+            break e;
+        }
+    };
+
+    let mut reader = StreamReader::new(Trickle::new(truncated, 5));
+    let error = loop {
+        if let Err(e) = reader.read_event() {
+            break e;
+        }
+    };
+    assert!(error.is_out_of_bounds());
+    assert_eq!(error.position(), expected.position());
+}
+
+#[test]
+fn stream_errors_are_reported() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    let mut stream = Trickle::new(bytes, 64);
+    stream.error_at = Some(256);
+
+    let mut reader = StreamReader::new(stream);
+    let error = loop {
+        if let Err(e) = reader.read_event() {
+            break e;
+        }
+    };
+    assert!(matches!(
+        error.kind(),
+        ReaderErrorKind::ReadError(ReadError::Io(_))
+    ));
+    assert!(error.position() <= 256);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "synth")]
+
+#[path = "../src/synthesizer/tests/common/mod.rs"]
+mod common;
+
+use common::*;
+use midix::{MidiTarget, prelude::*, synth::Synthesizer};
+
+fn note_on(channel: Channel, key: Key) -> ChannelVoiceMessage {
+    channel.send_event(VoiceEvent::note_on(key, Velocity::MAX))
+}
+
+#[test]
+fn voice_messages() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+
+    synthesizer.handle_voice(&note_on(Channel::One, key!(A, 4)));
+    render_frequency(&mut synthesizer);
+    let frequency = render_frequency(&mut synthesizer);
+    assert!((frequency - 440.).abs() < 15., "{frequency}");
+
+    // a full bend up is two semitones
+    synthesizer
+        .handle_voice(&Channel::One.send_event(VoiceEvent::PitchBend(PitchBend::from_u16(0x3FFF))));
+    render_frequency(&mut synthesizer);
+    let frequency = render_frequency(&mut synthesizer);
+    assert!((frequency - 493.9).abs() < 15., "{frequency}");
+
+    synthesizer
+        .handle_voice(&Channel::One.send_event(VoiceEvent::note_off(key!(A, 4), Velocity::MAX)));
+    render_level(&mut synthesizer);
+    assert_eq!(synthesizer.get_active_voice_count(), 0);
+}
+
+#[test]
+fn mode_and_system_messages() {
+    let mut synthesizer = synthesizer(sine_sound_font(&[], &[]));
+
+    synthesizer.handle_voice(&note_on(Channel::Two, key!(C, 4)));
+    synthesizer.handle_voice(&note_on(Channel::Three, key!(C, 4)));
+    synthesizer.handle_mode(&ChannelModeMessage::new(
+        Channel::Two,
+        ChannelMode::AllSoundOff,
+    ));
+    assert_eq!(synthesizer.get_active_voice_count(), 1);
+
+    synthesizer
+        .handle_event(MidiMessage::SysRealTime(SystemRealTimeMessage::Reset))
+        .unwrap();
+    assert_eq!(synthesizer.get_active_voice_count(), 0);
+
+    // universal real-time master volume, at half
+    let level = |synthesizer: &mut Synthesizer| {
+        synthesizer.handle_live(&LiveEvent::ChannelVoice(note_on(Channel::One, key!(A, 4))));
+        let level = render_level(synthesizer);
+        synthesizer.reset_all_controllers();
+        synthesizer.note_off_all(true);
+        level
+    };
+    let full = level(&mut synthesizer);
+    synthesizer
+        .handle_event(MidiMessage::SysExclusive(SystemExclusiveMessage::new(
+            [0x7F, 0x7F, 0x04, 0x01, 0x00, 0x40].as_slice(),
+        )))
+        .unwrap();
+    let half = level(&mut synthesizer);
+    assert!((half / full - 0.5).abs() < 0.01, "{}", half / full);
+}
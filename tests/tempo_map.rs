@@ -0,0 +1,34 @@
+use midix::prelude::*;
+
+#[test]
+fn tempo_map_follows_tempo_changes() {
+    let bytes = include_bytes!("../test-asset/RiverFlowsInYou.mid");
+    let parsed = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    let map = parsed.tempo_map();
+
+    let tempo_changes: Vec<_> = parsed
+        .tracks()
+        .iter()
+        .flat_map(|track| track.meta_events())
+        .filter_map(|meta| match meta.event() {
+            MetaMessage::Tempo(tempo) => Some((meta.accumulated_ticks(), *tempo)),
+            _ => None,
+        })
+        .collect();
+
+    for (tick, tempo) in &tempo_changes {
+        assert_eq!(map.tempo_at(*tick), *tempo);
+    }
+
+    // every tempo segment should elapse at its own rate
+    let tpqn = parsed.header().timing().ticks_per_quarter_note().unwrap() as u64;
+    for window in tempo_changes.windows(2) {
+        let ((start, tempo), (end, _)) = (window[0], window[1]);
+        if start == end {
+            continue;
+        }
+        let elapsed = map.ticks_to_micros(end) - map.ticks_to_micros(start);
+        let expected = (end - start) as u64 * tempo.micros_per_quarter_note() as u64 / tpqn;
+        assert!(elapsed.abs_diff(expected) <= 1);
+    }
+}
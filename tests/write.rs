@@ -0,0 +1,137 @@
+use midix::{prelude::*, writer::WriterErrorKind};
+use pretty_assertions::assert_eq;
+
+/// Reads every event of a file and writes it back out
+fn rewrite(bytes: &[u8], running_status: bool) -> Vec<u8> {
+    let mut reader = Reader::from_byte_slice(bytes);
+    let mut writer = Writer::new(Vec::new()).with_running_status(running_status);
+
+    loop {
+        let event = reader.read_event().unwrap();
+        writer.write_event(&event).unwrap();
+        if event == FileEvent::EOF {
+            break;
+        }
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn round_trip_clementi() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    assert_eq!(rewrite(bytes, true), bytes);
+}
+
+#[test]
+fn round_trip_clementi_rewritten() {
+    let bytes = include_bytes!("../test-asset/ClementiRewritten.mid");
+    assert_eq!(rewrite(bytes, true), bytes);
+}
+
+#[test]
+fn round_trip_crab_rave() {
+    let bytes = include_bytes!("../test-asset/CrabRave.mid");
+    assert_eq!(rewrite(bytes, false), bytes);
+}
+
+#[test]
+fn round_trip_simple() {
+    let bytes = include_bytes!("simple_midi/simple.mid");
+    assert_eq!(rewrite(bytes, true), bytes);
+}
+
+#[test]
+fn written_file_parses_identically() {
+    let bytes = include_bytes!("../test-asset/Clementi.mid");
+    let parsed = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+
+    let written = parsed.to_bytes().unwrap();
+    let reparsed = ParsedMidiFile::parse(written.as_slice()).unwrap();
+
+    assert_eq!(reparsed.format_type(), parsed.format_type());
+    assert_eq!(reparsed.header().timing(), parsed.header().timing());
+    assert_eq!(reparsed.tracks(), parsed.tracks());
+}
+
+#[test]
+fn parsed_file_round_trip() {
+    for bytes in [
+        include_bytes!("../test-asset/Clementi.mid").as_slice(),
+        include_bytes!("simple_midi/simple.mid").as_slice(),
+        include_bytes!("../test-asset/Sandstorm.mid").as_slice(),
+    ] {
+        let parsed = ParsedMidiFile::parse(bytes).unwrap();
+        assert_eq!(parsed.to_bytes().unwrap(), bytes);
+    }
+}
+
+#[test]
+fn mid_song_tempo_changes_are_kept() {
+    let bytes = include_bytes!("../test-asset/RiverFlowsInYou.mid");
+    let parsed = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+
+    let tempo_changes = parsed
+        .tracks()
+        .iter()
+        .flat_map(|track| track.meta_events())
+        .filter(|meta| matches!(meta.event(), MetaMessage::Tempo(_)))
+        .count();
+    assert_eq!(tempo_changes, 14);
+
+    let reparsed = ParsedMidiFile::parse(parsed.to_bytes().unwrap()).unwrap();
+    assert_eq!(reparsed.tracks(), parsed.tracks());
+}
+
+#[test]
+fn end_of_track_is_appended() {
+    let mut writer = Writer::new(Vec::new());
+    writer
+        .write_event(
+            &RawHeaderChunk::new(
+                RawFormat::single_multichannel(),
+                Timing::new_ticks_per_quarter_note(96),
+            )
+            .into(),
+        )
+        .unwrap();
+    writer.start_track().unwrap();
+    let bytes = writer.finish().unwrap();
+
+    let mut reader = Reader::from_byte_slice(&bytes);
+    let _header = reader.read_event().unwrap();
+    let _track = reader.read_event().unwrap();
+    let Ok(FileEvent::TrackEvent(event)) = reader.read_event() else {
+        panic!("Expected an end of track event");
+    };
+    assert_eq!(event.event(), &TrackMessage::Meta(MetaMessage::EndOfTrack));
+    assert_eq!(reader.read_event().unwrap(), FileEvent::EOF);
+}
+
+#[test]
+fn track_event_outside_of_track() {
+    let mut writer = Writer::new(Vec::new());
+    let err = writer
+        .write_event(&FileEvent::TrackEvent(TrackEvent::new(
+            0,
+            MetaMessage::EndOfTrack.into(),
+        )))
+        .unwrap_err();
+    assert!(matches!(err.kind(), WriterErrorKind::NotInTrack));
+}
+
+#[test]
+fn too_many_tracks() {
+    // the header only counts one track, but every track chunk is read
+    let mut bytes = b"MThd\0\0\0\x06\0\x01\0\x01\0\x60".to_vec();
+    for _ in 0..=u16::MAX as usize {
+        bytes.extend_from_slice(b"MTrk\0\0\0\x04\0\xFF\x2F\0");
+    }
+    let parsed = ParsedMidiFile::parse(bytes.as_slice()).unwrap();
+    assert_eq!(parsed.tracks().len(), 65536);
+
+    let error = parsed.to_bytes().unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        WriterErrorKind::TooManyTracks(65536)
+    ));
+}